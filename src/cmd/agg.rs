@@ -34,6 +34,10 @@ You can rename the output columns using the 'as' syntax:
 
     $ xan agg 'sum(n) as sum, max(replies_count) as \"Max Replies\"' file.csv
 
+You can restrict the rows seen by an aggregation using an 'if' clause:
+
+    $ xan agg 'count() if status eq \"ok\" as ok_count, mean(n) if n > 0' file.csv
+
 This command can also be used to aggregate a selection of columns per row,
 instead of aggregating the whole file, when using the --cols flag. In which
 case the expression will take a single variable named `cell`, representing
@@ -4,7 +4,7 @@ use std::path::PathBuf;
 
 use glob::glob;
 
-static COMMANDS: [&str; 58] = [
+static COMMANDS: [&str; 66] = [
     "agg",
     "behead",
     "bins",
@@ -34,18 +34,24 @@ static COMMANDS: [&str; 58] = [
     "implode",
     "index",
     "input",
+    "interval-join",
     "join",
     "map",
     "matrix",
+    "melt",
     "merge",
     "network",
+    "outliers",
     "parallel",
     "partition",
+    "pivot",
     "plot",
+    "profile",
     "progress",
     "range",
     "rename",
     "regex-join",
+    "regress",
     "reverse",
     "sample",
     "scrape",
@@ -56,6 +62,7 @@ static COMMANDS: [&str; 58] = [
     "sort",
     "split",
     "stats",
+    "test",
     "tokenize",
     "top",
     "transform",
@@ -63,10 +70,11 @@ static COMMANDS: [&str; 58] = [
     "url-join",
     "view",
     "vocab",
+    "window",
 ];
 
 static HELP_SUBCOMMANDS: [&str; 4] = ["cheatsheet", "functions", "aggs", "scraping"];
-static MATRIX_SUBCOMMANDS: [&str; 1] = ["corr"];
+static MATRIX_SUBCOMMANDS: [&str; 6] = ["corr", "cov", "dist", "contingency", "pca", "mds"];
 static NETWORK_SUBCOMMANDS: [&str; 2] = ["edgelist", "bipartite"];
 static SCRAPE_SUBCOMMANDS: [&str; 5] = ["title", "canonical", "links", "urls", "images"];
 static TOKENIZE_SUBCOMMANDS: [&str; 3] = ["words", "sentences", "paragraphs"];
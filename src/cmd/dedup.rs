@@ -1,10 +1,14 @@
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 
+use bytesize::ByteSize;
+use colored::Colorize;
 use dlv_list::{Index, VecList};
 use indexmap::{map::Entry as IndexMapEntry, IndexMap};
 use transient_btree_index::{BtreeConfig, BtreeIndex};
 
+use crate::collections::{IncrementalId, ScalableBloomFilter, UnionFind};
 use crate::config::{Config, Delimiter};
+use crate::minhash::{estimate_jaccard, LshIndex, MinHash, Shingling};
 use crate::moonblade::ChooseProgram;
 use crate::select::SelectColumns;
 use crate::util;
@@ -17,6 +21,13 @@ the distinct number of row identities.
 If your file is already sorted on the deduplication selection, use the -S/--sorted flag
 to run in O(1) memory instead.
 
+If your file is too large and you can tolerate a tiny error rate, use the -a/--approx
+flag to rely on a scalable Bloom filter instead, running in bounded memory. Some
+rows will then be wrongly considered as duplicates and dropped, at a rate that
+will remain under the one given to --error-rate, unless the memory budget given
+to -m/--memory-limit is exhausted. The estimated error rate will be reported at the
+end.
+
 Note that, by default, this command will write the first row having
 a specific identity to the output, unless you use -l/--keep-last.
 
@@ -36,6 +47,21 @@ Notice how the column names of the currently kept row were prefixed
 with \"current_\", while the ones of the new row were prefixed
 with \"new_\" instead.
 
+Near-duplicates, e.g. almost identical tweets or articles, can also be found
+using the --jaccard flag, given a similarity threshold between 0 and 1. The text
+of the selected columns will be split into overlapping shingles (sequences of
+characters or words), whose MinHash signatures are indexed using
+locality-sensitive hashing to efficiently find rows whose estimated Jaccard
+similarity is at least the given threshold:
+
+    $ xan dedup -s text --jaccard 0.8 tweets.csv > deduped.csv
+
+In this mode, a row will be dropped if it is similar to any previous row, even
+a dropped one. Use the --cluster-column flag to keep every row instead and
+add a column containing the id of its group of near-duplicates, computed
+transitively. Note that this mode needs to keep a signature in memory for each
+row, and must buffer the whole file when using --cluster-column.
+
 Note that if you need to aggregate cell values from duplicated
 rows, you should probably check out `xan groupby` instead, that can
 be used for this very purpose, especially with the --keep flag.
@@ -45,24 +71,42 @@ Usage:
     xan dedup --help
 
 dedup options:
-    --check             Verify whether the selection has any duplicates, i.e. whether
-                        the selected columns satisfy a uniqueness constraint.
-    -s, --select <arg>  Select a subset of columns to on which to deduplicate.
-                        See 'xan select --help' for the format details.
-    -S, --sorted        Use if you know your file is already sorted on the deduplication
-                        selection to avoid needing to keep a hashmap of values
-                        in memory.
-    -l, --keep-last     Keep the last row having a specific identity, rather than
-                        the first one. Note that it will cost more memory and that
-                        no rows will be flushed before the whole file has been read
-                        if -S/--sorted is not used.
-    -e, --external      Use an external btree index to keep the index on disk and avoid
-                        overflowing RAM. Does not work with -l/--keep-last and --keep-duplicates.
-    --keep-duplicates   Emit only the duplicated rows.
-    --choose <expr>     Evaluate an expression that must return whether to
-                        keep a newly seen row or not. Column name in the given
-                        expression will be prefixed with \"current_\" for the
-                        currently kept row and \"new_\" for the new row to consider.
+    --check                  Verify whether the selection has any duplicates, i.e. whether
+                             the selected columns satisfy a uniqueness constraint.
+    -s, --select <arg>       Select a subset of columns to on which to deduplicate.
+                             See 'xan select --help' for the format details.
+    -S, --sorted             Use if you know your file is already sorted on the deduplication
+                             selection to avoid needing to keep a hashmap of values
+                             in memory.
+    -l, --keep-last          Keep the last row having a specific identity, rather than
+                             the first one. Note that it will cost more memory and that
+                             no rows will be flushed before the whole file has been read
+                             if -S/--sorted is not used.
+    -e, --external           Use an external btree index to keep the index on disk and avoid
+                             overflowing RAM. Does not work with -l/--keep-last and --keep-duplicates.
+    -a, --approx             Use a scalable Bloom filter to deduplicate in bounded
+                             memory, at the cost of wrongly dropping some rows.
+    --error-rate <p>         Target false positive rate for -a/--approx.
+                             [default: 0.001]
+    -m, --memory-limit <mb>  Maximum memory, in megabytes, allowed for the
+                             Bloom filter of -a/--approx.
+    --keep-duplicates        Emit only the duplicated rows.
+    --choose <expr>          Evaluate an expression that must return whether to
+                             keep a newly seen row or not. Column name in the given
+                             expression will be prefixed with \"current_\" for the
+                             currently kept row and \"new_\" for the new row to consider.
+
+near-duplicates options:
+    --jaccard <threshold>    Drop near-duplicate rows, whose estimated Jaccard similarity
+                             with a previous row is at least the given threshold.
+    --shingle-size <n>       Size of the shingles. [default: 5]
+    --word-shingles          Use shingles made of words instead of characters. A
+                             shingle size of 2 or 3 is usually better suited then.
+    --permutations <n>       Number of permutations used to compute MinHash signatures.
+                             More permutations means more precise estimations, but
+                             more memory and computations. [default: 128]
+    --cluster-column <name>  Keep every row and add a column with the given name
+                             containing the id of its group of near-duplicates.
 
 Common options:
     -h, --help               Display this message
@@ -86,6 +130,14 @@ struct Args {
     flag_external: bool,
     flag_keep_duplicates: bool,
     flag_choose: Option<String>,
+    flag_approx: bool,
+    flag_error_rate: f64,
+    flag_memory_limit: Option<f64>,
+    flag_jaccard: Option<f64>,
+    flag_shingle_size: usize,
+    flag_word_shingles: bool,
+    flag_permutations: usize,
+    flag_cluster_column: Option<String>,
 }
 
 type DeduplicationKey = Vec<Vec<u8>>;
@@ -111,6 +163,25 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
+    if args.flag_approx {
+        if args.flag_check
+            || args.flag_sorted
+            || args.flag_external
+            || args.flag_keep_last
+            || args.flag_keep_duplicates
+            || args.flag_choose.is_some()
+            || args.flag_jaccard.is_some()
+        {
+            Err("-a/--approx cannot be used with --check, -S/--sorted, -e/--external, -l/--keep-last, --keep-duplicates, --choose or --jaccard!")?;
+        }
+
+        if args.flag_error_rate <= 0.0 || args.flag_error_rate >= 1.0 {
+            Err("--error-rate must be > 0 and < 1!")?;
+        }
+    } else if args.flag_memory_limit.is_some() {
+        Err("-m/--memory-limit can only be used with -a/--approx!")?;
+    }
+
     let mut mutually_exclusive_count: usize = 0;
 
     if args.flag_keep_last {
@@ -127,6 +198,34 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("must select only one of --choose, -l/--keep-last, --keep-duplicates")?;
     }
 
+    if let Some(threshold) = args.flag_jaccard {
+        if args.flag_check
+            || args.flag_sorted
+            || args.flag_external
+            || args.flag_keep_last
+            || args.flag_keep_duplicates
+            || args.flag_choose.is_some()
+        {
+            Err("--jaccard cannot be used with --check, -S/--sorted, -e/--external, -l/--keep-last, --keep-duplicates or --choose!")?;
+        }
+
+        if threshold <= 0.0 || threshold > 1.0 {
+            Err("--jaccard threshold must be > 0 and <= 1!")?;
+        }
+
+        if args.flag_shingle_size == 0 {
+            Err("--shingle-size must be > 0!")?;
+        }
+
+        if args.flag_permutations == 0 {
+            Err("--permutations must be > 0!")?;
+        }
+
+        return dedup_near_duplicates(args, threshold);
+    } else if args.flag_cluster_column.is_some() {
+        Err("--cluster-column can only be used with --jaccard!")?;
+    }
+
     if args.flag_sorted {
         args.flag_external = false;
     }
@@ -184,6 +283,56 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         return Ok(wtr.flush()?);
     }
 
+    // Approximate
+    if args.flag_approx {
+        let max_bytes = args
+            .flag_memory_limit
+            .map(|limit| (limit * 1024.0 * 1024.0) as usize);
+
+        let mut filter = ScalableBloomFilter::new(args.flag_error_rate, max_bytes);
+
+        let mut record = csv::ByteRecord::new();
+        let mut rows: u64 = 0;
+        let mut dropped: u64 = 0;
+
+        while rdr.read_byte_record(&mut record)? {
+            let key = sel.collect(&record);
+
+            rows += 1;
+
+            if filter.insert(&key) {
+                wtr.write_byte_record(&record)?;
+            } else {
+                dropped += 1;
+            }
+        }
+
+        wtr.flush()?;
+
+        eprintln!(
+            "{} {}",
+            "rows                ".cyan(),
+            util::format_number(rows)
+        );
+        eprintln!(
+            "{} {}",
+            "dropped             ".cyan(),
+            util::format_number(dropped)
+        );
+        eprintln!(
+            "{} {}",
+            "memory              ".cyan(),
+            ByteSize::b(filter.bytes() as u64).display().si()
+        );
+        eprintln!(
+            "{} {:.6}",
+            "estimated error rate".cyan(),
+            filter.estimated_false_positive_rate()
+        );
+
+        return Ok(());
+    }
+
     enum DedupMode {
         KeepFirst,
         KeepLast,
@@ -424,6 +573,100 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(wtr.flush()?)
 }
 
+fn dedup_near_duplicates(args: Args, threshold: f64) -> CliResult<()> {
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconf.reader()?;
+    let mut headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    if !args.flag_no_headers {
+        if let Some(name) = &args.flag_cluster_column {
+            headers.push_field(name.as_bytes());
+        }
+
+        wtr.write_byte_record(&headers)?;
+    }
+
+    let shingling = if args.flag_word_shingles {
+        Shingling::Words(args.flag_shingle_size)
+    } else {
+        Shingling::Chars(args.flag_shingle_size)
+    };
+
+    let minhash = MinHash::new(args.flag_permutations);
+    let mut index = LshIndex::new(args.flag_permutations, threshold);
+    let mut signatures: Vec<Vec<u64>> = Vec::new();
+
+    let mut record = csv::ByteRecord::new();
+
+    let compute_signature = |record: &csv::ByteRecord| {
+        let text = sel
+            .select(record)
+            .map(String::from_utf8_lossy)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        minhash.signature(&shingling.hashes(&text))
+    };
+
+    // Dropping near-duplicates in streaming
+    if args.flag_cluster_column.is_none() {
+        while rdr.read_byte_record(&mut record)? {
+            let signature = compute_signature(&record);
+
+            let is_duplicate = index
+                .candidates(&signature)
+                .into_iter()
+                .any(|i| estimate_jaccard(&signatures[i], &signature) >= threshold);
+
+            index.insert(signatures.len(), &signature);
+            signatures.push(signature);
+
+            if !is_duplicate {
+                wtr.write_byte_record(&record)?;
+            }
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    // Clustering near-duplicates
+    let mut records = Vec::new();
+    let mut union_find = UnionFind::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        let signature = compute_signature(&record);
+        let i = union_find.make_set();
+
+        for j in index.candidates(&signature) {
+            if estimate_jaccard(&signatures[j], &signature) >= threshold {
+                union_find.union(i, j);
+            }
+        }
+
+        index.insert(i, &signature);
+        signatures.push(signature);
+        records.push(record.clone());
+    }
+
+    let mut ids = IncrementalId::new();
+
+    for (i, mut record) in records.into_iter().enumerate() {
+        let id = ids.get(union_find.find(i));
+
+        record.push_field(id.to_string().as_bytes());
+        wtr.write_byte_record(&record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
+
 struct KeepLastSet {
     map: HashMap<DeduplicationKey, Index<csv::ByteRecord>>,
     list: VecList<csv::ByteRecord>,
@@ -1,6 +1,7 @@
 use bstr::ByteSlice;
 use csv::{self, ByteRecord};
 
+use crate::collation::Collation;
 use crate::collections::{ClusteredInsertHashmap, Counter};
 use crate::config::{Config, Delimiter};
 use crate::select::SelectColumns;
@@ -27,6 +28,16 @@ Since this computes an exact frequency table, memory proportional to the
 cardinality of each selected column is required. If you expect this will overflow
 your memory, you can compute an approximate top-k using the -a, --approx flag.
 
+When cleaning identifiers, phone numbers or dates, it can be useful to count the
+shapes of the values instead of the values themselves, using the -S, --shapes flag.
+Shapes are computed by masking uppercase letters as \"A\", other letters as \"a\"
+and digits as \"9\", so that \"2024-01-31\" has shape \"9999-99-99\" and
+\"AB-123\" has shape \"AA-999\", which makes malformed values stand out.
+
+Rows can also be weighted using the -w, --weight flag, in which case the count
+of a value will be the sum of the weights of the rows containing it. Weights can
+be any non-negative number.
+
 To compute custom aggregations per group, beyond just counting, please be sure to
 check the `xan groupby` command instead.
 
@@ -42,6 +53,8 @@ frequency options:
                            provided separator.
     -g, --groupby <cols>   If given, will compute frequency tables per group
                            as defined by the given columns.
+    -w, --weight <column>  Column containing row weights, summed instead of
+                           counting rows.
     -A, --all              Remove the limit.
     -l, --limit <arg>      Limit the frequency table to the N most common
                            items. Use -A, -all or set to 0 to disable the limit.
@@ -54,8 +67,19 @@ frequency options:
     -t, --threshold <arg>  If set, won't return items having a count less than
                            this given threshold. It is combined with -l/--limit.
     -N, --no-extra         Don't include empty cells & remaining counts.
+    -S, --shapes           Count value shapes instead of values.
+    --collapse             Squeeze repeated characters of value shapes, so
+                           that \"Hello world\" has shape \"Aa a\". Only
+                           works with -S, --shapes.
     -p, --parallel         Allow sorting to be done in parallel. This is only
                            useful with -l/--limit set to 0, or with -A, --all.
+    --natural              Order values having the same count naturally, i.e.
+                           treating sequences of digits as numbers, so that
+                           \"file9\" comes before \"file10\".
+    --collate <locale>     Order values having the same count using Unicode
+                           collation rules tailored for the given locale, e.g.
+                           \"fr\" or \"en\". Unknown locales fall back to the
+                           root collation. Can be combined with --natural.
 
 Hidden options:
     --no-limit-we-reach-for-the-sky  Nothing to see here...
@@ -79,13 +103,18 @@ struct Args {
     flag_all: bool,
     flag_limit: usize,
     flag_approx: bool,
-    flag_threshold: Option<u64>,
+    flag_threshold: Option<f64>,
     flag_no_extra: bool,
+    flag_shapes: bool,
+    flag_collapse: bool,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
     flag_parallel: bool,
+    flag_natural: bool,
+    flag_collate: Option<String>,
     flag_groupby: Option<SelectColumns>,
+    flag_weight: Option<SelectColumns>,
     flag_no_limit_we_reach_for_the_sky: bool,
 }
 
@@ -105,12 +134,22 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("-a, --approx cannot work with --limit=0 or -A, --all!")?;
     }
 
+    if args.flag_approx && args.flag_weight.is_some() {
+        Err("-a, --approx cannot work with -w, --weight!")?;
+    }
+
+    if args.flag_collapse && !args.flag_shapes {
+        Err("--collapse can only be used with -S, --shapes!")?;
+    }
+
     if args.flag_no_limit_we_reach_for_the_sky {
         opener::open_browser("https://www.youtube.com/watch?v=7kmEEkECFQw")
             .expect("could not easter egg");
         return Ok(());
     }
 
+    let collation = Collation::new(args.flag_natural, args.flag_collate.as_deref())?;
+
     let approx_k = if args.flag_approx {
         Some(args.flag_limit)
     } else {
@@ -137,6 +176,31 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         sel.subtract(gsel);
     }
 
+    // Nor the weight column
+    let weight_index = match &args.flag_weight {
+        Some(col) => {
+            let index = col.single_selection(&headers, !args.flag_no_headers)?;
+            sel.subtract(&col.selection(&headers, !args.flag_no_headers)?);
+            Some(index)
+        }
+        None => None,
+    };
+
+    let parse_weight = |record: &ByteRecord| -> CliResult<f64> {
+        Ok(match weight_index {
+            Some(index) => util::parse_weight(&record[index])?,
+            None => 1.0,
+        })
+    };
+
+    let new_counter = || -> Counter<ValueKey> {
+        if weight_index.is_some() {
+            Counter::weighted()
+        } else {
+            Counter::new(approx_k)
+        }
+    };
+
     // Nothing was selected
     if sel.is_empty() {
         return Ok(());
@@ -150,19 +214,30 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         sel.select(&headers).map(|h| h.to_vec()).collect()
     };
 
-    fn coerce_cell(cell: &[u8], no_extra: bool) -> Option<&[u8]> {
-        if !no_extra {
-            if cell.is_empty() {
-                Some(b"<empty>")
-            } else {
-                Some(cell)
-            }
-        } else if cell.is_empty() {
-            None
+    let limit = if args.flag_limit == 0 {
+        None
+    } else {
+        Some(args.flag_limit)
+    };
+
+    let total_and_items = |counter: Counter<ValueKey>| match &collation {
+        Some(collation) => {
+            counter.into_total_and_items_by(limit, args.flag_parallel, |a, b| collation.cmp(a, b))
+        }
+        None => counter.into_total_and_items(limit, args.flag_parallel),
+    };
+
+    let coerce_cell = |cell: &[u8]| -> Option<ValueKey> {
+        if cell.is_empty() {
+            return (!args.flag_no_extra).then(|| b"<empty>".to_vec());
+        }
+
+        if args.flag_shapes {
+            Some(util::value_shape(&String::from_utf8_lossy(cell), args.flag_collapse).into_bytes())
         } else {
-            Some(cell)
+            Some(cell.to_vec())
         }
-    }
+    };
 
     if let Some(groupby_sel) = groupby_sel_opt {
         let mut groups_to_fields_to_counter: ClusteredInsertHashmap<
@@ -193,12 +268,13 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 .select(&record)
                 .map(|cell| cell.to_vec())
                 .collect();
+            let weight = parse_weight(&record)?;
 
             let fields_to_counter = groups_to_fields_to_counter.insert_with(group, || {
                 let mut list = Vec::with_capacity(sel.len());
 
                 for _ in 0..sel.len() {
-                    list.push(Counter::new(approx_k));
+                    list.push(new_counter());
                 }
 
                 list
@@ -207,20 +283,20 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             for (i, cell) in sel.select(&record).enumerate() {
                 if let Some(sep) = &args.flag_sep {
                     for sub_cell in cell.split_str(sep) {
-                        let sub_cell = match coerce_cell(sub_cell, args.flag_no_extra) {
+                        let sub_cell = match coerce_cell(sub_cell) {
                             Some(c) => c,
                             None => continue,
                         };
 
-                        fields_to_counter[i].add(sub_cell.to_vec());
+                        fields_to_counter[i].add_with_weight(sub_cell, weight);
                     }
                 } else {
-                    let cell = match coerce_cell(cell, args.flag_no_extra) {
+                    let cell = match coerce_cell(cell) {
                         Some(c) => c,
                         None => continue,
                     };
 
-                    fields_to_counter[i].add(cell.to_vec());
+                    fields_to_counter[i].add_with_weight(cell, weight);
                 }
             }
         }
@@ -230,16 +306,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             for (group, counters) in groups_to_fields_to_counter.iter_mut() {
                 let counter = counters.pop().unwrap();
 
-                let (total, items) = counter.into_total_and_items(
-                    if args.flag_limit == 0 {
-                        None
-                    } else {
-                        Some(args.flag_limit)
-                    },
-                    args.flag_parallel,
-                );
+                let (total, items) = total_and_items(counter);
 
-                let mut emitted: u64 = 0;
+                let mut emitted: f64 = 0.0;
 
                 for (value, count) in items {
                     if let Some(threshold) = args.flag_threshold {
@@ -264,7 +333,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
                 let remaining = total - emitted;
 
-                if !args.flag_no_extra && remaining > 0 {
+                if !args.flag_no_extra && remaining > 0.0 {
                     record.clear();
                     record.push_field(&name);
 
@@ -279,8 +348,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             }
         }
     } else {
-        let mut fields: Vec<Counter<ValueKey>> =
-            (0..sel.len()).map(|_| Counter::new(approx_k)).collect();
+        let mut fields: Vec<Counter<ValueKey>> = (0..sel.len()).map(|_| new_counter()).collect();
 
         let output_headers = {
             let mut r = ByteRecord::new();
@@ -296,39 +364,34 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
         // Aggregating
         while rdr.read_byte_record(&mut record)? {
+            let weight = parse_weight(&record)?;
+
             for (cell, counter) in sel.select(&record).zip(fields.iter_mut()) {
                 if let Some(sep) = &args.flag_sep {
                     for sub_cell in cell.split_str(sep) {
-                        let sub_cell = match coerce_cell(sub_cell, args.flag_no_extra) {
+                        let sub_cell = match coerce_cell(sub_cell) {
                             Some(c) => c,
                             None => continue,
                         };
 
-                        counter.add(sub_cell.to_vec());
+                        counter.add_with_weight(sub_cell, weight);
                     }
                 } else {
-                    let cell = match coerce_cell(cell, args.flag_no_extra) {
+                    let cell = match coerce_cell(cell) {
                         Some(c) => c,
                         None => continue,
                     };
 
-                    counter.add(cell.to_vec());
+                    counter.add_with_weight(cell, weight);
                 }
             }
         }
 
         // Writing output
         for (name, counter) in field_names.into_iter().zip(fields.into_iter()) {
-            let (total, items) = counter.into_total_and_items(
-                if args.flag_limit == 0 {
-                    None
-                } else {
-                    Some(args.flag_limit)
-                },
-                args.flag_parallel,
-            );
+            let (total, items) = total_and_items(counter);
 
-            let mut emitted: u64 = 0;
+            let mut emitted: f64 = 0.0;
 
             for (value, count) in items {
                 if let Some(threshold) = args.flag_threshold {
@@ -348,7 +411,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
             let remaining = total - emitted;
 
-            if !args.flag_no_extra && remaining > 0 {
+            if !args.flag_no_extra && remaining > 0.0 {
                 record.clear();
                 record.push_field(&name);
                 record.push_field(b"<rest>");
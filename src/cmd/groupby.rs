@@ -1,9 +1,17 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::Write;
+use std::path::Path;
+
+use bytesize::MB;
+use ext_sort::{buffer::mem::MemoryLimitedBufferBuilder, ExternalSorter, ExternalSorterBuilder};
+use tempfile::NamedTempFile;
 
 use crate::config::{Config, Delimiter};
-use crate::select::SelectColumns;
-use crate::util;
-use crate::CliResult;
+use crate::select::{SelectColumns, Selection};
+use crate::util::{self, DeepSizedByteRecord};
+use crate::{CliError, CliResult};
 
 use crate::moonblade::AggregationProgram;
 use crate::moonblade::GroupAggregationProgram;
@@ -24,6 +32,284 @@ fn write_group(
     Ok(())
 }
 
+// NOTE: records are expected to be sorted on the group columns, so that only
+// one group needs to be aggregated at once.
+fn aggregate_sorted_records<I>(
+    wtr: &mut csv::Writer<Box<dyn Write + Send>>,
+    sel: &Selection,
+    program: &mut AggregationProgram,
+    error_policy: &MoonbladeErrorPolicy,
+    records: I,
+) -> CliResult<()>
+where
+    I: Iterator<Item = CliResult<(usize, csv::ByteRecord)>>,
+{
+    let mut current: Option<Vec<Vec<u8>>> = None;
+
+    for result in records {
+        let (index, record) = result?;
+        let group = sel.collect(&record);
+
+        match current.as_ref() {
+            None => {
+                current = Some(group);
+            }
+            Some(current_group) => {
+                if current_group != &group {
+                    write_group(
+                        wtr,
+                        current_group,
+                        &error_policy.handle_error(program.finalize(false))?,
+                    )?;
+                    program.clear();
+                    current = Some(group);
+                }
+            }
+        };
+
+        program
+            .run_with_record(index, &record)
+            .or_else(|error| error_policy.handle_row_error(index, error))?;
+    }
+
+    // Flushing final group
+    if let Some(current_group) = current {
+        write_group(
+            wtr,
+            &current_group,
+            &error_policy.handle_error(program.finalize(false))?,
+        )?;
+    }
+
+    Ok(())
+}
+
+// NOTE: when grouping externally, groups are aggregated in memory until their
+// estimated size reaches the memory limit. From then on, rows belonging to
+// groups that are not already in memory are spilled to hash partitions on
+// disk, along with their original index, and each partition is aggregated the
+// same way, recursively. Since every pass admits at least one group, this
+// always terminates, and a group already in memory, e.g. a hot key, is never
+// spilled. Aggregated groups are finally sorted back on the index of their
+// first row so that they are emitted in first-seen order.
+const EXTERNAL_FANOUT: usize = 16;
+
+// NOTE: aggregation states cannot be measured precisely, so a group is
+// estimated to cost the size of its key plus this arbitrary overhead.
+const EXTERNAL_GROUP_OVERHEAD: u64 = 256;
+
+type Partition = (NamedTempFile, csv::Writer<File>);
+
+// NOTE: indices are written as fixed-width big-endian bytes so they don't need
+// to be parsed and so they can be compared bytewise.
+fn encode_index(index: usize) -> [u8; 8] {
+    (index as u64).to_be_bytes()
+}
+
+fn decode_index(cell: &[u8]) -> CliResult<usize> {
+    <[u8; 8]>::try_from(cell)
+        .map(|bytes| u64::from_be_bytes(bytes) as usize)
+        .map_err(|_| CliError::Other("could not decode row index!".to_string()))
+}
+
+struct ExternalAggregation<'a> {
+    sel: &'a Selection,
+    program: &'a GroupAggregationProgram,
+    error_policy: &'a MoonbladeErrorPolicy,
+    tmp_dir: &'a Path,
+    memory_limit: u64,
+}
+
+impl ExternalAggregation<'_> {
+    fn temp_writer(&self) -> CliResult<Partition> {
+        let file = NamedTempFile::new_in(self.tmp_dir)?;
+        let writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(file.reopen()?);
+
+        Ok((file, writer))
+    }
+
+    // NOTE: aggregated groups are written to `results`, prefixed by the
+    // index of their first row.
+    fn aggregate(
+        &self,
+        results: &mut csv::Writer<File>,
+        records: &mut dyn Iterator<Item = CliResult<(usize, csv::ByteRecord)>>,
+        depth: usize,
+    ) -> CliResult<()> {
+        let mut program = self.program.clone();
+        let mut memory: u64 = 0;
+        let mut partitions: Vec<Option<Partition>> = (0..EXTERNAL_FANOUT).map(|_| None).collect();
+
+        for result in records {
+            let (index, mut record) = result?;
+            let group = self.sel.collect(&record);
+
+            if memory >= self.memory_limit && !program.is_empty() && !program.contains(&group) {
+                // NOTE: the depth is used as a seed so that rows spilled from
+                // a partition get split differently than its parent.
+                let mut hasher = DefaultHasher::new();
+                depth.hash(&mut hasher);
+                group.hash(&mut hasher);
+
+                let slot = &mut partitions[(hasher.finish() % EXTERNAL_FANOUT as u64) as usize];
+
+                if slot.is_none() {
+                    *slot = Some(self.temp_writer()?);
+                }
+
+                let (_, writer) = slot.as_mut().unwrap();
+
+                record.push_field(&encode_index(index));
+                writer.write_byte_record(&record)?;
+
+                continue;
+            }
+
+            let len = program.len();
+            let size =
+                group.iter().map(|cell| cell.len() as u64).sum::<u64>() + EXTERNAL_GROUP_OVERHEAD;
+
+            program
+                .run_with_record(group, index, &record)
+                .or_else(|error| self.error_policy.handle_row_error(index, error))?;
+
+            if program.len() > len {
+                memory += size;
+            }
+        }
+
+        for result in program.into_indexed_byte_records(false) {
+            let (index, group, group_record) = self.error_policy.handle_error(result)?;
+
+            let mut record = csv::ByteRecord::new();
+            record.push_field(&encode_index(index));
+            record.extend(&group);
+            record.extend(&group_record);
+
+            results.write_byte_record(&record)?;
+        }
+
+        for (file, mut writer) in partitions.into_iter().flatten() {
+            writer.flush()?;
+
+            let mut rdr = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(file.reopen()?);
+
+            let mut records = rdr.byte_records().map(|result| -> CliResult<_> {
+                let mut record = result?;
+                let index = decode_index(&record[record.len() - 1])?;
+                record.truncate(record.len() - 1);
+
+                Ok((index, record))
+            });
+
+            self.aggregate(results, &mut records, depth + 1)?;
+        }
+
+        Ok(())
+    }
+
+    fn run(
+        &self,
+        wtr: &mut csv::Writer<Box<dyn Write + Send>>,
+        records: &mut dyn Iterator<Item = CliResult<(usize, csv::ByteRecord)>>,
+    ) -> CliResult<()> {
+        let (results_file, mut results) = self.temp_writer()?;
+
+        self.aggregate(&mut results, records, 0)?;
+        results.flush()?;
+
+        let sorter: ExternalSorter<
+            DeepSizedByteRecord,
+            csv::Error,
+            MemoryLimitedBufferBuilder,
+            util::CsvExternalChunk,
+        > = ExternalSorterBuilder::new()
+            .with_tmp_dir(self.tmp_dir)
+            .with_buffer(MemoryLimitedBufferBuilder::new(self.memory_limit))
+            .build()
+            .map_err(|err| CliError::Other(err.to_string()))?;
+
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(results_file.reopen()?);
+
+        let sorted = sorter
+            .sort_by(
+                rdr.byte_records()
+                    .map(|result| result.map(DeepSizedByteRecord)),
+                |r1, r2| r1.as_ref()[0].cmp(&r2.as_ref()[0]),
+            )
+            .map_err(|err| CliError::Other(err.to_string()))?;
+
+        for result in sorted {
+            let record = result?.into_inner();
+
+            wtr.write_record(record.iter().skip(1))?;
+        }
+
+        Ok(())
+    }
+}
+
+// NOTE: a grouping set is represented as a mask over the group columns,
+// indicating which ones are kept.
+type GroupingSet = Vec<bool>;
+
+fn rollup_grouping_sets(n: usize) -> Vec<GroupingSet> {
+    (0..=n)
+        .rev()
+        .map(|k| (0..n).map(|i| i < k).collect())
+        .collect()
+}
+
+fn cube_grouping_sets(n: usize) -> Vec<GroupingSet> {
+    let mut sets = (0..(1_usize << n))
+        .rev()
+        .map(|bits| {
+            (0..n)
+                .map(|i| bits & (1 << (n - i - 1)) != 0)
+                .collect::<GroupingSet>()
+        })
+        .collect::<Vec<_>>();
+
+    sets.sort_by_key(|set| std::cmp::Reverse(set.iter().filter(|kept| **kept).count()));
+
+    sets
+}
+
+fn parse_grouping_sets(
+    spec: &str,
+    sel: &Selection,
+    headers: &csv::ByteRecord,
+    use_names: bool,
+) -> CliResult<Vec<GroupingSet>> {
+    spec.split(';')
+        .map(|part| {
+            let part = part.trim();
+
+            if part.is_empty() {
+                return Ok(vec![false; sel.len()]);
+            }
+
+            let set_sel = SelectColumns::parse(part)?.selection(headers, use_names)?;
+
+            if let Some(i) = set_sel.iter().find(|i| !sel.contains(**i)) {
+                return Err(format!(
+                    "--grouping-sets: column \"{}\" is not one of the group columns!",
+                    String::from_utf8_lossy(&headers[*i])
+                ))?;
+            }
+
+            Ok(sel.iter().map(|i| set_sel.contains(*i)).collect())
+        })
+        .collect()
+}
+
 static USAGE: &str = "
 Group a CSV file by values contained in a column selection then aggregate data per
 group using a custom aggregation expression.
@@ -47,10 +333,29 @@ You can rename the output columns using the 'as' syntax:
 
     $ xan groupby user_name 'sum(n) as sum, max(replies_count) as \"Max Replies\"' file.csv
 
+You can restrict the rows seen by an aggregation using an 'if' clause:
+
+    $ xan groupby user_name 'count() if status eq \"ok\" as ok_count, mean(n) if n > 0' file.csv
+
 You can group on multiple columns (read `xan select -h` for more information about column selection):
 
     $ xan groupby name,surname 'sum(count)' file.csv
 
+You can also compute subtotals & grand totals in a single pass using
+the --rollup, --cube or --grouping-sets flags, in which case columns that
+were rolled up will be filled with an empty value, or the one given to
+the --total-marker flag:
+
+    $ xan groupby country,year 'sum(count)' --rollup --total-marker TOTAL file.csv
+
+country,year,sum(count)
+France,2020,12
+France,2021,5
+Italy,2020,6
+France,TOTAL,17
+Italy,TOTAL,6
+TOTAL,TOTAL,23
+
 For a quick review of the capabilities of the expression language,
 check out the `xan help cheatsheet` command.
 
@@ -64,17 +369,43 @@ Usage:
     xan groupby --help
 
 groupby options:
-    --keep <cols>           Keep this selection of columns, in addition to
-                            the ones representing groups, in the output. Only
-                            values from the first seen row per group will be kept.
-    -S, --sorted            Use this flag to indicate that the file is already sorted on the
-                            group columns, in which case the command will be able to considerably
-                            optimize memory usage.
-    -e, --errors <policy>   What to do with evaluation errors. One of:
-                              - \"panic\": exit on first error
-                              - \"ignore\": ignore row altogether
-                              - \"log\": print error to stderr
-                            [default: panic].
+    --keep <cols>             Keep this selection of columns, in addition to
+                              the ones representing groups, in the output. Only
+                              values from the first seen row per group will be kept.
+    -S, --sorted              Use this flag to indicate that the file is already sorted on the
+                              group columns, in which case the command will be able to considerably
+                              optimize memory usage.
+    --external                Aggregate groups in memory until --memory-limit is
+                              reached, then spill the rows of any new group to
+                              temporary files on disk, hash-partitioned by group,
+                              to be aggregated later, for when there are too many
+                              groups to fit in memory. Groups will be emitted in
+                              the order they were first seen. Note that memory
+                              usage is only estimated from the number and size of
+                              groups, so that aggregations keeping all their values,
+                              such as \"values\" or \"median\", may exceed the limit.
+    --tmp-dir <arg>           Directory where external partitions will be
+                              written. Will default to the grouped file's directory
+                              or \"./\" if grouping an incoming stream.
+    -m, --memory-limit <arg>  Estimated memory, in megabytes, that groups can use
+                              before rows start being spilled to disk when
+                              using --external. [default: 512].
+    -e, --errors <policy>     What to do with evaluation errors. One of:
+                                - \"panic\": exit on first error
+                                - \"ignore\": ignore row altogether
+                                - \"log\": print error to stderr
+                              [default: panic].
+    --rollup                  Also aggregate data for every prefix of the group
+                              columns, e.g. \"country,year\", then \"country\"
+                              and finally the whole file.
+    --cube                    Also aggregate data for every combination of
+                              the group columns, including the whole file.
+    --grouping-sets <sets>    Aggregate data for each of the given sets of group
+                              columns, separated by \";\". An empty set means
+                              aggregating the whole file, e.g. \"country,year;country;\".
+    --total-marker <value>    Value used to fill the group columns that were
+                              rolled up by --rollup, --cube or --grouping-sets.
+                              [default: ]
 
 Common options:
     -h, --help               Display this message
@@ -95,7 +426,14 @@ struct Args {
     flag_delimiter: Option<Delimiter>,
     flag_keep: Option<SelectColumns>,
     flag_sorted: bool,
+    flag_external: bool,
+    flag_tmp_dir: Option<String>,
+    flag_memory_limit: u64,
     flag_errors: String,
+    flag_rollup: bool,
+    flag_cube: bool,
+    flag_grouping_sets: Option<String>,
+    flag_total_marker: String,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -114,6 +452,31 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let sel = rconf.selection(headers)?;
 
+    let grouping_sets = match (
+        args.flag_rollup,
+        args.flag_cube,
+        args.flag_grouping_sets.as_ref(),
+    ) {
+        (false, false, None) => None,
+        (true, false, None) => Some(rollup_grouping_sets(sel.len())),
+        (false, true, None) => Some(cube_grouping_sets(sel.len())),
+        (false, false, Some(spec)) => Some(parse_grouping_sets(
+            spec,
+            &sel,
+            headers,
+            !args.flag_no_headers,
+        )?),
+        _ => Err("--rollup, --cube & --grouping-sets are mutually exclusive!")?,
+    };
+
+    if grouping_sets.is_some() && (args.flag_sorted || args.flag_external) {
+        Err("-S, --sorted & --external cannot be used with --rollup, --cube or --grouping-sets!")?;
+    }
+
+    if args.flag_sorted && args.flag_external {
+        Err("-S, --sorted & --external are mutually exclusive!")?;
+    }
+
     // Lol, what a hack...
     if let Some(selection) = args.flag_keep.take() {
         let mut keep_sel = selection.selection(headers, !args.flag_no_headers)?;
@@ -142,7 +505,6 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     if args.flag_sorted {
         let mut program = AggregationProgram::parse(&args.arg_expression, headers)?;
-        let mut current: Option<Vec<Vec<u8>>> = None;
 
         write_group(
             &mut wtr,
@@ -150,42 +512,112 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             &program.headers().collect(),
         )?;
 
+        aggregate_sorted_records(
+            &mut wtr,
+            &sel,
+            &mut program,
+            &error_policy,
+            rdr.byte_records()
+                .enumerate()
+                .map(|(index, result)| Ok((index, result?))),
+        )?;
+    } else if args.flag_external {
+        let program = GroupAggregationProgram::parse(&args.arg_expression, headers)?;
+
+        write_group(
+            &mut wtr,
+            &sel.collect(headers),
+            &program.headers().collect(),
+        )?;
+
+        let tmp_dir = args.flag_tmp_dir.unwrap_or(match &args.arg_input {
+            None => "./".to_string(),
+            Some(p) => Path::new(p).parent().unwrap().to_str().unwrap().to_string(),
+        });
+
+        let external = ExternalAggregation {
+            sel: &sel,
+            program: &program,
+            error_policy: &error_policy,
+            tmp_dir: Path::new(&tmp_dir),
+            memory_limit: args.flag_memory_limit * MB,
+        };
+
+        external.run(
+            &mut wtr,
+            &mut rdr
+                .into_byte_records()
+                .enumerate()
+                .map(|(index, result)| Ok((index, result?))),
+        )?;
+    } else if let Some(grouping_sets) = grouping_sets {
+        // NOTE: each grouping set gets its own program, so that its groups
+        // remain clustered, but rows are only evaluated once for all of them.
+        let mut programs = vec![
+            GroupAggregationProgram::parse(&args.arg_expression, headers)?;
+            grouping_sets.len()
+        ];
+
+        write_group(
+            &mut wtr,
+            &sel.collect(headers),
+            &programs[0].headers().collect(),
+        )?;
+
         let mut index: usize = 0;
 
         while rdr.read_byte_record(&mut record)? {
-            let group = sel.collect(&record);
-
-            match current.as_ref() {
-                None => {
-                    current = Some(group);
-                }
-                Some(current_group) => {
-                    if current_group != &group {
-                        write_group(
-                            &mut wtr,
-                            current_group,
-                            &error_policy.handle_error(program.finalize(false))?,
-                        )?;
-                        program.clear();
-                        current = Some(group);
+            let values = sel.collect(&record);
+
+            programs[0]
+                .evaluate(index, &record)
+                .and_then(|row| {
+                    let mut result = Ok(());
+
+                    // NOTE: every grouping set must see the row, but its
+                    // error will only be reported once
+                    for (program, set) in programs.iter_mut().zip(grouping_sets.iter()) {
+                        let group = values
+                            .iter()
+                            .zip(set.iter())
+                            .filter(|(_, kept)| **kept)
+                            .map(|(value, _)| value.clone())
+                            .collect();
+
+                        result = result.and(program.run_with_evaluated(
+                            group,
+                            index,
+                            row.clone(),
+                            &record,
+                        ));
                     }
-                }
-            };
 
-            program
-                .run_with_record(index, &record)
+                    result
+                })
                 .or_else(|error| error_policy.handle_row_error(index, error))?;
 
             index += 1;
         }
 
-        // Flushing final group
-        if let Some(current_group) = current {
-            write_group(
-                &mut wtr,
-                &current_group,
-                &error_policy.handle_error(program.finalize(false))?,
-            )?;
+        for (program, set) in programs.into_iter().zip(grouping_sets.iter()) {
+            for result in program.into_byte_records(false) {
+                let (group, group_record) = error_policy.handle_error(result)?;
+
+                let mut values = group.into_iter();
+
+                let full_group: Vec<Vec<u8>> = set
+                    .iter()
+                    .map(|kept| {
+                        if *kept {
+                            values.next().unwrap()
+                        } else {
+                            args.flag_total_marker.as_bytes().to_vec()
+                        }
+                    })
+                    .collect();
+
+                write_group(&mut wtr, &full_group, &group_record)?;
+            }
         }
     } else {
         let mut program = GroupAggregationProgram::parse(&args.arg_expression, headers)?;
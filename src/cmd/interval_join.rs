@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::ops::Sub;
+
+use jiff::SignedDuration;
+
+use crate::cmd::join::{parse_datetime_key, parse_number_key};
+use crate::collections::IntervalTree;
+use crate::config::{Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+fn prefix_header(headers: &csv::ByteRecord, prefix: &String) -> csv::ByteRecord {
+    let mut prefixed_headers = csv::ByteRecord::new();
+
+    for column in headers.iter() {
+        prefixed_headers.push_field(&[prefix.as_bytes(), column].concat());
+    }
+
+    prefixed_headers
+}
+
+static USAGE: &str = "
+Join a CSV file containing intervals, e.g. genomic ranges or periods of
+time, with another CSV file containing intervals or points, by matching rows
+whose intervals overlap.
+
+Intervals are given by two columns, representing their start and end, which
+are both inclusive. Points are given by a single column. Since bounds are
+inclusive, intervals merely touching each other, e.g. [1, 5] and [5, 8], are
+considered to overlap, by an amount of 0.
+
+The default behavior of this command is to be an 'inner join', which
+means only matched rows will be written in the output. Use the --left
+flag if you want to perform a 'left join' and keep every row of the searched
+file in the output.
+
+The file containing the intervals will always be completely read in memory,
+and indexed using an interval tree, while the second one will always be
+streamed.
+
+Bounds are parsed as numbers by default, but can also be parsed as datetimes
+using the --dates flag.
+
+For instance, to find the genes overlapping some variants, given as points,
+on the same chromosome:
+
+    $ xan interval-join --by chrom pos variants.csv start,end genes.csv
+
+Or to find which periods of time contain some events:
+
+    $ xan interval-join --dates --within time events.csv from,to periods.csv
+
+Usage:
+    xan interval-join [options] <columns> <input> <interval-columns> <intervals>
+    xan interval-join --help
+
+interval-join options:
+    -B, --by <columns>           Only match intervals sharing the same values in
+                                 the given columns, e.g. a chromosome. Those columns
+                                 are selected in the searched file and also in the
+                                 intervals file, unless using --by-intervals.
+    --by-intervals <columns>     Columns to use to partition the intervals file,
+                                 if they differ from the ones given to -B, --by.
+    --within                     Only match when the searched interval (or point)
+                                 is fully contained in the indexed one. Cannot
+                                 be used with --contains.
+    --contains                   Only match when the searched interval fully
+                                 contains the indexed one. Cannot be used with
+                                 --within.
+    --min-overlap <value>        Only match when intervals overlap by at least this
+                                 amount, i.e. the length of their intersection,
+                                 computed as the difference between the smallest
+                                 end and the largest start. Touching intervals
+                                 therefore overlap by 0. Must be a non-negative
+                                 number, or a duration such as \"2h\" or \"90s\" when
+                                 using --dates.
+    --dates                      Parse bounds as datetimes instead of numbers.
+    --left                       Write every row from input file in the output, with empty
+                                 padding cells on the right when no interval from the
+                                 second file produced any match.
+    -L, --prefix-left <prefix>   Add a prefix to the names of the columns in the
+                                 searched file.
+    -R, --prefix-right <prefix>  Add a prefix to the names of the columns in the
+                                 intervals file.
+
+Common options:
+    -h, --help                  Display this message
+    -o, --output <file>         Write output to <file> instead of stdout.
+    -n, --no-headers            When set, the first row will not be interpreted
+                                as headers. (i.e., They are not searched, analyzed,
+                                sliced, etc.)
+    -d, --delimiter <arg>       The field delimiter for reading CSV data.
+                                Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: String,
+    arg_interval_columns: SelectColumns,
+    arg_intervals: String,
+    flag_by: Option<SelectColumns>,
+    flag_by_intervals: Option<SelectColumns>,
+    flag_within: bool,
+    flag_contains: bool,
+    flag_min_overlap: Option<String>,
+    flag_dates: bool,
+    flag_left: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_prefix_left: Option<String>,
+    flag_prefix_right: Option<String>,
+}
+
+type Index<K> = HashMap<Vec<Vec<u8>>, IntervalTree<K, csv::ByteRecord>>;
+
+impl Args {
+    fn by_selections(
+        &self,
+        headers: &csv::ByteRecord,
+        intervals_headers: &csv::ByteRecord,
+    ) -> CliResult<(Selection, Selection)> {
+        let use_names = !self.flag_no_headers;
+
+        let sel = match &self.flag_by {
+            None => Selection::empty(),
+            Some(by) => by.selection(headers, use_names)?,
+        };
+
+        let intervals_sel = match self.flag_by_intervals.as_ref().or(self.flag_by.as_ref()) {
+            None => Selection::empty(),
+            Some(by) => by.selection(intervals_headers, use_names)?,
+        };
+
+        if sel.len() != intervals_sel.len() {
+            Err("not the same number of --by columns selected on both files!")?;
+        }
+
+        Ok((sel, intervals_sel))
+    }
+
+    fn run<K, F>(self, parse: F, min_overlap: Option<K>) -> CliResult<()>
+    where
+        K: Copy + PartialOrd + Sub<Output = K>,
+        F: Fn(&[u8]) -> CliResult<Option<K>>,
+    {
+        let intervals_rconf = Config::new(&Some(self.arg_intervals.clone()))
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers)
+            .select(self.arg_interval_columns.clone());
+
+        let mut intervals_reader = intervals_rconf.reader()?;
+        let mut intervals_headers = intervals_reader.byte_headers()?.clone();
+        let intervals_sel = intervals_rconf.selection(&intervals_headers)?;
+
+        if intervals_sel.len() != 2 {
+            Err("<interval-columns> must select exactly two columns: start & end!")?;
+        }
+
+        let padding: csv::ByteRecord = (0..intervals_headers.len()).map(|_| b"").collect();
+
+        let rconf = Config::new(&Some(self.arg_input.clone()))
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers)
+            .select(self.arg_columns.clone());
+
+        let mut reader = rconf.reader()?;
+        let mut headers = reader.byte_headers()?.clone();
+        let sel = rconf.selection(&headers)?;
+
+        if sel.is_empty() || sel.len() > 2 {
+            Err("<columns> must select either one column (a point) or two columns (start & end)!")?;
+        }
+
+        let (by_sel, intervals_by_sel) = self.by_selections(&headers, &intervals_headers)?;
+
+        if let Some(prefix) = &self.flag_prefix_right {
+            intervals_headers = prefix_header(&intervals_headers, prefix);
+        }
+
+        if let Some(prefix) = &self.flag_prefix_left {
+            headers = prefix_header(&headers, prefix);
+        }
+
+        let mut writer = Config::new(&self.flag_output).writer()?;
+
+        if !self.flag_no_headers {
+            let mut full_headers = csv::ByteRecord::new();
+            full_headers.extend(headers.iter());
+            full_headers.extend(intervals_headers.iter());
+
+            writer.write_record(&full_headers)?;
+        }
+
+        // Indexing the intervals
+        let mut index: Index<K> = HashMap::new();
+
+        for result in intervals_reader.into_byte_records() {
+            let record = result?;
+
+            let (start, end) = match (
+                parse(&record[intervals_sel[0]])?,
+                parse(&record[intervals_sel[1]])?,
+            ) {
+                (Some(start), Some(end)) => (start, end),
+                _ => continue,
+            };
+
+            if start > end {
+                Err(format!(
+                    "invalid interval where start is greater than end: {}, {}",
+                    String::from_utf8_lossy(&record[intervals_sel[0]]),
+                    String::from_utf8_lossy(&record[intervals_sel[1]])
+                ))?;
+            }
+
+            index
+                .entry(intervals_by_sel.collect(&record))
+                .or_insert_with(IntervalTree::new)
+                .insert(start, end, record);
+        }
+
+        for tree in index.values_mut() {
+            tree.build();
+        }
+
+        // Performing join
+        let mut record = csv::ByteRecord::new();
+
+        while reader.read_byte_record(&mut record)? {
+            let bounds = match (parse(&record[sel[0]])?, sel.get(1)) {
+                (Some(start), Some(i)) => parse(&record[*i])?.map(|end| (start, end)),
+                (Some(point), None) => Some((point, point)),
+                _ => None,
+            };
+
+            let mut something_was_written = false;
+
+            if let Some((start, end)) = bounds {
+                if let Some(tree) = index.get(&by_sel.collect(&record)) {
+                    for (other_start, other_end, other_record) in tree.overlapping(start, end) {
+                        if self.flag_within && (*other_start > start || *other_end < end) {
+                            continue;
+                        }
+
+                        if self.flag_contains && (start > *other_start || end < *other_end) {
+                            continue;
+                        }
+
+                        // NOTE: bounds are inclusive, so the length of the
+                        // intersection is 0 when intervals only touch.
+                        if let Some(amount) = min_overlap {
+                            let overlap_start = if start > *other_start {
+                                start
+                            } else {
+                                *other_start
+                            };
+
+                            let overlap_end = if end < *other_end { end } else { *other_end };
+
+                            if overlap_end - overlap_start < amount {
+                                continue;
+                            }
+                        }
+
+                        something_was_written = true;
+                        writer.write_record(record.iter().chain(other_record.iter()))?;
+                    }
+                }
+            }
+
+            if self.flag_left && !something_was_written {
+                writer.write_record(record.iter().chain(padding.iter()))?;
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_within && args.flag_contains {
+        Err("--within and --contains cannot be used together!")?;
+    }
+
+    if args.flag_dates {
+        let min_overlap = args
+            .flag_min_overlap
+            .as_ref()
+            .map(|m| {
+                m.parse::<SignedDuration>()
+                    .ok()
+                    .filter(|d| !d.is_negative())
+                    .map(|d| d.as_nanos())
+                    .ok_or_else(|| format!("invalid --min-overlap duration \"{}\"!", m))
+            })
+            .transpose()?;
+
+        args.run(parse_datetime_key, min_overlap)
+    } else {
+        let min_overlap = args
+            .flag_min_overlap
+            .as_ref()
+            .map(|m| {
+                m.parse::<f64>()
+                    .ok()
+                    .filter(|n| n.is_finite() && *n >= 0.0)
+                    .ok_or_else(|| format!("invalid --min-overlap number \"{}\"!", m))
+            })
+            .transpose()?;
+
+        args.run(parse_number_key, min_overlap)
+    }
+}
@@ -1,14 +1,25 @@
+use std::cmp::Ordering;
 use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
 use std::io;
 use std::num::NonZeroUsize;
+use std::ops::Sub;
+use std::path::Path;
 
 use bstr::ByteSlice;
+use bytesize::MB;
 use csv::ByteRecord;
+use ext_sort::{buffer::mem::MemoryLimitedBufferBuilder, ExternalSorter, ExternalSorterBuilder};
+use indexmap::IndexMap;
+use jiff::SignedDuration;
 
+use crate::cmd::sort::iter_cmp;
 use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::moonblade::Program;
 use crate::select::{SelectColumns, Selection};
-use crate::util;
-use crate::CliResult;
+use crate::util::{self, DeepSizedByteRecord};
+use crate::{CliError, CliResult};
 
 type IndexKey = Vec<Vec<u8>>;
 
@@ -18,6 +29,51 @@ fn get_row_key(sel: &Selection, row: &ByteRecord, case_insensitive: bool) -> Ind
         .collect()
 }
 
+// NOTE: join keys are either given by a column selection, or computed by
+// evaluating a moonblade expression for each row.
+enum KeySelection {
+    Columns(Selection),
+    Expression(Program),
+}
+
+impl KeySelection {
+    fn columns(&self) -> Option<&Selection> {
+        match self {
+            Self::Columns(sel) => Some(sel),
+            Self::Expression(_) => None,
+        }
+    }
+
+    fn get(
+        &self,
+        index: usize,
+        record: &ByteRecord,
+        case_insensitive: bool,
+    ) -> CliResult<IndexKey> {
+        Ok(match self {
+            Self::Columns(sel) => get_row_key(sel, record, case_insensitive),
+            Self::Expression(program) => {
+                let key = program.generate_key(index, record)?;
+
+                vec![transform(key.as_bytes(), case_insensitive)]
+            }
+        })
+    }
+}
+
+fn compare_row_keys(
+    sel: &Selection,
+    a: &ByteRecord,
+    b: &ByteRecord,
+    case_insensitive: bool,
+) -> Ordering {
+    if case_insensitive {
+        get_row_key(sel, a, true).cmp(&get_row_key(sel, b, true))
+    } else {
+        iter_cmp(sel.select(a), sel.select(b))
+    }
+}
+
 fn transform(bs: &[u8], case_insensitive: bool) -> Vec<u8> {
     if !case_insensitive {
         bs.to_vec()
@@ -53,6 +109,43 @@ fn build_headers(
     headers
 }
 
+// NOTE: prefixes are based on the file stems, e.g. "people_" for "people.csv",
+// falling back to the 1-based index of the inputs, e.g. "input2_", when
+// several inputs share the same stem.
+fn get_input_prefixes(paths: &[String]) -> Vec<String> {
+    let stems = paths
+        .iter()
+        .map(|path| {
+            if path == "-" {
+                return "stdin".to_string();
+            }
+
+            Path::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+
+    for stem in stems.iter() {
+        *counts.entry(stem).or_insert(0) += 1;
+    }
+
+    stems
+        .iter()
+        .enumerate()
+        .map(|(i, stem)| {
+            if counts[stem.as_str()] > 1 {
+                format!("input{}_", i + 1)
+            } else {
+                format!("{}_", stem)
+            }
+        })
+        .collect()
+}
+
 fn get_padding(headers: &ByteRecord) -> ByteRecord {
     (0..headers.len()).map(|_| b"").collect()
 }
@@ -80,16 +173,14 @@ impl IndexNode {
 // for the user and not aligned with usual affordances.
 #[derive(Debug)]
 struct Index {
-    case_insensitive: bool,
     nulls: bool,
     map: HashMap<IndexKey, (usize, usize)>,
     nodes: Vec<IndexNode>,
 }
 
 impl Index {
-    fn new(case_insensitive: bool, nulls: bool) -> Self {
+    fn new(nulls: bool) -> Self {
         Self {
-            case_insensitive,
             nulls,
             map: HashMap::new(),
             nodes: Vec::new(),
@@ -98,24 +189,22 @@ impl Index {
 
     fn from_csv_reader<R: io::Read>(
         reader: &mut csv::Reader<R>,
-        sel: &Selection,
+        sel: &KeySelection,
         case_insensitive: bool,
         nulls: bool,
     ) -> CliResult<Self> {
-        let mut index = Index::new(case_insensitive, nulls);
+        let mut index = Index::new(nulls);
 
-        for result in reader.byte_records() {
+        for (i, result) in reader.byte_records().enumerate() {
             let record = result?;
 
-            index.add(sel, record);
+            index.add(sel.get(i, &record, case_insensitive)?, record);
         }
 
         Ok(index)
     }
 
-    fn add(&mut self, sel: &Selection, record: ByteRecord) {
-        let key = get_row_key(sel, &record, self.case_insensitive);
-
+    fn add(&mut self, key: IndexKey, record: ByteRecord) {
         if !self.nulls && key.iter().all(|c| c.is_empty()) {
             return;
         }
@@ -137,22 +226,15 @@ impl Index {
         };
     }
 
-    fn for_each_node_mut<F, E>(
-        &mut self,
-        sel: &Selection,
-        record: &ByteRecord,
-        mut callback: F,
-    ) -> Result<(), E>
+    fn for_each_node_mut<F, E>(&mut self, key: &IndexKey, mut callback: F) -> Result<(), E>
     where
         F: FnMut(&mut IndexNode) -> Result<(), E>,
     {
-        let key = get_row_key(sel, record, self.case_insensitive);
-
         if !self.nulls && key.iter().all(|c| c.is_empty()) {
             return Ok(());
         }
 
-        if let Some((i, _)) = self.map.get(&key) {
+        if let Some((i, _)) = self.map.get(key) {
             let mut current_node = &mut self.nodes[i - 1];
 
             callback(current_node)?;
@@ -166,16 +248,11 @@ impl Index {
         Ok(())
     }
 
-    fn for_each_record<F, E>(
-        &mut self,
-        sel: &Selection,
-        record: &ByteRecord,
-        mut callback: F,
-    ) -> Result<(), E>
+    fn for_each_record<F, E>(&mut self, key: &IndexKey, mut callback: F) -> Result<(), E>
     where
         F: FnMut(&ByteRecord) -> Result<(), E>,
     {
-        self.for_each_node_mut(sel, record, |node| callback(&node.record))
+        self.for_each_node_mut(key, |node| callback(&node.record))
     }
 
     fn records_not_written(&self) -> impl Iterator<Item = &ByteRecord> {
@@ -189,6 +266,218 @@ impl Index {
     }
 }
 
+// NOTE: this abstraction reads sorted records and yields them grouped by key,
+// so that both sides of a merge join can be advanced group by group.
+struct KeyGroups<'a, I> {
+    records: I,
+    name: &'a str,
+    sel: &'a KeySelection,
+    case_insensitive: bool,
+    index: usize,
+    pending: Option<(IndexKey, ByteRecord)>,
+}
+
+impl<'a, I> KeyGroups<'a, I>
+where
+    I: Iterator<Item = CliResult<ByteRecord>>,
+{
+    fn new(records: I, name: &'a str, sel: &'a KeySelection, case_insensitive: bool) -> Self {
+        Self {
+            records,
+            name,
+            sel,
+            case_insensitive,
+            index: 0,
+            pending: None,
+        }
+    }
+
+    fn read(&mut self) -> CliResult<Option<(IndexKey, ByteRecord)>> {
+        Ok(match self.records.next() {
+            None => None,
+            Some(result) => {
+                let record = result?;
+                let key = self.sel.get(self.index, &record, self.case_insensitive)?;
+
+                self.index += 1;
+
+                Some((key, record))
+            }
+        })
+    }
+
+    fn next_group(&mut self) -> CliResult<Option<(IndexKey, Vec<ByteRecord>)>> {
+        let (key, record) = match self.pending.take() {
+            Some(pending) => pending,
+            None => match self.read()? {
+                None => return Ok(None),
+                Some(next) => next,
+            },
+        };
+
+        let mut group = vec![record];
+
+        while let Some((next_key, next_record)) = self.read()? {
+            match next_key.cmp(&key) {
+                Ordering::Equal => group.push(next_record),
+                Ordering::Less => {
+                    return Err(format!("{} is not sorted on the join columns!", self.name))?;
+                }
+                Ordering::Greater => {
+                    self.pending = Some((next_key, next_record));
+                    break;
+                }
+            }
+        }
+
+        Ok(Some((key, group)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+enum AsofDirection {
+    Backward,
+    Forward,
+    Nearest,
+}
+
+impl TryFrom<String> for AsofDirection {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(match value.as_str() {
+            "backward" => Self::Backward,
+            "forward" => Self::Forward,
+            "nearest" => Self::Nearest,
+            _ => return Err(format!("unsupported as-of direction \"{}\"", &value)),
+        })
+    }
+}
+
+// NOTE: entries are sorted by key once the whole file has been indexed. Since
+// the sort is stable, entries having the same key remain in insertion order.
+#[derive(Debug)]
+struct AsofIndex<K> {
+    case_insensitive: bool,
+    nulls: bool,
+    map: HashMap<IndexKey, Vec<(K, ByteRecord)>>,
+}
+
+impl<K> AsofIndex<K>
+where
+    K: Copy + PartialOrd + Sub<Output = K>,
+{
+    fn new(case_insensitive: bool, nulls: bool) -> Self {
+        Self {
+            case_insensitive,
+            nulls,
+            map: HashMap::new(),
+        }
+    }
+
+    fn get_by_key(&self, by_sel: &Selection, record: &ByteRecord) -> Option<IndexKey> {
+        let key = get_row_key(by_sel, record, self.case_insensitive);
+
+        if !self.nulls && !key.is_empty() && key.iter().all(|c| c.is_empty()) {
+            return None;
+        }
+
+        Some(key)
+    }
+
+    fn add(&mut self, by_sel: &Selection, key: K, record: ByteRecord) {
+        if let Some(by_key) = self.get_by_key(by_sel, &record) {
+            self.map.entry(by_key).or_default().push((key, record));
+        }
+    }
+
+    fn finalize(&mut self) {
+        for entries in self.map.values_mut() {
+            entries.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        }
+    }
+
+    fn find(
+        &self,
+        by_sel: &Selection,
+        key: K,
+        record: &ByteRecord,
+        direction: AsofDirection,
+        tolerance: Option<K>,
+    ) -> Option<&ByteRecord> {
+        let entries = self.map.get(&self.get_by_key(by_sel, record)?)?;
+
+        let backward = || {
+            let i = entries.partition_point(|(k, _)| *k <= key);
+            i.checked_sub(1).map(|i| &entries[i])
+        };
+
+        let forward = || {
+            let i = entries.partition_point(|(k, _)| *k < key);
+            entries.get(i)
+        };
+
+        let (found_key, found_record) = match direction {
+            AsofDirection::Backward => backward()?,
+            AsofDirection::Forward => forward()?,
+            AsofDirection::Nearest => match (backward(), forward()) {
+                (None, None) => return None,
+                (Some(b), None) => b,
+                (None, Some(f)) => f,
+                (Some(b), Some(f)) => {
+                    if f.0 - key < key - b.0 {
+                        f
+                    } else {
+                        b
+                    }
+                }
+            },
+        };
+
+        if let Some(max_distance) = tolerance {
+            let distance = if *found_key < key {
+                key - *found_key
+            } else {
+                *found_key - key
+            };
+
+            if distance > max_distance {
+                return None;
+            }
+        }
+
+        Some(found_record)
+    }
+}
+
+pub fn parse_number_key(cell: &[u8]) -> CliResult<Option<f64>> {
+    if cell.is_empty() {
+        return Ok(None);
+    }
+
+    match fast_float::parse::<f64, &[u8]>(cell) {
+        Ok(number) if !number.is_nan() => Ok(Some(number)),
+        _ => Err(format!(
+            "could not parse \"{}\" as a number!",
+            String::from_utf8_lossy(cell)
+        ))?,
+    }
+}
+
+pub fn parse_datetime_key(cell: &[u8]) -> CliResult<Option<i128>> {
+    if cell.is_empty() {
+        return Ok(None);
+    }
+
+    let string = String::from_utf8_lossy(cell);
+
+    match dates::parse_zoned(&string, None, None) {
+        Ok(zoned) => Ok(Some(zoned.timestamp().as_nanosecond())),
+        Err(_) => Err(format!("could not parse \"{}\" as a datetime!", string))?,
+    }
+}
+
 static USAGE: &str = "
 Join two sets of CSV data on the specified columns.
 
@@ -198,6 +487,12 @@ perform a left outer join with --left, a right outer join with --right,
 a full outer join with --full and finally a cartesian product/cross join
 with --cross.
 
+The command can also perform a semi join with --semi, i.e. only keeping
+the rows of the first file having a match in the second one, or an anti join
+with --anti, i.e. only keeping the rows of the first file having no match in the
+second one. In both cases, rows of the first file are emitted only once and
+are left untouched.
+
 By default, joins are done case sensitively, but this can be disabled using
 the -i, --ignore-case flag.
 
@@ -205,10 +500,39 @@ The column arguments specify the columns to join for each input. Columns can
 be selected using the same syntax as the \"xan select\" command. Both selections
 must return a same number of columns, for the join keys to be properly aligned.
 
+Alternatively, when using the --expr flag, the column arguments will be
+understood as expressions to evaluate for each row of their respective input,
+in order to compute the join keys. This can be useful to join on normalized
+values without having to create temporary columns beforehand, for instance:
+
+    $ xan join --expr 'fingerprint(name)' people.csv 'fingerprint(full_name)' users.csv
+
+For a quick review of the capabilities of the expression language,
+check out the `xan help cheatsheet` command.
+
 Note that this command is able to consume streams such as stdin (in which case
 the file name must be \"-\" to indicate which file will be read from stdin) and
 gzipped files out of the box.
 
+# As-of joins
+
+The --asof flag performs an \"as-of\" join, where each row of the first file is
+matched with the row of the second file having the nearest ordered key, e.g. to
+attach to each event the last reference row whose timestamp is less than or
+equal to the event's own.
+
+In this mode, the last column of both selections is used as the ordered key,
+while the preceding ones, if any, are used as exact \"by\" keys. Ordered keys
+are parsed as numbers, or as datetimes when using the --dates flag. Every row
+of the first file is emitted exactly once, padded with empty fields when no
+match could be found, just like with a left join. Note that none of the files
+needs to be sorted beforehand.
+
+For instance, to attach to each trade the last known quote for the same ticker,
+if it is not older than one hour:
+
+    $ xan join --asof --dates --tolerance 1h ticker,time trades.csv ticker,time quotes.csv
+
 # Memory considerations
 
     - `inner join`: the command does not try to be clever and
@@ -227,8 +551,41 @@ gzipped files out of the box.
                     always indexes the left file, while the right
                     file is streamed. Prefer placing the smaller file
                     on the left.
+    - `as-of join`: the command always indexes the right file and streams
+                    the left file.
+    - `semi join`:  the command only indexes the keys of the right file
+                    and streams the left file.
+    - `anti join`:  the command only indexes the keys of the right file
+                    and streams the left file.
+
+If your files do not fit in memory, all of the above joins, except for
+cross and as-of joins, can also be performed as a \"merge\" join, by streaming
+both files at once, given that they are sorted on the join columns. Use
+the --sorted flag if your files are already sorted (e.g. using \"xan sort -s\"),
+or the -e/--external flag to let the command sort them on disk beforehand.
+Note that, in this case, rows will be emitted in the order of the join keys,
+and that only the rows sharing a same key need to fit in memory.
+
+# Joining multiple files
+
+The --multi flag makes it possible to join more than two files at once, on
+a same selection of columns, in a single pass. Only inner joins (the default)
+and full outer joins (using --full) are supported in this mode.
+
+The output will contain the key columns only once, followed by the other
+columns of each file in turn. Columns whose name is found in more than one file
+will be prefixed by the name of their file, without extension, e.g.
+\"people_name\" for a column \"name\" found in \"people.csv\". If several files
+share the same name, they will be prefixed by their position instead, e.g.
+\"input2_name\".
+
+    $ xan join --multi id people.csv addresses.csv jobs.csv
+
+Note that all files but the first one will be indexed in memory, while the
+first one is streamed.
 
 Usage:
+    xan join [options] --multi <columns> <inputs>...
     xan join [options] <columns1> <input1> <columns2> <input2>
     xan join [options] --cross <input1> <input2>
     xan join --help
@@ -248,11 +605,49 @@ join options:
                                  both data sets with matching records joined. If
                                  there is no match, the missing side will be padded
                                  out with empty fields.
+    --semi                       Do a \"semi\" join. This only returns the rows of
+                                 the first CSV data set having a match in the
+                                 second one.
+    --anti                       Do an \"anti\" join. This only returns the rows of
+                                 the first CSV data set having no match in the
+                                 second one.
     --cross                      This returns the cartesian product of the given CSV
                                  files. The number of rows emitted will be equal to N * M,
                                  where N and M correspond to the number of rows in the given
                                  data sets, respectively.
+    --multi                      Join more than two files on a same selection of
+                                 columns. See the dedicated section above for more
+                                 details.
+    --asof                       Do an \"as-of\" join. See the dedicated section above
+                                 for more details.
+    --direction <direction>      Direction of the as-of join. \"backward\" matches the
+                                 last row whose key is less than or equal to the
+                                 joined one, \"forward\" matches the first row whose
+                                 key is greater than or equal to it, and \"nearest\"
+                                 matches the closest one, preferring backward matches
+                                 in case of ties. Defaults to \"backward\".
+    --tolerance <value>          Maximum distance allowed between as-of keys. Must be
+                                 a non-negative number, or a non-negative duration
+                                 such as \"2h\" or \"90s\" when using --dates.
+    --dates                      Parse as-of keys as datetimes instead of numbers.
+    --indicator <name>           Name of a column to add to the output of a full
+                                 outer join, indicating whether the row was found in
+                                 \"both\" data sets, or only in the first one
+                                 (\"left_only\") or the second one (\"right_only\").
+    --sorted                     Perform a merge join, by assuming both files are
+                                 already sorted on the join columns. Cannot be
+                                 used with -i, --ignore-case.
+    -e, --external               Perform a merge join, after sorting both files on
+                                 disk, for when they cannot fit in memory.
+    --tmp-dir <arg>              Directory where external sorting chunks will be
+                                 written. Will default to the joined files' directories
+                                 or \"./\" if joining incoming streams.
+    -m, --memory-limit <arg>     Maximum allowed memory when using external sorting,
+                                 in megabytes. [default: 512].
     -i, --ignore-case            When set, joins are done case insensitively.
+    --expr                       When set, column arguments will be evaluated as
+                                 expressions to compute the join keys. Cannot be
+                                 used with --cross, --asof or -e, --external.
     --nulls                      When set, joins will work on empty fields.
                                  Otherwise, empty keys are completely ignored, i.e. when
                                  column selection yield only empty cells.
@@ -273,17 +668,32 @@ Common options:
 
 #[derive(Deserialize)]
 struct Args {
-    arg_columns1: SelectColumns,
+    arg_columns1: String,
     arg_input1: String,
-    arg_columns2: SelectColumns,
+    arg_columns2: String,
     arg_input2: String,
+    arg_columns: String,
+    arg_inputs: Vec<String>,
     flag_left: bool,
     flag_right: bool,
     flag_full: bool,
+    flag_semi: bool,
+    flag_anti: bool,
     flag_cross: bool,
+    flag_multi: bool,
+    flag_asof: bool,
+    flag_direction: Option<AsofDirection>,
+    flag_tolerance: Option<String>,
+    flag_dates: bool,
+    flag_indicator: Option<String>,
+    flag_sorted: bool,
+    flag_external: bool,
+    flag_tmp_dir: Option<String>,
+    flag_memory_limit: u64,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_ignore_case: bool,
+    flag_expr: bool,
     flag_nulls: bool,
     flag_delimiter: Option<Delimiter>,
     flag_prefix_left: Option<String>,
@@ -293,27 +703,37 @@ struct Args {
 type BoxedReader = csv::Reader<Box<dyn io::Read + Send>>;
 
 impl Args {
+    fn key_selection(&self, arg: &str, headers: &ByteRecord) -> CliResult<KeySelection> {
+        Ok(if self.flag_expr {
+            KeySelection::Expression(Program::parse(arg, headers)?)
+        } else {
+            KeySelection::Columns(
+                SelectColumns::parse(arg)?.selection(headers, !self.flag_no_headers)?,
+            )
+        })
+    }
+
     fn readers_and_selections(
         &self,
-    ) -> CliResult<((BoxedReader, Selection), (BoxedReader, Selection))> {
+    ) -> CliResult<((BoxedReader, KeySelection), (BoxedReader, KeySelection))> {
         let left = Config::new(&Some(self.arg_input1.clone()))
             .delimiter(self.flag_delimiter)
-            .no_headers(self.flag_no_headers)
-            .select(self.arg_columns1.clone());
+            .no_headers(self.flag_no_headers);
 
         let right = Config::new(&Some(self.arg_input2.clone()))
             .delimiter(self.flag_delimiter)
-            .no_headers(self.flag_no_headers)
-            .select(self.arg_columns2.clone());
+            .no_headers(self.flag_no_headers);
 
         let mut left_reader = left.reader()?;
         let mut right_reader = right.reader()?;
 
-        let left_sel = left.selection(left_reader.byte_headers()?)?;
-        let right_sel = right.selection(right_reader.byte_headers()?)?;
+        let left_sel = self.key_selection(&self.arg_columns1, left_reader.byte_headers()?)?;
+        let right_sel = self.key_selection(&self.arg_columns2, right_reader.byte_headers()?)?;
 
-        if !self.flag_cross && left_sel.len() != right_sel.len() {
-            Err("not the same number of columns selected on left & right!")?;
+        if let (Some(l), Some(r)) = (left_sel.columns(), right_sel.columns()) {
+            if !self.flag_cross && l.len() != r.len() {
+                Err("not the same number of columns selected on left & right!")?;
+            }
         }
 
         Ok(((left_reader, left_sel), (right_reader, right_sel)))
@@ -323,7 +743,11 @@ impl Args {
         Config::new(&self.flag_output)
     }
 
-    fn index(&self, reader: &mut BoxedReader, sel: &Selection) -> CliResult<Index> {
+    fn is_null_key(&self, key: &IndexKey) -> bool {
+        !self.flag_nulls && key.iter().all(|c| c.is_empty())
+    }
+
+    fn index(&self, reader: &mut BoxedReader, sel: &KeySelection) -> CliResult<Index> {
         Index::from_csv_reader(reader, sel, self.flag_ignore_case, self.flag_nulls)
     }
 
@@ -334,12 +758,18 @@ impl Args {
         right_headers: &ByteRecord,
     ) -> CliResult<()> {
         if !self.flag_no_headers {
-            writer.write_byte_record(&build_headers(
+            let mut headers = build_headers(
                 left_headers,
                 right_headers,
                 &self.flag_prefix_left,
                 &self.flag_prefix_right,
-            ))?;
+            );
+
+            if let Some(name) = &self.flag_indicator {
+                headers.push_field(name.as_bytes());
+            }
+
+            writer.write_byte_record(&headers)?;
         }
 
         Ok(())
@@ -360,9 +790,13 @@ impl Args {
         let mut index = self.index(&mut left_reader, &left_sel)?;
 
         let mut right_record = csv::ByteRecord::new();
+        let mut i: usize = 0;
 
         while right_reader.read_byte_record(&mut right_record)? {
-            index.for_each_record(&right_sel, &right_record, |left_record| {
+            let key = right_sel.get(i, &right_record, self.flag_ignore_case)?;
+            i += 1;
+
+            index.for_each_record(&key, |left_record| {
                 writer.write_record(left_record.iter().chain(right_record.iter()))
             })?;
         }
@@ -382,28 +816,50 @@ impl Args {
         let left_padding = get_padding(&left_headers);
         let right_padding = get_padding(&right_headers);
 
+        let indicator = self.flag_indicator.is_some();
+
         self.write_headers(&mut writer, &left_headers, &right_headers)?;
 
         let mut index = self.index(&mut left_reader, &left_sel)?;
 
         let mut right_record = csv::ByteRecord::new();
+        let mut i: usize = 0;
 
         while right_reader.read_byte_record(&mut right_record)? {
+            let key = right_sel.get(i, &right_record, self.flag_ignore_case)?;
+            i += 1;
+
             let mut something_was_written: bool = false;
 
-            index.for_each_node_mut(&right_sel, &right_record, |left_node| {
+            index.for_each_node_mut(&key, |left_node| {
                 something_was_written = true;
                 left_node.written = true;
-                writer.write_record(left_node.record.iter().chain(right_record.iter()))
+                writer.write_record(
+                    left_node
+                        .record
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(indicator.then_some(b"both".as_slice())),
+                )
             })?;
 
             if !something_was_written {
-                writer.write_record(left_padding.iter().chain(right_record.iter()))?;
+                writer.write_record(
+                    left_padding
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(indicator.then_some(b"right_only".as_slice())),
+                )?;
             }
         }
 
         for left_record in index.records_not_written() {
-            writer.write_record(left_record.iter().chain(right_padding.iter()))?;
+            writer.write_record(
+                left_record
+                    .iter()
+                    .chain(right_padding.iter())
+                    .chain(indicator.then_some(b"left_only".as_slice())),
+            )?;
         }
 
         Ok(writer.flush()?)
@@ -425,11 +881,15 @@ impl Args {
         let mut index = self.index(&mut right_reader, &right_sel)?;
 
         let mut left_record = csv::ByteRecord::new();
+        let mut i: usize = 0;
 
         while left_reader.read_byte_record(&mut left_record)? {
+            let key = left_sel.get(i, &left_record, self.flag_ignore_case)?;
+            i += 1;
+
             let mut something_was_written: bool = false;
 
-            index.for_each_record(&left_sel, &left_record, |right_record| {
+            index.for_each_record(&key, |right_record| {
                 something_was_written = true;
                 writer.write_record(left_record.iter().chain(right_record.iter()))
             })?;
@@ -458,11 +918,15 @@ impl Args {
         let mut index = self.index(&mut left_reader, &left_sel)?;
 
         let mut right_record = csv::ByteRecord::new();
+        let mut i: usize = 0;
 
         while right_reader.read_byte_record(&mut right_record)? {
+            let key = right_sel.get(i, &right_record, self.flag_ignore_case)?;
+            i += 1;
+
             let mut something_was_written: bool = false;
 
-            index.for_each_record(&right_sel, &right_record, |left_record| {
+            index.for_each_record(&key, |left_record| {
                 something_was_written = true;
                 writer.write_record(left_record.iter().chain(right_record.iter()))
             })?;
@@ -475,6 +939,266 @@ impl Args {
         Ok(writer.flush()?)
     }
 
+    fn semi_or_anti_join(self, anti: bool) -> CliResult<()> {
+        let ((mut left_reader, left_sel), (mut right_reader, right_sel)) =
+            self.readers_and_selections()?;
+
+        let mut writer = self.wconf().writer()?;
+
+        if !self.flag_no_headers {
+            writer.write_byte_record(&build_headers(
+                left_reader.byte_headers()?,
+                &ByteRecord::new(),
+                &self.flag_prefix_left,
+                &None,
+            ))?;
+        }
+
+        let mut keys: HashSet<IndexKey> = HashSet::new();
+
+        for (i, result) in right_reader.byte_records().enumerate() {
+            let key = right_sel.get(i, &result?, self.flag_ignore_case)?;
+
+            if !self.is_null_key(&key) {
+                keys.insert(key);
+            }
+        }
+
+        let mut left_record = csv::ByteRecord::new();
+        let mut i: usize = 0;
+
+        while left_reader.read_byte_record(&mut left_record)? {
+            let key = left_sel.get(i, &left_record, self.flag_ignore_case)?;
+            i += 1;
+
+            let has_match = !self.is_null_key(&key) && keys.contains(&key);
+
+            if has_match != anti {
+                writer.write_byte_record(&left_record)?;
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    fn external_sorter(
+        &self,
+        input: &str,
+    ) -> CliResult<
+        ExternalSorter<
+            DeepSizedByteRecord,
+            csv::Error,
+            MemoryLimitedBufferBuilder,
+            util::CsvExternalChunk,
+        >,
+    > {
+        let tmp_dir = self.flag_tmp_dir.clone().unwrap_or(if input == "-" {
+            "./".to_string()
+        } else {
+            Path::new(input)
+                .parent()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string()
+        });
+
+        ExternalSorterBuilder::new()
+            .with_tmp_dir(Path::new(&tmp_dir))
+            .with_buffer(MemoryLimitedBufferBuilder::new(self.flag_memory_limit * MB))
+            .build()
+            .map_err(|err| CliError::Other(err.to_string()))
+    }
+
+    fn merge_join(self) -> CliResult<()> {
+        let ((mut left_reader, left_sel), (mut right_reader, right_sel)) =
+            self.readers_and_selections()?;
+
+        let left_headers = left_reader.byte_headers()?.clone();
+        let right_headers = right_reader.byte_headers()?.clone();
+
+        let case_insensitive = self.flag_ignore_case;
+
+        if !self.flag_external {
+            return self.merge_join_groups(
+                KeyGroups::new(
+                    left_reader.into_byte_records().map(|r| Ok(r?)),
+                    &self.arg_input1,
+                    &left_sel,
+                    case_insensitive,
+                ),
+                KeyGroups::new(
+                    right_reader.into_byte_records().map(|r| Ok(r?)),
+                    &self.arg_input2,
+                    &right_sel,
+                    case_insensitive,
+                ),
+                &left_headers,
+                &right_headers,
+            );
+        }
+
+        // NOTE: external sorting is only possible with column selections,
+        // as checked beforehand.
+        let left_columns = left_sel.columns().unwrap();
+        let right_columns = right_sel.columns().unwrap();
+
+        let left_sorter = self.external_sorter(&self.arg_input1)?;
+        let right_sorter = self.external_sorter(&self.arg_input2)?;
+
+        let left_sorted = left_sorter
+            .sort_by(
+                left_reader
+                    .into_byte_records()
+                    .map(|result| result.map(DeepSizedByteRecord)),
+                |r1, r2| compare_row_keys(left_columns, r1.as_ref(), r2.as_ref(), case_insensitive),
+            )
+            .map_err(|err| CliError::Other(err.to_string()))?
+            .map(|result| Ok(result?.into_inner()));
+
+        let right_sorted = right_sorter
+            .sort_by(
+                right_reader
+                    .into_byte_records()
+                    .map(|result| result.map(DeepSizedByteRecord)),
+                |r1, r2| {
+                    compare_row_keys(right_columns, r1.as_ref(), r2.as_ref(), case_insensitive)
+                },
+            )
+            .map_err(|err| CliError::Other(err.to_string()))?
+            .map(|result| Ok(result?.into_inner()));
+
+        self.merge_join_groups(
+            KeyGroups::new(left_sorted, &self.arg_input1, &left_sel, case_insensitive),
+            KeyGroups::new(right_sorted, &self.arg_input2, &right_sel, case_insensitive),
+            &left_headers,
+            &right_headers,
+        )
+    }
+
+    fn merge_join_groups<L, R>(
+        &self,
+        mut left: KeyGroups<L>,
+        mut right: KeyGroups<R>,
+        left_headers: &ByteRecord,
+        right_headers: &ByteRecord,
+    ) -> CliResult<()>
+    where
+        L: Iterator<Item = CliResult<ByteRecord>>,
+        R: Iterator<Item = CliResult<ByteRecord>>,
+    {
+        let mut writer = self.wconf().writer()?;
+
+        let semi_or_anti = self.flag_semi || self.flag_anti;
+
+        if semi_or_anti {
+            if !self.flag_no_headers {
+                writer.write_byte_record(&build_headers(
+                    left_headers,
+                    &ByteRecord::new(),
+                    &self.flag_prefix_left,
+                    &None,
+                ))?;
+            }
+        } else {
+            self.write_headers(&mut writer, left_headers, right_headers)?;
+        }
+
+        let left_padding = get_padding(left_headers);
+        let right_padding = get_padding(right_headers);
+
+        let emit_left_only = self.flag_left || self.flag_full || self.flag_anti;
+        let emit_right_only = self.flag_right || self.flag_full;
+        let indicator = self.flag_indicator.is_some();
+
+        let mut left_group = left.next_group()?;
+        let mut right_group = right.next_group()?;
+
+        loop {
+            // NOTE: null keys never match, and are therefore emitted as if
+            // they were lesser than the other side's key.
+            let ordering = match (&left_group, &right_group) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((left_key, _)), Some((right_key, _))) => {
+                    if self.is_null_key(left_key) {
+                        Ordering::Less
+                    } else if self.is_null_key(right_key) {
+                        Ordering::Greater
+                    } else {
+                        left_key.cmp(right_key)
+                    }
+                }
+            };
+
+            match ordering {
+                Ordering::Less => {
+                    let (_, left_records) = left_group.take().unwrap();
+
+                    if emit_left_only {
+                        for left_record in left_records.iter() {
+                            if semi_or_anti {
+                                writer.write_byte_record(left_record)?;
+                            } else {
+                                writer.write_record(
+                                    left_record
+                                        .iter()
+                                        .chain(right_padding.iter())
+                                        .chain(indicator.then_some(b"left_only".as_slice())),
+                                )?;
+                            }
+                        }
+                    }
+
+                    left_group = left.next_group()?;
+                }
+                Ordering::Greater => {
+                    let (_, right_records) = right_group.take().unwrap();
+
+                    if emit_right_only {
+                        for right_record in right_records.iter() {
+                            writer.write_record(
+                                left_padding
+                                    .iter()
+                                    .chain(right_record.iter())
+                                    .chain(indicator.then_some(b"right_only".as_slice())),
+                            )?;
+                        }
+                    }
+
+                    right_group = right.next_group()?;
+                }
+                Ordering::Equal => {
+                    let (_, left_records) = left_group.take().unwrap();
+                    let (_, right_records) = right_group.take().unwrap();
+
+                    if self.flag_semi {
+                        for left_record in left_records.iter() {
+                            writer.write_byte_record(left_record)?;
+                        }
+                    } else if !self.flag_anti {
+                        for left_record in left_records.iter() {
+                            for right_record in right_records.iter() {
+                                writer.write_record(
+                                    left_record
+                                        .iter()
+                                        .chain(right_record.iter())
+                                        .chain(indicator.then_some(b"both".as_slice())),
+                                )?;
+                            }
+                        }
+                    }
+
+                    left_group = left.next_group()?;
+                    right_group = right.next_group()?;
+                }
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+
     fn cross_join(self) -> CliResult<()> {
         let ((mut left_reader, _), (mut right_reader, _)) = self.readers_and_selections()?;
 
@@ -500,6 +1224,264 @@ impl Args {
 
         Ok(writer.flush()?)
     }
+
+    fn multi_join(self) -> CliResult<()> {
+        let select = SelectColumns::parse(&self.arg_columns)?;
+
+        let mut readers = Vec::with_capacity(self.arg_inputs.len());
+        let mut all_headers = Vec::with_capacity(self.arg_inputs.len());
+        let mut selections: Vec<Selection> = Vec::with_capacity(self.arg_inputs.len());
+
+        for path in self.arg_inputs.iter() {
+            let conf = Config::new(&Some(path.clone()))
+                .delimiter(self.flag_delimiter)
+                .no_headers(self.flag_no_headers)
+                .select(select.clone());
+
+            let mut reader = conf.reader()?;
+            let headers = reader.byte_headers()?.clone();
+            let sel = conf.selection(&headers)?;
+
+            if !selections.is_empty() && sel.len() != selections[0].len() {
+                Err("not the same number of columns selected in every file!")?;
+            }
+
+            readers.push(reader);
+            all_headers.push(headers);
+            selections.push(sel);
+        }
+
+        let others: Vec<Selection> = all_headers
+            .iter()
+            .zip(selections.iter())
+            .map(|(headers, sel)| Selection::without_indices(headers.len(), sel))
+            .collect();
+
+        let paddings: Vec<ByteRecord> = all_headers.iter().map(get_padding).collect();
+
+        let mut writer = self.wconf().writer()?;
+
+        if !self.flag_no_headers {
+            let mut counts: HashMap<&[u8], usize> = HashMap::new();
+
+            for name in selections[0].select(&all_headers[0]) {
+                counts.insert(name, 1);
+            }
+
+            for (headers, other) in all_headers.iter().zip(others.iter()) {
+                for name in other.select(headers) {
+                    counts.entry(name).and_modify(|c| *c += 1).or_insert(1);
+                }
+            }
+
+            let mut output_headers: ByteRecord = selections[0].select(&all_headers[0]).collect();
+
+            let prefixes = get_input_prefixes(&self.arg_inputs);
+
+            for ((headers, other), prefix) in
+                all_headers.iter().zip(others.iter()).zip(prefixes.iter())
+            {
+                for name in other.select(headers) {
+                    if counts[name] > 1 {
+                        output_headers.push_field(&[prefix.as_bytes(), name].concat());
+                    } else {
+                        output_headers.push_field(name);
+                    }
+                }
+            }
+
+            writer.write_byte_record(&output_headers)?;
+        }
+
+        // Indexing every file but the first one
+        let mut indices: Vec<IndexMap<IndexKey, Vec<ByteRecord>>> = Vec::new();
+
+        for (reader, sel) in readers.iter_mut().zip(selections.iter()).skip(1) {
+            let mut index: IndexMap<IndexKey, Vec<ByteRecord>> = IndexMap::new();
+
+            for result in reader.byte_records() {
+                let record = result?;
+                let key = get_row_key(sel, &record, self.flag_ignore_case);
+
+                if self.is_null_key(&key) {
+                    continue;
+                }
+
+                index.entry(key).or_default().push(record);
+            }
+
+            indices.push(index);
+        }
+
+        // NOTE: key values are taken from the first file having a match
+        let mut write_product = |first: usize, groups: &[&[ByteRecord]]| -> CliResult<()> {
+            let key_values = selections[first]
+                .select(&groups[first][0])
+                .map(|cell| cell.to_vec())
+                .collect::<Vec<_>>();
+
+            let mut cursors = vec![0; groups.len()];
+            let mut output_record = ByteRecord::new();
+
+            loop {
+                output_record.clear();
+
+                for cell in key_values.iter() {
+                    output_record.push_field(cell);
+                }
+
+                for ((group, other), cursor) in groups.iter().zip(others.iter()).zip(cursors.iter())
+                {
+                    for cell in other.select(&group[*cursor]) {
+                        output_record.push_field(cell);
+                    }
+                }
+
+                writer.write_byte_record(&output_record)?;
+
+                // Advancing cursors to enumerate the cartesian product
+                let mut i = groups.len();
+
+                loop {
+                    if i == 0 {
+                        return Ok(());
+                    }
+
+                    i -= 1;
+                    cursors[i] += 1;
+
+                    if cursors[i] < groups[i].len() {
+                        break;
+                    }
+
+                    cursors[i] = 0;
+                }
+            }
+        };
+
+        let mut written_keys: HashSet<IndexKey> = HashSet::new();
+
+        for result in readers[0].byte_records() {
+            let record = result?;
+            let key = get_row_key(&selections[0], &record, self.flag_ignore_case);
+
+            let mut groups: Vec<&[ByteRecord]> = vec![std::slice::from_ref(&record)];
+
+            for (index, padding) in indices.iter().zip(paddings.iter().skip(1)) {
+                match index.get(&key) {
+                    Some(group) if !self.is_null_key(&key) => groups.push(group),
+                    _ => {
+                        if !self.flag_full {
+                            break;
+                        }
+
+                        groups.push(std::slice::from_ref(padding));
+                    }
+                }
+            }
+
+            if groups.len() < paddings.len() {
+                continue;
+            }
+
+            write_product(0, &groups)?;
+
+            if self.flag_full {
+                written_keys.insert(key);
+            }
+        }
+
+        if self.flag_full {
+            for (i, index) in indices.iter().enumerate() {
+                for key in index.keys() {
+                    if written_keys.contains(key) {
+                        continue;
+                    }
+
+                    let mut groups: Vec<&[ByteRecord]> = vec![std::slice::from_ref(&paddings[0])];
+
+                    for (other_index, padding) in indices.iter().zip(paddings.iter().skip(1)) {
+                        match other_index.get(key) {
+                            Some(group) => groups.push(group),
+                            None => groups.push(std::slice::from_ref(padding)),
+                        }
+                    }
+
+                    write_product(i + 1, &groups)?;
+
+                    // NOTE: keys from the last index cannot be found again
+                    if i < indices.len() - 1 {
+                        written_keys.insert(key.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    fn asof_join<K, F>(self, parse: F, tolerance: Option<K>) -> CliResult<()>
+    where
+        K: Copy + PartialOrd + Sub<Output = K>,
+        F: Fn(&[u8]) -> CliResult<Option<K>>,
+    {
+        let ((mut left_reader, left_sel), (mut right_reader, right_sel)) =
+            self.readers_and_selections()?;
+
+        // NOTE: as-of joins are only possible with column selections, as
+        // checked beforehand.
+        let mut left_sel = left_sel.columns().unwrap().clone();
+        let mut right_sel = right_sel.columns().unwrap().clone();
+
+        let (left_on, right_on) = match (left_sel.pop(), right_sel.pop()) {
+            (Some(l), Some(r)) => (l, r),
+            _ => Err("as-of joins require at least one column on both sides!")?,
+        };
+
+        let mut writer = self.wconf().writer()?;
+
+        let left_headers = left_reader.byte_headers()?.clone();
+        let right_headers = right_reader.byte_headers()?.clone();
+
+        let right_padding = get_padding(&right_headers);
+
+        self.write_headers(&mut writer, &left_headers, &right_headers)?;
+
+        let mut index = AsofIndex::new(self.flag_ignore_case, self.flag_nulls);
+
+        for result in right_reader.into_byte_records() {
+            let record = result?;
+
+            if let Some(key) = parse(&record[right_on])? {
+                index.add(&right_sel, key, record);
+            }
+        }
+
+        index.finalize();
+
+        let mut left_record = csv::ByteRecord::new();
+
+        while left_reader.read_byte_record(&mut left_record)? {
+            let right_record = match parse(&left_record[left_on])? {
+                None => None,
+                Some(key) => index.find(
+                    &right_sel,
+                    key,
+                    &left_record,
+                    self.flag_direction.unwrap_or(AsofDirection::Backward),
+                    tolerance,
+                ),
+            };
+
+            writer.write_record(
+                left_record
+                    .iter()
+                    .chain(right_record.unwrap_or(&right_padding).iter()),
+            )?;
+        }
+
+        Ok(writer.flush()?)
+    }
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -509,7 +1491,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         args.flag_left,
         args.flag_right,
         args.flag_full,
+        args.flag_semi,
+        args.flag_anti,
         args.flag_cross,
+        args.flag_asof,
     ]
     .iter()
     .filter(|flag| **flag)
@@ -519,12 +1504,103 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("Please pick exactly one join operation.")?;
     }
 
-    if args.flag_left {
+    if !args.flag_asof
+        && (args.flag_dates || args.flag_tolerance.is_some() || args.flag_direction.is_some())
+    {
+        Err("--dates, --direction and --tolerance can only be used with --asof!")?;
+    }
+
+    if args.flag_multi {
+        if args.flag_left
+            || args.flag_right
+            || args.flag_semi
+            || args.flag_anti
+            || args.flag_cross
+            || args.flag_asof
+        {
+            Err("--multi only supports inner joins and full outer joins!")?;
+        }
+
+        if args.flag_expr
+            || args.flag_sorted
+            || args.flag_external
+            || args.flag_indicator.is_some()
+            || args.flag_prefix_left.is_some()
+            || args.flag_prefix_right.is_some()
+        {
+            Err("--multi cannot be used with --expr, --sorted, -e/--external, --indicator, -L/--prefix-left or -R/--prefix-right!")?;
+        }
+
+        if args.arg_inputs.len() < 2 {
+            Err("--multi requires at least two inputs!")?;
+        }
+
+        return args.multi_join();
+    }
+
+    if args.flag_expr && (args.flag_cross || args.flag_asof || args.flag_external) {
+        Err("--expr cannot be used with --cross, --asof or -e/--external!")?;
+    }
+
+    if !args.flag_full && args.flag_indicator.is_some() {
+        Err("--indicator can only be used with --full!")?;
+    }
+
+    if args.flag_sorted || args.flag_external {
+        if args.flag_sorted && args.flag_external {
+            Err("--sorted and -e/--external cannot be used together!")?;
+        }
+
+        if args.flag_cross || args.flag_asof {
+            Err("--sorted and -e/--external cannot be used with --cross or --asof!")?;
+        }
+
+        if args.flag_sorted && args.flag_ignore_case {
+            Err("--sorted cannot be used with -i/--ignore-case!")?;
+        }
+
+        return args.merge_join();
+    }
+
+    if args.flag_asof {
+        if args.flag_dates {
+            let tolerance = args
+                .flag_tolerance
+                .as_ref()
+                .map(|t| {
+                    t.parse::<SignedDuration>()
+                        .ok()
+                        .filter(|d| !d.is_negative())
+                        .map(|d| d.as_nanos())
+                        .ok_or_else(|| format!("invalid --tolerance duration \"{}\"!", t))
+                })
+                .transpose()?;
+
+            args.asof_join(parse_datetime_key, tolerance)
+        } else {
+            let tolerance = args
+                .flag_tolerance
+                .as_ref()
+                .map(|t| {
+                    t.parse::<f64>()
+                        .ok()
+                        .filter(|n| n.is_finite() && *n >= 0.0)
+                        .ok_or_else(|| format!("invalid --tolerance number \"{}\"!", t))
+                })
+                .transpose()?;
+
+            args.asof_join(parse_number_key, tolerance)
+        }
+    } else if args.flag_left {
         args.left_join()
     } else if args.flag_right {
         args.right_join()
     } else if args.flag_full {
         args.full_outer_join()
+    } else if args.flag_semi {
+        args.semi_or_anti_join(false)
+    } else if args.flag_anti {
+        args.semi_or_anti_join(true)
     } else if args.flag_cross {
         args.cross_join()
     } else {
@@ -547,10 +1623,14 @@ mod tests {
     }
 
     impl Index {
+        fn test_add(&mut self, sel: &Selection, record: ByteRecord) {
+            self.add(get_row_key(sel, &record, false), record);
+        }
+
         fn test_vec(&mut self, sel: &Selection, record: &ByteRecord) -> Vec<ByteRecord> {
             let mut v = Vec::new();
 
-            self.for_each_node_mut(sel, record, |node| -> Result<(), ()> {
+            self.for_each_node_mut(&get_row_key(sel, record, false), |node| -> Result<(), ()> {
                 v.push(node.record.clone());
                 Ok(())
             })
@@ -562,15 +1642,15 @@ mod tests {
 
     #[test]
     fn test_index_linked_lists() {
-        let mut index = Index::new(false, false);
+        let mut index = Index::new(false);
         let sel = Selection::full(1);
 
-        index.add(&sel, ByteRecord::from(rec(&["a", "one"])));
-        index.add(&sel, ByteRecord::from(rec(&["b", "one"])));
-        index.add(&sel, ByteRecord::from(rec(&["a", "two"])));
-        index.add(&sel, ByteRecord::from(rec(&["a", "three"])));
-        index.add(&sel, ByteRecord::from(rec(&["b", "two"])));
-        index.add(&sel, ByteRecord::from(rec(&["c", "one"])));
+        index.test_add(&sel, ByteRecord::from(rec(&["a", "one"])));
+        index.test_add(&sel, ByteRecord::from(rec(&["b", "one"])));
+        index.test_add(&sel, ByteRecord::from(rec(&["a", "two"])));
+        index.test_add(&sel, ByteRecord::from(rec(&["a", "three"])));
+        index.test_add(&sel, ByteRecord::from(rec(&["b", "two"])));
+        index.test_add(&sel, ByteRecord::from(rec(&["c", "one"])));
 
         assert_eq!(
             index.test_vec(&sel, &rec(&["d", "one"])),
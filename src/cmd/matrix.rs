@@ -1,3 +1,8 @@
+use std::collections::HashSet;
+
+use bstr::ByteSlice;
+use indexmap::{IndexMap, IndexSet};
+
 use crate::config::{Config, Delimiter};
 use crate::moonblade::agg::CovarianceWelford;
 use crate::select::SelectColumns;
@@ -8,44 +13,416 @@ static USAGE: &str = "
 Convert CSV data to matrix data.
 
 Supported modes:
-    corr: convert a selection of columns into a full
-          correlation matrix.
+    corr:        convert a selection of columns into a full
+                 correlation matrix.
+    cov:         convert a selection of columns into a full
+                 (sample) covariance matrix.
+    dist:        compute a pairwise distance or similarity matrix
+                 between the rows of the file.
+    contingency: build a contingency table (crosstab) counting the
+                 co-occurrences of the values of two categorical
+                 columns. Rows having an empty value in any of
+                 those columns are ignored.
+    pca:         project the rows of the file on their principal
+                 components, computed from a selection of columns.
+    mds:         project the items of a square distance matrix, as
+                 output by the dist mode, using classical
+                 multidimensional scaling.
+
+The corr, cov, dist & contingency modes output a square matrix by default,
+but can also output a long table with \"source\", \"target\" & \"value\"
+columns when using -L, --long. In this layout, symmetric matrices (i.e. corr,
+cov & dist) will only emit each pair of distinct items once, while contingency
+tables will only emit non-zero counts.
+
+The pca & mds modes output the coordinates of each row on the first components
+(\"pc_1\", \"pc_2\"... or \"dim_1\", \"dim_2\"...), which can be drawn using
+`xan plot`. They can also output the share of variance explained by each
+component, or the loadings of the principal components. Note that the whole
+file will be buffered in memory and that mds runs in cubic time.
+
+For instance, to plot the rows of a file on their two first principal
+components:
+
+    $ xan matrix pca -K name -s 'a:d' file.csv | xan plot pc_1 pc_2
+
+Or to do the same using the euclidean distances between rows:
+
+    $ xan matrix dist -i name -s 'a:d' file.csv | xan matrix mds | xan plot dim_1 dim_2
 
 Usage:
     xan matrix corr [options] [<input>]
+    xan matrix cov [options] [<input>]
+    xan matrix dist [options] [<input>]
+    xan matrix contingency [options] <row> <column> [<input>]
+    xan matrix pca [options] [<input>]
+    xan matrix mds [options] [<input>]
     xan matrix --help
 
+matrix corr, cov, dist & pca options:
+    -s, --select <columns>  Columns to consider for the matrix.
+
 matrix corr options:
-    -s, --select <columns>  Columns to consider for the correlation
-                            matrix.
     -D, --fill-diagonal     Whether to fill diagonal with ones.
+    -m, --method <method>   Correlation method, either \"pearson\", \"spearman\"
+                            or \"kendall\" (tau-b). Note that spearman & kendall
+                            need to buffer the selected columns in memory, and
+                            that kendall runs in quadratic time.
+                            [default: pearson]
+
+matrix dist options:
+    --metric <metric>       Metric to use, either \"euclidean\" (distance),
+                            \"cosine\" (similarity) or \"jaccard\" (similarity
+                            of the sets of tokens contained in the selected
+                            columns). Note that the whole file will be buffered
+                            in memory and that the matrix is computed in
+                            quadratic time. [default: euclidean]
+    -i, --id <column>       Column containing the names of the rows. Will use
+                            the 0-based index of the rows if not given.
+    --sep <sep>             Separator used to split the cells into tokens,
+                            when using the jaccard metric. [default: |]
+
+matrix pca & mds options:
+    -k, --components <n>    Number of components to output. [default: 2]
+    --variance              Output the variance explained by each component
+                            instead of the coordinates of the rows.
+
+matrix pca options:
+    -K, --keep <columns>    Columns to keep in the output, e.g. an id column.
+                            They will be excluded from the default selection.
+    --scale                 Scale the columns to unit variance before computing
+                            the components, i.e. use the correlation matrix
+                            instead of the covariance matrix.
+    --loadings              Output the loadings of the components, i.e. the
+                            weight of each column in each component, instead
+                            of the coordinates of the rows.
+
+matrix output options:
+    -L, --long              Output a long table with \"source\", \"target\" &
+                            \"value\" columns instead of a square matrix.
 
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -n, --no-headers       When set, the file will be considered as having no
                            headers.
-    -d, --delimiter <arg>  The field delimiter foDirectedr reading CSV data.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
 ";
 
 #[derive(Deserialize, Debug)]
 struct Args {
+    cmd_corr: bool,
+    cmd_cov: bool,
+    cmd_dist: bool,
+    cmd_contingency: bool,
+    cmd_pca: bool,
+    cmd_mds: bool,
+    arg_row: Option<SelectColumns>,
+    arg_column: Option<SelectColumns>,
     arg_input: Option<String>,
     flag_select: SelectColumns,
     flag_fill_diagonal: bool,
+    flag_method: String,
+    flag_metric: String,
+    flag_id: Option<SelectColumns>,
+    flag_sep: String,
+    flag_long: bool,
+    flag_components: usize,
+    flag_variance: bool,
+    flag_keep: Option<SelectColumns>,
+    flag_scale: bool,
+    flag_loadings: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
     flag_output: Option<String>,
 }
 
+type Labels = Vec<Vec<u8>>;
+
+const JACOBI_MAX_SWEEPS: usize = 100;
+
+fn parse_float(cell: &[u8]) -> CliResult<f64> {
+    Ok(fast_float::parse::<f64, &[u8]>(cell).map_err(|_| {
+        format!(
+            "could not parse cell \"{}\" as a float!",
+            String::from_utf8_lossy(cell)
+        )
+    })?)
+}
+
+fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    let mut welford = CovarianceWelford::new();
+
+    for (a, b) in x.iter().zip(y.iter()) {
+        welford.add(*a, *b);
+    }
+
+    welford.correlation()
+}
+
+// NOTE: tau-b, accounting for ties. Runs in quadratic time.
+fn kendall(x: &[f64], y: &[f64]) -> Option<f64> {
+    let n = x.len();
+
+    let mut concordant: f64 = 0.0;
+    let mut discordant: f64 = 0.0;
+    let mut ties_x: f64 = 0.0;
+    let mut ties_y: f64 = 0.0;
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let dx = x[i] - x[j];
+            let dy = y[i] - y[j];
+
+            if dx == 0.0 && dy == 0.0 {
+                continue;
+            } else if dx == 0.0 {
+                ties_x += 1.0;
+            } else if dy == 0.0 {
+                ties_y += 1.0;
+            } else if (dx > 0.0) == (dy > 0.0) {
+                concordant += 1.0;
+            } else {
+                discordant += 1.0;
+            }
+        }
+    }
+
+    let denominator =
+        ((concordant + discordant + ties_x) * (concordant + discordant + ties_y)).sqrt();
+
+    if denominator == 0.0 {
+        return None;
+    }
+
+    Some((concordant - discordant) / denominator)
+}
+
+fn euclidean(x: &[f64], y: &[f64]) -> Option<f64> {
+    Some(
+        x.iter()
+            .zip(y.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            .sqrt(),
+    )
+}
+
+fn cosine(x: &[f64], y: &[f64]) -> Option<f64> {
+    let mut dot = 0.0;
+    let mut norm_x = 0.0;
+    let mut norm_y = 0.0;
+
+    for (a, b) in x.iter().zip(y.iter()) {
+        dot += a * b;
+        norm_x += a * a;
+        norm_y += b * b;
+    }
+
+    if norm_x == 0.0 || norm_y == 0.0 {
+        return None;
+    }
+
+    Some(dot / (norm_x.sqrt() * norm_y.sqrt()))
+}
+
+fn jaccard(x: &HashSet<Vec<u8>>, y: &HashSet<Vec<u8>>) -> Option<f64> {
+    let union = x.union(y).count();
+
+    if union == 0 {
+        return None;
+    }
+
+    Some(x.intersection(y).count() as f64 / union as f64)
+}
+
+// NOTE: cyclic Jacobi eigenvalue algorithm, returning the eigenvalues of
+// given symmetric matrix, along with their eigenvectors, sorted by decreasing
+// eigenvalue. Eigenvectors are oriented so that their largest component,
+// in absolute value, is positive.
+#[allow(clippy::needless_range_loop)]
+fn symmetric_eigen(mut a: Vec<Vec<f64>>) -> Vec<(f64, Vec<f64>)> {
+    let n = a.len();
+
+    let mut v: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    let norm: f64 = a.iter().flatten().map(|x| x * x).sum();
+
+    for _ in 0..JACOBI_MAX_SWEEPS {
+        let mut off_diagonal = 0.0;
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                off_diagonal += a[p][q] * a[p][q];
+            }
+        }
+
+        if off_diagonal <= norm * f64::EPSILON * f64::EPSILON {
+            break;
+        }
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[p][q] == 0.0 {
+                    continue;
+                }
+
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for k in 0..n {
+                    let (akp, akq) = (a[k][p], a[k][q]);
+                    a[k][p] = c * akp - s * akq;
+                    a[k][q] = s * akp + c * akq;
+                }
+
+                for k in 0..n {
+                    let (apk, aqk) = (a[p][k], a[q][k]);
+                    a[p][k] = c * apk - s * aqk;
+                    a[q][k] = s * apk + c * aqk;
+                }
+
+                for k in 0..n {
+                    let (vkp, vkq) = (v[k][p], v[k][q]);
+                    v[k][p] = c * vkp - s * vkq;
+                    v[k][q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let mut pairs = (0..n)
+        .map(|i| {
+            let mut vector = v.iter().map(|row| row[i]).collect::<Vec<_>>();
+
+            let largest = vector
+                .iter()
+                .copied()
+                .max_by(|x, y| x.abs().total_cmp(&y.abs()))
+                .unwrap_or(0.0);
+
+            if largest < 0.0 {
+                for x in vector.iter_mut() {
+                    *x = -*x;
+                }
+            }
+
+            (a[i][i], vector)
+        })
+        .collect::<Vec<_>>();
+
+    pairs.sort_by(|x, y| y.0.total_cmp(&x.0));
+
+    pairs
+}
+
+struct LabelledMatrix {
+    row_labels: Labels,
+    column_labels: Labels,
+    cells: Vec<Vec<Option<f64>>>,
+    symmetric: bool,
+}
+
+impl LabelledMatrix {
+    // NOTE: the given function is only called for the upper triangle, diagonal
+    // included, of the matrix.
+    fn symmetric<F>(labels: Labels, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> Option<f64>,
+    {
+        let n = labels.len();
+        let mut cells = vec![vec![None; n]; n];
+
+        #[allow(clippy::needless_range_loop)]
+        for i in 0..n {
+            for j in i..n {
+                let value = f(i, j);
+
+                cells[i][j] = value;
+                cells[j][i] = value;
+            }
+        }
+
+        Self {
+            row_labels: labels.clone(),
+            column_labels: labels,
+            cells,
+            symmetric: true,
+        }
+    }
+
+    fn write(&self, output: &Option<String>, long: bool) -> CliResult<()> {
+        let mut writer = Config::new(output).writer()?;
+        let mut record = csv::ByteRecord::new();
+
+        if long {
+            writer.write_record(["source", "target", "value"])?;
+
+            for (i, row) in self.cells.iter().enumerate() {
+                for (j, cell) in row.iter().enumerate() {
+                    if self.symmetric && j <= i {
+                        continue;
+                    }
+
+                    let value = match cell {
+                        Some(value) if self.symmetric || *value != 0.0 => value,
+                        _ => continue,
+                    };
+
+                    record.clear();
+                    record.push_field(&self.row_labels[i]);
+                    record.push_field(&self.column_labels[j]);
+                    record.push_field(value.to_string().as_bytes());
+
+                    writer.write_byte_record(&record)?;
+                }
+            }
+
+            return Ok(writer.flush()?);
+        }
+
+        record.push_field(b"");
+
+        for label in self.column_labels.iter() {
+            record.push_field(label);
+        }
+
+        writer.write_byte_record(&record)?;
+
+        for (row, label) in self.cells.iter().zip(self.row_labels.iter()) {
+            record.clear();
+            record.push_field(label);
+
+            for cell in row {
+                match cell {
+                    None => record.push_field(b""),
+                    Some(f) => record.push_field(f.to_string().as_bytes()),
+                }
+            }
+
+            writer.write_byte_record(&record)?;
+        }
+
+        Ok(writer.flush()?)
+    }
+}
+
 impl Args {
-    fn correlation(&self) -> CliResult<()> {
-        let rconf = Config::new(&self.arg_input)
+    fn selection_config(&self) -> Config {
+        Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
             .no_headers(self.flag_no_headers)
-            .select(self.flag_select.clone());
+            .select(self.flag_select.clone())
+    }
 
+    // NOTE: returns the selected column names & values, column by column
+    fn read_columns(&self) -> CliResult<(Labels, Vec<Vec<f64>>)> {
+        let rconf = self.selection_config();
         let mut reader = rconf.reader()?;
         let headers = reader.byte_headers()?.clone();
 
@@ -55,105 +432,499 @@ impl Args {
             Err("less that 2 columns in selection!")?;
         }
 
-        let mut writer = Config::new(&self.flag_output).writer()?;
-        let mut output_headers = csv::ByteRecord::new();
-        output_headers.push_field(b"");
-        output_headers.extend(sel.select(&headers));
+        let mut columns: Vec<Vec<f64>> = vec![Vec::new(); sel.len()];
+        let mut record = csv::ByteRecord::new();
 
-        writer.write_byte_record(&output_headers)?;
+        while reader.read_byte_record(&mut record)? {
+            for (cell, column) in sel.select(&record).zip(columns.iter_mut()) {
+                column.push(parse_float(cell)?);
+            }
+        }
+
+        Ok((sel.collect(&headers), columns))
+    }
+
+    // NOTE: returns the selected column names & the welfords of their pairs,
+    // in a single pass and constant memory.
+    fn read_welfords(&self) -> CliResult<(Labels, Vec<Vec<CovarianceWelford>>)> {
+        let rconf = self.selection_config();
+        let mut reader = rconf.reader()?;
+        let headers = reader.byte_headers()?.clone();
+
+        let sel = rconf.selection(&headers)?;
+
+        if sel.len() < 2 {
+            Err("less that 2 columns in selection!")?;
+        }
 
         let n = sel.len();
-        let m = (n * (n - 1)) / 2;
 
-        for i in 0..n {
-            let mut row: Vec<Option<CovarianceWelford>> = Vec::with_capacity(n);
+        let mut welfords = vec![vec![CovarianceWelford::new(); n]; n];
+        let mut record = csv::ByteRecord::new();
 
-            for j in 0..n {
-                if i == j {
-                    row.push(None);
+        while reader.read_byte_record(&mut record)? {
+            let values = sel
+                .select(&record)
+                .map(parse_float)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for i in 0..n {
+                for j in i..n {
+                    welfords[i][j].add(values[i], values[j]);
                 }
             }
         }
 
-        let mut welfords: Vec<CovarianceWelford> = Vec::with_capacity(m);
+        Ok((sel.collect(&headers), welfords))
+    }
+
+    fn correlation(&self) -> CliResult<LabelledMatrix> {
+        let mut matrix = match self.flag_method.as_str() {
+            "pearson" => {
+                let (labels, welfords) = self.read_welfords()?;
 
-        for _ in 0..m {
-            welfords.push(CovarianceWelford::new());
+                LabelledMatrix::symmetric(labels, |i, j| welfords[i][j].correlation())
+            }
+            "spearman" => {
+                let (labels, columns) = self.read_columns()?;
+
+                let ranks = columns
+                    .iter()
+                    .map(|column| util::average_ranks(column).0)
+                    .collect::<Vec<_>>();
+
+                LabelledMatrix::symmetric(labels, |i, j| pearson(&ranks[i], &ranks[j]))
+            }
+            "kendall" => {
+                let (labels, columns) = self.read_columns()?;
+
+                LabelledMatrix::symmetric(labels, |i, j| kendall(&columns[i], &columns[j]))
+            }
+            _ => Err(format!(
+                "unknown correlation method \"{}\"!",
+                self.flag_method
+            ))?,
+        };
+
+        for (i, row) in matrix.cells.iter_mut().enumerate() {
+            row[i] = self.flag_fill_diagonal.then_some(1.0);
         }
 
+        Ok(matrix)
+    }
+
+    fn covariance(&self) -> CliResult<LabelledMatrix> {
+        let (labels, welfords) = self.read_welfords()?;
+
+        Ok(LabelledMatrix::symmetric(labels, |i, j| {
+            welfords[i][j].sample_covariance()
+        }))
+    }
+
+    fn distance(&self) -> CliResult<LabelledMatrix> {
+        let rconf = self.selection_config();
+        let mut reader = rconf.reader()?;
+        let headers = reader.byte_headers()?.clone();
+
+        let mut sel = rconf.selection(&headers)?;
+
+        let id_index = self
+            .flag_id
+            .as_ref()
+            .map(|col| -> CliResult<usize> {
+                let index = col.single_selection(&headers, !self.flag_no_headers)?;
+                sel.subtract(&col.selection(&headers, !self.flag_no_headers)?);
+                Ok(index)
+            })
+            .transpose()?;
+
+        let mut labels: Labels = Vec::new();
+        let mut vectors: Vec<Vec<f64>> = Vec::new();
+        let mut token_sets: Vec<HashSet<Vec<u8>>> = Vec::new();
+
+        let jaccard_metric = match self.flag_metric.as_str() {
+            "euclidean" | "cosine" => false,
+            "jaccard" => true,
+            _ => Err(format!("unknown metric \"{}\"!", self.flag_metric))?,
+        };
+
         let mut record = csv::ByteRecord::new();
-        let mut k: usize;
 
         while reader.read_byte_record(&mut record)? {
-            let values = sel
-                .select(&record)
-                .map(|cell| {
-                    fast_float::parse::<f64, &[u8]>(cell).map_err(|_| {
-                        format!(
-                            "could not parse cell \"{}\" as a float!",
-                            std::str::from_utf8(cell).unwrap()
-                        )
-                    })
-                })
-                .collect::<Result<Vec<_>, _>>()?;
+            labels.push(match id_index {
+                Some(index) => record[index].to_vec(),
+                None => labels.len().to_string().into_bytes(),
+            });
+
+            if jaccard_metric {
+                token_sets.push(
+                    sel.select(&record)
+                        .flat_map(|cell| cell.split_str(&self.flag_sep))
+                        .filter(|token| !token.is_empty())
+                        .map(|token| token.to_vec())
+                        .collect(),
+                );
+            } else {
+                vectors.push(
+                    sel.select(&record)
+                        .map(parse_float)
+                        .collect::<Result<Vec<_>, _>>()?,
+                );
+            }
+        }
 
-            k = 0;
+        Ok(match self.flag_metric.as_str() {
+            "euclidean" => {
+                LabelledMatrix::symmetric(labels, |i, j| euclidean(&vectors[i], &vectors[j]))
+            }
+            "cosine" => LabelledMatrix::symmetric(labels, |i, j| cosine(&vectors[i], &vectors[j])),
+            _ => LabelledMatrix::symmetric(labels, |i, j| jaccard(&token_sets[i], &token_sets[j])),
+        })
+    }
 
-            for i in 0..n {
-                for j in (i + 1)..n {
-                    if i == j {
-                        continue;
-                    }
+    fn contingency(&self) -> CliResult<LabelledMatrix> {
+        let rconf = Config::new(&self.arg_input)
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers);
 
-                    welfords[k].add(values[i], values[j]);
+        let mut reader = rconf.reader()?;
+        let headers = reader.byte_headers()?.clone();
 
-                    k += 1;
-                }
+        let row_index = self
+            .arg_row
+            .as_ref()
+            .unwrap()
+            .single_selection(&headers, !self.flag_no_headers)?;
+        let column_index = self
+            .arg_column
+            .as_ref()
+            .unwrap()
+            .single_selection(&headers, !self.flag_no_headers)?;
+
+        let mut row_labels: IndexSet<Vec<u8>> = IndexSet::new();
+        let mut column_labels: IndexSet<Vec<u8>> = IndexSet::new();
+        let mut counts: IndexMap<(usize, usize), u64> = IndexMap::new();
+
+        let mut record = csv::ByteRecord::new();
+
+        while reader.read_byte_record(&mut record)? {
+            let (row_value, column_value) = (&record[row_index], &record[column_index]);
+
+            if row_value.is_empty() || column_value.is_empty() {
+                continue;
             }
+
+            let i = row_labels.insert_full(row_value.to_vec()).0;
+            let j = column_labels.insert_full(column_value.to_vec()).0;
+
+            *counts.entry((i, j)).or_insert(0) += 1;
         }
 
-        let mut correlation_matrix: Vec<Vec<Option<f64>>> = Vec::new();
+        let mut cells = vec![vec![Some(0.0); column_labels.len()]; row_labels.len()];
 
-        for _ in 0..n {
-            correlation_matrix.push(vec![None; n]);
+        for ((i, j), count) in counts {
+            cells[i][j] = Some(count as f64);
         }
 
-        k = 0;
+        Ok(LabelledMatrix {
+            row_labels: row_labels.into_iter().collect(),
+            column_labels: column_labels.into_iter().collect(),
+            cells,
+            symmetric: false,
+        })
+    }
 
-        for i in 0..n {
-            for j in (i + 1)..n {
-                let correlation = welfords[k].correlation();
+    fn components(&self, available: usize) -> CliResult<usize> {
+        if self.flag_components == 0 {
+            Err("-k, --components must be greater than 0!")?;
+        }
+
+        Ok(self.flag_components.min(available))
+    }
+
+    fn write_variance(&self, prefix: &str, eigenvalues: &[f64]) -> CliResult<()> {
+        let mut writer = Config::new(&self.flag_output).writer()?;
+
+        let eigenvalues = eigenvalues.iter().map(|v| v.max(0.0)).collect::<Vec<_>>();
+        let total: f64 = eigenvalues.iter().sum();
+        let mut cumulative = 0.0;
+
+        writer.write_record([
+            "component",
+            "variance",
+            "explained_variance_ratio",
+            "cumulative_explained_variance_ratio",
+        ])?;
+
+        for (i, eigenvalue) in eigenvalues
+            .iter()
+            .take(self.components(eigenvalues.len())?)
+            .enumerate()
+        {
+            let ratio = eigenvalue / total;
+            cumulative += ratio;
+
+            writer.write_record([
+                format!("{}_{}", prefix, i + 1),
+                eigenvalue.to_string(),
+                ratio.to_string(),
+                cumulative.to_string(),
+            ])?;
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    fn pca(&self) -> CliResult<()> {
+        let rconf = self.selection_config();
+        let mut reader = rconf.reader()?;
+        let headers = reader.byte_headers()?.clone();
+
+        let mut sel = rconf.selection(&headers)?;
 
-                correlation_matrix[i][j] = correlation;
-                correlation_matrix[j][i] = correlation;
+        let keep_sel = self
+            .flag_keep
+            .as_ref()
+            .map(|cols| cols.selection(&headers, !self.flag_no_headers))
+            .transpose()?;
+
+        if let Some(ksel) = &keep_sel {
+            sel.subtract(ksel);
+        }
+
+        if sel.is_empty() {
+            Err("no column in selection!")?;
+        }
 
-                k += 1;
+        let mut kept: Vec<Labels> = Vec::new();
+        let mut rows: Vec<Vec<f64>> = Vec::new();
+        let mut record = csv::ByteRecord::new();
+
+        while reader.read_byte_record(&mut record)? {
+            rows.push(
+                sel.select(&record)
+                    .map(parse_float)
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+
+            if let Some(ksel) = &keep_sel {
+                kept.push(ksel.collect(&record));
             }
         }
 
-        for (row, name) in correlation_matrix
-            .into_iter()
-            .zip(output_headers.iter().skip(1))
-        {
+        if rows.len() < 2 {
+            Err("cannot compute principal components of less than 2 rows!")?;
+        }
+
+        let n = rows.len() as f64;
+        let p = sel.len();
+
+        // Centering & scaling columns
+        for j in 0..p {
+            let mean = rows.iter().map(|row| row[j]).sum::<f64>() / n;
+
+            let scale = if self.flag_scale {
+                let variance =
+                    rows.iter().map(|row| (row[j] - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+                if variance == 0.0 {
+                    1.0
+                } else {
+                    variance.sqrt()
+                }
+            } else {
+                1.0
+            };
+
+            for row in rows.iter_mut() {
+                row[j] = (row[j] - mean) / scale;
+            }
+        }
+
+        let covariance = (0..p)
+            .map(|i| {
+                (0..p)
+                    .map(|j| rows.iter().map(|row| row[i] * row[j]).sum::<f64>() / (n - 1.0))
+                    .collect()
+            })
+            .collect();
+
+        let eigen = symmetric_eigen(covariance);
+
+        if self.flag_variance {
+            return self.write_variance(
+                "pc",
+                &eigen.iter().map(|(value, _)| *value).collect::<Vec<_>>(),
+            );
+        }
+
+        let k = self.components(p)?;
+        let component_names = (1..=k).map(|i| format!("pc_{}", i)).collect::<Vec<_>>();
+
+        let mut writer = Config::new(&self.flag_output).writer()?;
+
+        if self.flag_loadings {
+            let mut output_headers = csv::ByteRecord::new();
+            output_headers.push_field(b"column");
+
+            for name in component_names.iter() {
+                output_headers.push_field(name.as_bytes());
+            }
+
+            writer.write_byte_record(&output_headers)?;
+
+            for (j, name) in sel.select(&headers).enumerate() {
+                record.clear();
+                record.push_field(name);
+
+                for (_, vector) in eigen.iter().take(k) {
+                    record.push_field(vector[j].to_string().as_bytes());
+                }
+
+                writer.write_byte_record(&record)?;
+            }
+
+            return Ok(writer.flush()?);
+        }
+
+        let mut output_headers = keep_sel
+            .as_ref()
+            .map(|ksel| ksel.select(&headers).collect::<csv::ByteRecord>())
+            .unwrap_or_default();
+
+        for name in component_names.iter() {
+            output_headers.push_field(name.as_bytes());
+        }
+
+        writer.write_byte_record(&output_headers)?;
+
+        for (i, row) in rows.iter().enumerate() {
             record.clear();
-            record.push_field(name);
 
-            for cell in row {
-                match cell {
-                    None => record.push_field(if self.flag_fill_diagonal { b"1" } else { b"" }),
-                    Some(f) => record.push_field(f.to_string().as_bytes()),
+            if let Some(cells) = kept.get(i) {
+                for cell in cells {
+                    record.push_field(cell);
                 }
             }
 
+            for (_, vector) in eigen.iter().take(k) {
+                let coordinate: f64 = row.iter().zip(vector.iter()).map(|(x, w)| x * w).sum();
+                record.push_field(coordinate.to_string().as_bytes());
+            }
+
             writer.write_byte_record(&record)?;
         }
 
-        Ok(())
+        Ok(writer.flush()?)
+    }
+
+    fn mds(&self) -> CliResult<()> {
+        let rconf = Config::new(&self.arg_input)
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers);
+
+        let mut reader = rconf.reader()?;
+        reader.byte_headers()?;
+
+        let mut labels: Labels = Vec::new();
+        let mut squared_distances: Vec<Vec<f64>> = Vec::new();
+
+        for result in reader.byte_records() {
+            let record = result?;
+
+            labels.push(record[0].to_vec());
+            squared_distances.push(
+                record
+                    .iter()
+                    .skip(1)
+                    .map(|cell| parse_float(cell).map(|d| d * d))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+
+        let n = labels.len();
+
+        if n < 2 || squared_distances.iter().any(|row| row.len() != n) {
+            Err("input should be a square distance matrix, as output by `xan matrix dist`!")?;
+        }
+
+        // Double centering
+        let row_means = squared_distances
+            .iter()
+            .map(|row| row.iter().sum::<f64>() / n as f64)
+            .collect::<Vec<_>>();
+        let grand_mean = row_means.iter().sum::<f64>() / n as f64;
+
+        let b = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        -0.5 * (squared_distances[i][j] - row_means[i] - row_means[j] + grand_mean)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let eigen = symmetric_eigen(b);
+
+        if self.flag_variance {
+            return self.write_variance(
+                "dim",
+                &eigen.iter().map(|(value, _)| *value).collect::<Vec<_>>(),
+            );
+        }
+
+        let k = self.components(n)?;
+
+        let mut writer = Config::new(&self.flag_output).writer()?;
+        let mut record = csv::ByteRecord::new();
+
+        record.push_field(b"name");
+
+        for i in 1..=k {
+            record.push_field(format!("dim_{}", i).as_bytes());
+        }
+
+        writer.write_byte_record(&record)?;
+
+        for (i, label) in labels.iter().enumerate() {
+            record.clear();
+            record.push_field(label);
+
+            for (value, vector) in eigen.iter().take(k) {
+                let coordinate = vector[i] * value.max(0.0).sqrt();
+                record.push_field(coordinate.to_string().as_bytes());
+            }
+
+            writer.write_byte_record(&record)?;
+        }
+
+        Ok(writer.flush()?)
     }
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
-    args.correlation()
+    if args.cmd_pca {
+        return args.pca();
+    }
+
+    if args.cmd_mds {
+        return args.mds();
+    }
+
+    let matrix = if args.cmd_corr {
+        args.correlation()?
+    } else if args.cmd_cov {
+        args.covariance()?
+    } else if args.cmd_dist {
+        args.distance()?
+    } else if args.cmd_contingency {
+        args.contingency()?
+    } else {
+        unreachable!()
+    };
+
+    matrix.write(&args.flag_output, args.flag_long)
 }
@@ -0,0 +1,191 @@
+use regex::bytes::Regex;
+
+use crate::config::{Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Melt a CSV file, i.e. reshape it from wide to long, by emitting one row per
+selected cell, containing the id columns, the name of the cell's column and
+the cell's value.
+
+This is conceptually the inverse of the \"pivot\" command.
+
+For instance the following CSV:
+
+*file.csv*
+country,year_2019,year_2020
+France,66,67
+Italy,59,
+
+Can be melted on the year columns:
+
+    $ xan melt 'year_*' file.csv > melted.csv
+
+To produce the following file:
+
+*melted.csv*
+country,variable,value
+France,year_2019,66
+France,year_2020,67
+Italy,year_2019,59
+Italy,year_2020,
+
+Column names can also be split into several columns using a regex with capture
+groups given to -r, --regex. Named groups will give their name to the new
+columns:
+
+    $ xan melt 'year_*' -r 'year_(?<year>\\d+)' file.csv
+
+country,year,value
+France,2019,66
+...
+
+Usage:
+    xan melt [options] <columns> [<input>]
+    xan melt --help
+
+melt options:
+    -i, --id <cols>             Id columns, that will be repeated for each
+                                emitted row. Defaults to all the columns not
+                                selected as value columns.
+    --variable-name <name>      Name of the column containing the melted
+                                column names. [default: variable]
+    --value-name <name>         Name of the column containing the melted
+                                values. [default: value]
+    -D, --drop-empty            Don't emit rows for empty cells.
+    -r, --regex <pattern>       Split melted column names into several columns
+                                using the capture groups of given regex. Named
+                                groups will give their name to the columns,
+                                while other ones will be named using the
+                                variable name and their position.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers. Melted column names will then be
+                           0-based indices and no header row will be emitted.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: Option<String>,
+    flag_id: Option<SelectColumns>,
+    flag_variable_name: String,
+    flag_value_name: String,
+    flag_drop_empty: bool,
+    flag_regex: Option<String>,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let value_sel = rconf.selection(&headers)?;
+
+    if value_sel.is_empty() {
+        Err("expecting a non-empty column selection")?;
+    }
+
+    let id_sel = match args.flag_id {
+        Some(cols) => cols.selection(&headers, !args.flag_no_headers)?,
+        None => Selection::without_indices(headers.len(), &value_sel),
+    };
+
+    let variables: Vec<Vec<u8>> = if args.flag_no_headers {
+        value_sel
+            .iter()
+            .map(|i| i.to_string().into_bytes())
+            .collect()
+    } else {
+        value_sel.select(&headers).map(|h| h.to_vec()).collect()
+    };
+
+    let mut output_headers = csv::ByteRecord::new();
+    output_headers.extend(id_sel.select(&headers));
+
+    // NOTE: when splitting using a regex, we precompute the captured values
+    // for each melted column name once and for all.
+    let variables: Vec<csv::ByteRecord> = match &args.flag_regex {
+        None => {
+            output_headers.push_field(args.flag_variable_name.as_bytes());
+
+            variables
+                .iter()
+                .map(|v| csv::ByteRecord::from(vec![v.as_slice()]))
+                .collect()
+        }
+        Some(pattern) => {
+            let regex = Regex::new(pattern)?;
+
+            if regex.captures_len() < 2 {
+                Err("-r, --regex pattern should contain at least one capture group!")?;
+            }
+
+            for (i, name) in regex.capture_names().enumerate().skip(1) {
+                match name {
+                    Some(name) => output_headers.push_field(name.as_bytes()),
+                    None => output_headers
+                        .push_field(format!("{}_{}", args.flag_variable_name, i).as_bytes()),
+                }
+            }
+
+            variables
+                .iter()
+                .map(|v| match regex.captures(v) {
+                    None => Err(format!(
+                        "column name \"{}\" does not match -r, --regex pattern!",
+                        String::from_utf8_lossy(v)
+                    )),
+                    Some(caps) => Ok(caps
+                        .iter()
+                        .skip(1)
+                        .map(|m| m.map(|m| m.as_bytes()).unwrap_or(b""))
+                        .collect()),
+                })
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    output_headers.push_field(args.flag_value_name.as_bytes());
+
+    if !args.flag_no_headers {
+        wtr.write_byte_record(&output_headers)?;
+    }
+
+    let mut record = csv::ByteRecord::new();
+    let mut output_record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        for (cell, variable) in value_sel.select(&record).zip(variables.iter()) {
+            if args.flag_drop_empty && cell.is_empty() {
+                continue;
+            }
+
+            output_record.clear();
+            output_record.extend(id_sel.select(&record));
+            output_record.extend(variable);
+            output_record.push_field(cell);
+
+            wtr.write_byte_record(&output_record)?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
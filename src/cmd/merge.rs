@@ -3,7 +3,10 @@ use std::collections::BinaryHeap;
 
 use colored::Colorize;
 
-use crate::cmd::sort::{ComparableByteRecord, NumericallyComparableByteRecord};
+use crate::cmd::sort::{
+    CollatedComparableByteRecord, ComparableByteRecord, NumericallyComparableByteRecord,
+};
+use crate::collation::Collation;
 use crate::config::{Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
@@ -13,7 +16,8 @@ static USAGE: &str = "
 Merge multiple CSV files already sorted the same way. Those files MUST:
 
 1. have the same columns in the same order.
-2. have the same row order wrt -s/--select, -R/--reverse & -N/--numeric
+2. have the same row order wrt -s/--select, -R/--reverse, -N/--numeric, as
+   well as --natural & --collate
 
 If those conditions are not met, the result will be in arbitrary order.
 
@@ -52,6 +56,13 @@ merge options:
                                 See 'xan select --help' for the format details.
     -N, --numeric               Compare according to string numerical value
     -R, --reverse               Reverse order
+    --natural                   Compare strings naturally, i.e. treating sequences of
+                                digits as numbers, so that \"file9\" comes before \"file10\".
+    --collate <locale>          Compare strings using Unicode collation rules tailored
+                                for the given locale, e.g. \"fr\" or \"en\". This means
+                                case & accents will only be used to break ties.
+                                Unknown locales fall back to the root collation.
+                                Can be combined with --natural.
     -u, --uniq                  When set, identical consecutive lines will be dropped
                                 to keep only one line per sorted value.
     -S, --source-column <name>  Name of a column to prepend in the output of the command
@@ -84,6 +95,8 @@ struct Args {
     flag_delimiter: Option<Delimiter>,
     flag_numeric: bool,
     flag_reverse: bool,
+    flag_natural: bool,
+    flag_collate: Option<String>,
     flag_uniq: bool,
     flag_source_column: Option<String>,
     flag_paths: Option<String>,
@@ -97,6 +110,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("--paths cannot be used with other positional arguments!")?;
     }
 
+    if args.flag_numeric && (args.flag_natural || args.flag_collate.is_some()) {
+        Err("-N/--numeric cannot be used with --natural or --collate!")?;
+    }
+
+    let collation = Collation::new(args.flag_natural, args.flag_collate.as_deref())?;
+
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
     let confs = args.configs()?.into_iter().collect::<Vec<Config>>();
@@ -156,7 +175,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     }
 
     macro_rules! kway {
-        ($wrapper:ident, $record:ident) => {
+        ($wrapper:ident, $record:ident $(, $extra:expr)*) => {
             let mut heap: BinaryHeap<($wrapper<$record>, usize)> =
                 BinaryHeap::with_capacity(record_iterators.len());
 
@@ -168,7 +187,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 match iter.next() {
                     None => continue,
                     Some(record) => {
-                        let record = $wrapper($record::new(record?, sel));
+                        let record = $wrapper($record::new(record?, sel $(, $extra)*));
                         heap.push((record, i));
                     }
                 }
@@ -203,7 +222,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         match record_iterators[i].next() {
                             None => continue,
                             Some(record) => {
-                                let record = $wrapper($record::new(record?, &selections[i]));
+                                let record = $wrapper($record::new(
+                                    record?,
+                                    &selections[i]
+                                    $(, $extra)*
+                                ));
                                 heap.push((record, i));
                             }
                         }
@@ -213,6 +236,16 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         };
     }
 
+    if let Some(collation) = &collation {
+        if args.flag_reverse {
+            kway!(Forward, CollatedComparableByteRecord, collation);
+        } else {
+            kway!(Reverse, CollatedComparableByteRecord, collation);
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
     match (args.flag_numeric, args.flag_reverse) {
         (false, false) => {
             kway!(Reverse, ComparableByteRecord);
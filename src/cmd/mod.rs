@@ -28,18 +28,24 @@ pub mod help;
 pub mod hist;
 pub mod implode;
 pub mod input;
+pub mod interval_join;
 pub mod join;
 pub mod map;
 pub mod matrix;
+pub mod melt;
 pub mod merge;
 mod moonblade;
 pub mod network;
+pub mod outliers;
 pub mod parallel;
 pub mod partition;
+pub mod pivot;
 pub mod plot;
+pub mod profile;
 pub mod progress;
 pub mod range;
 pub mod regex_join;
+pub mod regress;
 pub mod rename;
 pub mod reverse;
 pub mod sample;
@@ -51,6 +57,7 @@ pub mod slice;
 pub mod sort;
 pub mod split;
 pub mod stats;
+pub mod test;
 pub mod to;
 pub mod tokenize;
 pub mod top;
@@ -59,3 +66,4 @@ pub mod transpose;
 pub mod url_join;
 pub mod view;
 pub mod vocab;
+pub mod window;
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+
+use crate::config::{Config, Delimiter};
+use crate::moonblade::{OutlierFences, OutlierMethod, OutlierStats};
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+type GroupKey = Vec<Vec<u8>>;
+
+static USAGE: &str = "
+Detect outliers in numeric columns of a CSV file, using one of the following
+methods, given to -m, --method:
+
+    - iqr: values lower than q1 - k * iqr or higher than q3 + k * iqr, where
+      q1 & q3 are the first & third quartiles and iqr is the interquartile
+      range, i.e. q3 - q1. Scores are the distance of values to the nearest
+      quartile, in iqr units. k defaults to 1.5.
+    - zscore: values whose z-score, i.e. their distance to the mean in standard
+      deviations, is higher than k in absolute value. k defaults to 3.
+    - mad: values whose modified z-score, i.e. their distance to the median in
+      median absolute deviations (scaled by 1.4826), is higher than k in absolute
+      value. k defaults to 3.5.
+    - percentile: values lower than the quantile given to --lower or higher
+      than the one given to --upper. This method does not produce scores.
+
+The k threshold can be changed using -t, --threshold.
+
+For each selected column, two columns will be added to the output: one named
+\"<column>_score\", containing the score of the value (except for the percentile
+method), and one named \"<column>_outlier\", containing 1 if the value is an
+outlier and 0 otherwise. Empty & non-numeric cells are ignored and will have an
+empty score & flag.
+
+Rows can also be filtered instead, using the -f, --filter flag to only emit
+rows having at least one outlier, or the -x, --exclude flag to drop them, in
+which case no column will be added.
+
+For instance, to flag outliers of the \"price\" column per product category:
+
+    $ xan outliers -g category price file.csv
+
+By default, the command needs to read its input twice: once to compute the
+statistics of each column and once to flag the rows, which means stdin will
+have to be buffered in memory. Using -a, --approx, the input is only read once,
+each row being compared with the statistics of the rows seen so far, including
+itself, using approximate quantiles. This runs in constant memory, but results
+will be unreliable for the first rows of each group, and cannot work with the
+mad method.
+
+Usage:
+    xan outliers [options] <columns> [<input>]
+    xan outliers --help
+
+outliers options:
+    -m, --method <method>  Method used to detect outliers. Must be one of \"iqr\",
+                           \"zscore\", \"mad\" or \"percentile\". [default: iqr]
+    -t, --threshold <k>    Threshold used by the method. See above for the
+                           defaults.
+    --lower <q>            Lower quantile, between 0 and 1, used by the
+                           percentile method. [default: 0.01]
+    --upper <q>            Upper quantile, between 0 and 1, used by the
+                           percentile method. [default: 0.99]
+    -g, --groupby <cols>   If given, statistics will be computed per group as
+                           defined by the given columns.
+    -f, --filter           Only emit rows having at least one outlier.
+    -x, --exclude          Only emit rows without outliers, without adding
+                           any column.
+    -a, --approx           Only read the input once, comparing each row with
+                           the statistics of the rows seen so far.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: Option<String>,
+    flag_method: String,
+    flag_threshold: Option<f64>,
+    flag_lower: f64,
+    flag_upper: f64,
+    flag_groupby: Option<SelectColumns>,
+    flag_filter: bool,
+    flag_exclude: bool,
+    flag_approx: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+impl Args {
+    fn method(&self) -> CliResult<OutlierMethod> {
+        Ok(match self.flag_method.as_str() {
+            "iqr" => OutlierMethod::Iqr,
+            "zscore" => OutlierMethod::ZScore,
+            "mad" => OutlierMethod::Mad,
+            "percentile" => {
+                if !(0.0..=1.0).contains(&self.flag_lower)
+                    || !(0.0..=1.0).contains(&self.flag_upper)
+                    || self.flag_lower >= self.flag_upper
+                {
+                    Err("--lower & --upper should be quantiles between 0 and 1, with --lower < --upper!")?;
+                }
+
+                OutlierMethod::Percentile(self.flag_lower, self.flag_upper)
+            }
+            _ => Err(format!(
+                "unknown method \"{}\". Must be one of \"iqr\", \"zscore\", \"mad\" or \"percentile\"!",
+                self.flag_method
+            ))?,
+        })
+    }
+}
+
+fn parse_value(cell: &[u8]) -> Option<f64> {
+    fast_float::parse::<f64, &[u8]>(cell)
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+struct Flagger {
+    sel: Selection,
+    method: OutlierMethod,
+    threshold: f64,
+    filter: bool,
+    exclude: bool,
+}
+
+impl Flagger {
+    fn headers(&self, headers: &csv::ByteRecord) -> csv::ByteRecord {
+        let mut output_headers = headers.clone();
+
+        if self.exclude {
+            return output_headers;
+        }
+
+        for name in self.sel.select(headers) {
+            if self.method.has_scores() {
+                output_headers.push_field(&[name, b"_score"].concat());
+            }
+
+            output_headers.push_field(&[name, b"_outlier"].concat());
+        }
+
+        output_headers
+    }
+
+    fn new_stats(&self, approx: bool) -> Vec<OutlierStats> {
+        (0..self.sel.len())
+            .map(|_| OutlierStats::new(self.method, approx))
+            .collect()
+    }
+
+    // NOTE: returns the output record, if the row should be emitted
+    fn flag(
+        &self,
+        record: &csv::ByteRecord,
+        fences: &[Option<OutlierFences>],
+    ) -> Option<csv::ByteRecord> {
+        let mut output_record = record.clone();
+        let mut has_outlier = false;
+
+        for (cell, fences_opt) in self.sel.select(record).zip(fences.iter()) {
+            let value_and_fences = parse_value(cell).zip(fences_opt.as_ref());
+
+            if self.method.has_scores() {
+                match value_and_fences.and_then(|(value, fences)| fences.score(value)) {
+                    Some(score) => output_record.push_field(score.to_string().as_bytes()),
+                    None => output_record.push_field(b""),
+                }
+            }
+
+            match value_and_fences {
+                Some((value, fences)) => {
+                    if fences.is_outlier(value, self.threshold) {
+                        has_outlier = true;
+                        output_record.push_field(b"1");
+                    } else {
+                        output_record.push_field(b"0");
+                    }
+                }
+                None => output_record.push_field(b""),
+            }
+        }
+
+        if self.exclude {
+            return (!has_outlier).then(|| record.clone());
+        }
+
+        if self.filter && !has_outlier {
+            return None;
+        }
+
+        Some(output_record)
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let method = args.method()?;
+
+    if args.flag_filter && args.flag_exclude {
+        Err("-f, --filter & -x, --exclude are mutually exclusive!")?;
+    }
+
+    if args.flag_approx && matches!(method, OutlierMethod::Mad) {
+        Err("-a, --approx cannot work with the mad method!")?;
+    }
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns.clone());
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let groupby_sel = args
+        .flag_groupby
+        .as_ref()
+        .map(|cols| cols.selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    let group_key = |record: &csv::ByteRecord| -> GroupKey {
+        groupby_sel
+            .as_ref()
+            .map(|gsel| gsel.collect(record))
+            .unwrap_or_default()
+    };
+
+    let flagger = Flagger {
+        sel,
+        method,
+        threshold: args
+            .flag_threshold
+            .unwrap_or_else(|| method.default_threshold()),
+        filter: args.flag_filter,
+        exclude: args.flag_exclude,
+    };
+
+    if !args.flag_no_headers {
+        wtr.write_byte_record(&flagger.headers(&headers))?;
+    }
+
+    let mut record = csv::ByteRecord::new();
+
+    // Single pass, comparing each row with the rows seen so far
+    if args.flag_approx {
+        let mut groups: HashMap<GroupKey, Vec<OutlierStats>> = HashMap::new();
+
+        while rdr.read_byte_record(&mut record)? {
+            let stats = groups
+                .entry(group_key(&record))
+                .or_insert_with(|| flagger.new_stats(true));
+
+            let fences = flagger
+                .sel
+                .select(&record)
+                .zip(stats.iter_mut())
+                .map(|(cell, column_stats)| {
+                    if let Some(value) = parse_value(cell) {
+                        column_stats.add(value);
+                    }
+
+                    column_stats.fences()
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(output_record) = flagger.flag(&record, &fences) {
+                wtr.write_byte_record(&output_record)?;
+            }
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    // First pass, computing statistics
+    let mut buffer: Option<Vec<csv::ByteRecord>> = rconf.is_std().then(Vec::new);
+    let mut groups: HashMap<GroupKey, Vec<OutlierStats>> = HashMap::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        let stats = groups
+            .entry(group_key(&record))
+            .or_insert_with(|| flagger.new_stats(false));
+
+        for (cell, column_stats) in flagger.sel.select(&record).zip(stats.iter_mut()) {
+            if let Some(value) = parse_value(cell) {
+                column_stats.add(value);
+            }
+        }
+
+        if let Some(records) = buffer.as_mut() {
+            records.push(record.clone());
+        }
+    }
+
+    let groups: HashMap<GroupKey, Vec<Option<OutlierFences>>> = groups
+        .into_iter()
+        .map(|(group, stats)| {
+            (
+                group,
+                stats
+                    .into_iter()
+                    .map(|mut column_stats| column_stats.fences())
+                    .collect(),
+            )
+        })
+        .collect();
+
+    // Second pass, flagging rows
+    let mut flag_and_write = |record: &csv::ByteRecord| -> CliResult<()> {
+        if let Some(output_record) = flagger.flag(record, &groups[&group_key(record)]) {
+            wtr.write_byte_record(&output_record)?;
+        }
+
+        Ok(())
+    };
+
+    match buffer {
+        Some(records) => {
+            for record in records.iter() {
+                flag_and_write(record)?;
+            }
+        }
+        None => {
+            let mut rdr = rconf.reader()?;
+
+            while rdr.read_byte_record(&mut record)? {
+                flag_and_write(&record)?;
+            }
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -14,7 +14,7 @@ use rayon::{prelude::*, ThreadPoolBuilder};
 use crate::cmd::progress::get_progress_style;
 use crate::config::{Config, Delimiter};
 use crate::moonblade::{AggregationProgram, GroupAggregationProgram, Stats};
-use crate::select::SelectColumns;
+use crate::select::{SelectColumns, Selection};
 use crate::util;
 use crate::CliResult;
 
@@ -193,15 +193,15 @@ impl From<Vec<Child>> for Children {
 
 #[derive(Default)]
 struct FrequencyTable {
-    map: HashMap<Vec<u8>, u64>,
+    map: HashMap<Vec<u8>, f64>,
 }
 
 impl FrequencyTable {
-    fn inc(&mut self, key: Vec<u8>) {
-        self.add(key, 1);
-    }
+    fn add(&mut self, key: Vec<u8>, count: f64) {
+        if count == 0.0 {
+            return;
+        }
 
-    fn add(&mut self, key: Vec<u8>, count: u64) {
         self.map
             .entry(key)
             .and_modify(|current_count| *current_count += count)
@@ -261,10 +261,10 @@ impl FrequencyTables {
         Ok(())
     }
 
-    fn into_sorted(self) -> impl Iterator<Item = (Vec<u8>, Vec<(Vec<u8>, u64)>)> {
+    fn into_sorted(self) -> impl Iterator<Item = (Vec<u8>, Vec<(Vec<u8>, f64)>)> {
         self.tables.into_iter().map(|(name, table)| {
             let mut items: Vec<_> = table.map.into_iter().collect();
-            items.par_sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+            items.par_sort_unstable_by(|a, b| b.1.total_cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
 
             (name, items)
         })
@@ -416,9 +416,13 @@ parallel freq options:
     -s, --select <cols>  Columns for which to build frequency tables.
     --sep <char>         Split the cell into multiple values to count using the
                          provided separator.
+    -w, --weight <col>   Column containing row weights, summed instead of
+                         counting rows.
 
 parallel stats options:
     -s, --select <cols>    Columns for which to build statistics.
+    -w, --weight <col>     Column containing row weights. See \"xan stats -h\"
+                           for the statistics taking them into account.
     -A, --all              Shorthand for -cq.
     -c, --cardinality      Show cardinality and modes.
                            This requires storing all CSV data in memory.
@@ -458,6 +462,7 @@ struct Args {
     flag_source_column: Option<String>,
     flag_select: SelectColumns,
     flag_sep: Option<String>,
+    flag_weight: Option<SelectColumns>,
     flag_all: bool,
     flag_cardinality: bool,
     flag_quartiles: bool,
@@ -478,6 +483,10 @@ impl Args {
             stats.include_nulls();
         }
 
+        if self.flag_weight.is_some() {
+            stats.use_weights();
+        }
+
         if self.flag_all || self.flag_cardinality {
             stats.compute_frequencies();
         }
@@ -493,6 +502,22 @@ impl Args {
         stats
     }
 
+    // NOTE: the weight column is also removed from the given selection.
+    fn weight_index(
+        &self,
+        headers: &csv::ByteRecord,
+        sel: &mut Selection,
+    ) -> CliResult<Option<usize>> {
+        Ok(match &self.flag_weight {
+            Some(col) => {
+                let index = col.single_selection(headers, true)?;
+                sel.subtract(&col.selection(headers, true)?);
+                Some(index)
+            }
+            None => None,
+        })
+    }
+
     fn inputs(&self) -> CliResult<Vec<String>> {
         if !self.arg_inputs.is_empty() {
             Ok(self.arg_inputs.clone())
@@ -802,20 +827,26 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             let bar = progress_bar.start(path);
 
             let headers = reader.byte_headers()?.clone();
-            let sel = args.flag_select.selection(&headers, true)?;
+            let mut sel = args.flag_select.selection(&headers, true)?;
+            let weight_index = args.weight_index(&headers, &mut sel)?;
 
             let mut freq_tables = FrequencyTables::with_capacity(sel.collect(&headers));
 
             let mut record = csv::ByteRecord::new();
 
             while reader.read_byte_record(&mut record)? {
+                let weight = match weight_index {
+                    Some(index) => util::parse_weight(&record[index])?,
+                    None => 1.0,
+                };
+
                 for (table, cell) in freq_tables.iter_mut().zip(sel.select(&record)) {
                     if let Some(sep) = &args.flag_sep {
                         for subcell in cell.split_str(sep) {
-                            table.inc(subcell.to_vec());
+                            table.add(subcell.to_vec(), weight);
                         }
                     } else {
-                        table.inc(cell.to_vec());
+                        table.add(cell.to_vec(), weight);
                     }
                 }
 
@@ -868,15 +899,21 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             let bar = progress_bar.start(path);
 
             let headers = reader.byte_headers()?.clone();
-            let sel = args.flag_select.selection(&headers, true)?;
+            let mut sel = args.flag_select.selection(&headers, true)?;
+            let weight_index = args.weight_index(&headers, &mut sel)?;
 
             let mut local_stats =
                 StatsTables::with_capacity(sel.collect(&headers), || args.new_stats());
             let mut record = csv::ByteRecord::new();
 
             while reader.read_byte_record(&mut record)? {
+                let weight = match weight_index {
+                    Some(index) => util::parse_weight(&record[index])?,
+                    None => 1.0,
+                };
+
                 for (cell, stats) in sel.select(&record).zip(local_stats.iter_mut()) {
-                    stats.process(cell);
+                    stats.process_with_weight(cell, weight);
                 }
 
                 ParallelProgressBar::tick(&bar);
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use indexmap::IndexSet;
+
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+use crate::moonblade::GroupAggregationProgram;
+
+use crate::cmd::moonblade::MoonbladeErrorPolicy;
+
+static USAGE: &str = "
+Pivot a CSV file, i.e. reshape it from long to wide, by turning the distinct
+values of a column into new columns, filled by evaluating an aggregation
+expression on the rows sharing the same index & pivot values.
+
+This is conceptually the inverse of the \"melt\" command.
+
+For instance, given the following file:
+
+country,year,population
+France,2020,67
+France,2021,68
+Italy,2020,59
+
+Running the following command:
+
+    $ xan pivot year 'sum(population)' -g country file.csv
+
+Will produce:
+
+country,2020,2021
+France,67,68
+Italy,59,
+
+When the expression computes multiple aggregations, new columns will be named
+after both the pivot value and the aggregation name, e.g. \"2020_min\" and
+\"2020_max\".
+
+If no index columns are given using -g, --groupby, the whole file will be
+aggregated into a single row.
+
+For a quick review of the capabilities of the expression language,
+check out the `xan help cheatsheet` command.
+
+For a list of available aggregation functions, use `xan help aggs`.
+
+Usage:
+    xan pivot [options] <column> <expression> [<input>]
+    xan pivot --help
+
+pivot options:
+    -g, --groupby <cols>    Index columns, whose values will identify the
+                            rows of the output.
+    -F, --fill <value>      Value used to fill the cells for which no row
+                            exists in the input. [default: ]
+    --sort-headers          Sort the new columns lexicographically instead of
+                            keeping the order in which values were first seen.
+    -e, --errors <policy>   What to do with evaluation errors. One of:
+                              - \"panic\": exit on first error
+                              - \"ignore\": ignore row altogether
+                              - \"log\": print error to stderr
+                            [default: panic].
+
+Common options:
+    -h, --help               Display this message
+    -o, --output <file>      Write output to <file> instead of stdout.
+    -n, --no-headers         When set, the first row will not be evaled
+                             as headers.
+    -d, --delimiter <arg>    The field delimiter for reading CSV data.
+                             Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_expression: String,
+    arg_input: Option<String>,
+    flag_groupby: Option<SelectColumns>,
+    flag_fill: String,
+    flag_sort_headers: bool,
+    flag_errors: String,
+    flag_no_headers: bool,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let error_policy = MoonbladeErrorPolicy::try_from_restricted(&args.flag_errors)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let pivot_index = args
+        .arg_column
+        .single_selection(&headers, !args.flag_no_headers)?;
+
+    let index_sel = args
+        .flag_groupby
+        .map(|cols| cols.selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    if let Some(sel) = &index_sel {
+        if sel.contains(pivot_index) {
+            Err("pivot column cannot be part of the -g, --groupby selection!")?;
+        }
+    }
+
+    let mut program = GroupAggregationProgram::parse(&args.arg_expression, &headers)?;
+
+    let aggregation_names = program.headers().map(|h| h.to_vec()).collect::<Vec<_>>();
+
+    let mut index_keys: IndexSet<Vec<Vec<u8>>> = IndexSet::new();
+    let mut pivot_values: IndexSet<Vec<u8>> = IndexSet::new();
+
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        let index_key = index_sel
+            .as_ref()
+            .map(|sel| sel.collect(&record))
+            .unwrap_or_default();
+        let pivot_value = record[pivot_index].to_vec();
+
+        let mut group = index_key.clone();
+        group.push(pivot_value.clone());
+
+        program
+            .run_with_record(group, index, &record)
+            .or_else(|error| error_policy.handle_row_error(index, error))?;
+
+        index_keys.insert(index_key);
+        pivot_values.insert(pivot_value);
+
+        index += 1;
+    }
+
+    if args.flag_sort_headers {
+        pivot_values.sort_unstable();
+    }
+
+    let mut cells: HashMap<(usize, usize), csv::ByteRecord> = HashMap::new();
+
+    for result in program.into_byte_records(false) {
+        let (mut group, group_record) = error_policy.handle_error(result)?;
+
+        let pivot_value = group.pop().unwrap();
+
+        // NOTE: rows ignored because of errors may not have yielded a group
+        if let (Some(i), Some(j)) = (
+            index_keys.get_index_of(&group),
+            pivot_values.get_index_of(&pivot_value),
+        ) {
+            cells.insert((i, j), group_record);
+        }
+    }
+
+    let mut output_headers = csv::ByteRecord::new();
+
+    if let Some(sel) = &index_sel {
+        output_headers.extend(sel.select(&headers));
+    }
+
+    for pivot_value in pivot_values.iter() {
+        if aggregation_names.len() == 1 {
+            output_headers.push_field(pivot_value);
+        } else {
+            for name in aggregation_names.iter() {
+                let mut header = pivot_value.clone();
+                header.push(b'_');
+                header.extend(name);
+                output_headers.push_field(&header);
+            }
+        }
+    }
+
+    wtr.write_byte_record(&output_headers)?;
+
+    for (i, index_key) in index_keys.iter().enumerate() {
+        record.clear();
+        record.extend(index_key);
+
+        for j in 0..pivot_values.len() {
+            match cells.get(&(i, j)) {
+                Some(group_record) => record.extend(group_record),
+                None => {
+                    for _ in 0..aggregation_names.len() {
+                        record.push_field(args.flag_fill.as_bytes());
+                    }
+                }
+            }
+        }
+
+        wtr.write_byte_record(&record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
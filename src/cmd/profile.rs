@@ -0,0 +1,582 @@
+use colored::Colorize;
+use jiff::fmt::strtime;
+
+use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::moonblade::agg::aggregators::{
+    ApproxCardinality, ApproxQuantiles, Extent, Frequencies, MedianType, Numbers, NumericExtent,
+};
+use crate::moonblade::DynamicNumber;
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+// NOTE: order matters, since the first format matching every date value of
+// a column will be the reported one.
+static DATE_FORMATS: [&str; 19] = [
+    "%Y-%m-%d",
+    "%Y-%m",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%SZ",
+    "%Y-%m-%dT%H:%M:%S%.fZ",
+    "%Y-%m-%dT%H:%M:%S%:z",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%d/%m/%Y %H:%M",
+    "%d-%m-%Y",
+    "%d.%m.%Y",
+    "%d %B %Y",
+    "%B %d, %Y",
+    "%d %b %Y",
+    "%a, %d %b %Y %H:%M:%S %z",
+];
+
+// NOTE: when approximating, shapes are only counted until this many distinct
+// ones have been seen, to keep memory bounded.
+const MAX_APPROX_SHAPES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Int,
+    Float,
+    Boolean,
+    Date,
+    Url,
+    Email,
+    String,
+}
+
+impl Kind {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::Url => "url",
+            Self::Email => "email",
+            Self::String => "string",
+        }
+    }
+}
+
+static KINDS: [Kind; 7] = [
+    Kind::Int,
+    Kind::Float,
+    Kind::Boolean,
+    Kind::Date,
+    Kind::Url,
+    Kind::Email,
+    Kind::String,
+];
+
+fn could_be_boolean(string: &str) -> bool {
+    ["true", "false", "yes", "no"]
+        .iter()
+        .any(|b| string.eq_ignore_ascii_case(b))
+}
+
+fn date_formats_mask(string: &str) -> u32 {
+    let mut mask = 0;
+
+    for (i, format) in DATE_FORMATS.iter().enumerate() {
+        if strtime::parse(format, string).is_ok() {
+            mask |= 1 << i;
+        }
+    }
+
+    mask
+}
+
+#[derive(Debug)]
+struct Profile {
+    count: usize,
+    count_empty: usize,
+    kinds: [usize; 7],
+    date_formats: u32,
+    length_extent: Extent<usize>,
+    extent: NumericExtent,
+    numbers: Option<Numbers>,
+    approx_quantiles: Option<Box<ApproxQuantiles>>,
+    frequencies: Option<Frequencies>,
+    approx_cardinality: Option<Box<ApproxCardinality>>,
+    shapes: Frequencies,
+    samples: Vec<String>,
+}
+
+impl Profile {
+    fn new(approx: bool) -> Self {
+        Self {
+            count: 0,
+            count_empty: 0,
+            kinds: [0; 7],
+            date_formats: (1 << DATE_FORMATS.len()) - 1,
+            length_extent: Extent::new(),
+            extent: NumericExtent::new(),
+            numbers: (!approx).then(Numbers::new),
+            approx_quantiles: approx.then(|| Box::new(ApproxQuantiles::new())),
+            frequencies: (!approx).then(Frequencies::new),
+            approx_cardinality: approx.then(|| Box::new(ApproxCardinality::new())),
+            shapes: Frequencies::new(),
+            samples: Vec::new(),
+        }
+    }
+
+    fn process(&mut self, cell: &str, args: &Args) {
+        if cell.is_empty() {
+            self.count_empty += 1;
+            return;
+        }
+
+        self.count += 1;
+        self.length_extent.add(cell.chars().count());
+
+        let kind = if let Ok(number) = cell.parse::<DynamicNumber>() {
+            if !number.is_nan() {
+                self.extent.add(number);
+
+                if let Some(numbers) = self.numbers.as_mut() {
+                    numbers.add(number);
+                }
+
+                if let Some(approx_quantiles) = self.approx_quantiles.as_mut() {
+                    approx_quantiles.add(number.as_float());
+                }
+            }
+
+            if number.is_float() {
+                Kind::Float
+            } else {
+                Kind::Int
+            }
+        } else if could_be_boolean(cell) {
+            Kind::Boolean
+        } else {
+            let mask = date_formats_mask(cell);
+
+            if mask != 0 || dates::could_be_date(cell) {
+                self.date_formats &= mask;
+                Kind::Date
+            } else if util::could_be_url(cell) {
+                Kind::Url
+            } else if util::could_be_email(cell) {
+                Kind::Email
+            } else {
+                Kind::String
+            }
+        };
+
+        self.kinds[kind as usize] += 1;
+
+        let shape = util::value_shape(cell, args.flag_collapse);
+
+        if self.approx_cardinality.is_none()
+            || self.shapes.cardinality() < MAX_APPROX_SHAPES
+            || self.shapes.contains(&shape)
+        {
+            self.shapes.add(shape);
+        }
+
+        if let Some(frequencies) = self.frequencies.as_mut() {
+            frequencies.add(cell.to_string());
+        }
+
+        if let Some(approx_cardinality) = self.approx_cardinality.as_mut() {
+            approx_cardinality.add(cell);
+        }
+
+        if self.samples.len() < args.flag_samples && !self.samples.iter().any(|s| s == cell) {
+            self.samples.push(cell.to_string());
+        }
+    }
+
+    fn has(&self, kind: Kind) -> bool {
+        self.kinds[kind as usize] > 0
+    }
+
+    fn types(&self) -> Vec<&'static str> {
+        KINDS
+            .iter()
+            .filter(|k| self.has(**k))
+            .map(|k| k.as_str())
+            .collect()
+    }
+
+    fn most_likely_type(&self) -> Option<Kind> {
+        let witnessed = KINDS.iter().filter(|k| self.has(**k)).collect::<Vec<_>>();
+
+        match witnessed.as_slice() {
+            [] => None,
+            [kind] => Some(**kind),
+            [Kind::Int, Kind::Float] => Some(Kind::Float),
+            _ => Some(Kind::String),
+        }
+    }
+
+    fn finalize(mut self, name: &[u8], max_shapes: usize) -> ProfileResult {
+        let kind = self.most_likely_type();
+
+        let date_format = if kind == Some(Kind::Date) && self.date_formats != 0 {
+            Some(DATE_FORMATS[self.date_formats.trailing_zeros() as usize])
+        } else {
+            None
+        };
+
+        let cardinality = match self.approx_cardinality.as_mut() {
+            Some(approx_cardinality) => {
+                approx_cardinality.finalize();
+                approx_cardinality.get().min(self.count)
+            }
+            None => self.frequencies.as_ref().unwrap().cardinality(),
+        };
+
+        let is_numeric = matches!(kind, Some(Kind::Int | Kind::Float));
+
+        let median = if !is_numeric {
+            None
+        } else if let Some(numbers) = self.numbers.as_mut() {
+            numbers.finalize(false);
+            numbers
+                .median(&MedianType::Interpolation)
+                .map(|n| n.to_string())
+        } else {
+            let approx_quantiles = self.approx_quantiles.as_mut().unwrap();
+            approx_quantiles.finalize();
+            Some(approx_quantiles.get(0.5).to_string())
+        };
+
+        let total = self.count + self.count_empty;
+
+        ProfileResult {
+            name: String::from_utf8_lossy(name).into_owned(),
+            kind: kind.map(|k| k.as_str().to_string()),
+            types: self.types(),
+            date_format,
+            count: self.count,
+            count_empty: self.count_empty,
+            null_ratio: (total > 0).then(|| self.count_empty as f64 / total as f64),
+            cardinality,
+            distinctness: (self.count > 0).then(|| cardinality as f64 / self.count as f64),
+            length_extent: self.length_extent.min().zip(self.length_extent.max()),
+            extent: if is_numeric {
+                self.extent.min().zip(self.extent.max())
+            } else {
+                None
+            },
+            median,
+            shapes: self
+                .shapes
+                .most_common(max_shapes)
+                .into_iter()
+                .zip(self.shapes.most_common_counts(max_shapes))
+                .collect(),
+            samples: self.samples,
+        }
+    }
+}
+
+struct ProfileResult {
+    name: String,
+    kind: Option<String>,
+    types: Vec<&'static str>,
+    date_format: Option<&'static str>,
+    count: usize,
+    count_empty: usize,
+    null_ratio: Option<f64>,
+    cardinality: usize,
+    distinctness: Option<f64>,
+    length_extent: Option<(usize, usize)>,
+    extent: Option<(DynamicNumber, DynamicNumber)>,
+    median: Option<String>,
+    shapes: Vec<(String, u64)>,
+    samples: Vec<String>,
+}
+
+impl ProfileResult {
+    fn headers() -> csv::ByteRecord {
+        csv::ByteRecord::from(vec![
+            "field",
+            "type",
+            "types",
+            "date_format",
+            "count",
+            "count_empty",
+            "null_ratio",
+            "cardinality",
+            "distinctness",
+            "min_length",
+            "max_length",
+            "min",
+            "max",
+            "median",
+            "shapes",
+            "samples",
+        ])
+    }
+
+    fn to_byte_record(&self, sep: &str) -> csv::ByteRecord {
+        fn field<T: ToString>(opt: Option<T>) -> String {
+            opt.map(|v| v.to_string()).unwrap_or_default()
+        }
+
+        csv::ByteRecord::from(vec![
+            self.name.clone(),
+            field(self.kind.as_ref()),
+            self.types.join(sep),
+            field(self.date_format),
+            self.count.to_string(),
+            self.count_empty.to_string(),
+            field(self.null_ratio),
+            self.cardinality.to_string(),
+            field(self.distinctness),
+            field(self.length_extent.map(|(min, _)| min)),
+            field(self.length_extent.map(|(_, max)| max)),
+            field(self.extent.map(|(min, _)| min)),
+            field(self.extent.map(|(_, max)| max)),
+            field(self.median.as_ref()),
+            self.shapes
+                .iter()
+                .map(|(shape, _)| shape.as_str())
+                .collect::<Vec<_>>()
+                .join(sep),
+            self.samples.join(sep),
+        ])
+    }
+
+    fn print_report(&self, cols: usize) {
+        fn percent(ratio: f64) -> String {
+            format!("{:.2}%", ratio * 100.0)
+        }
+
+        let print_line = |label: &str, value: String| {
+            println!(
+                "  {} {}",
+                format!("{:<12}", label).cyan(),
+                util::unicode_aware_ellipsis(&value, cols.saturating_sub(15))
+            );
+        };
+
+        println!("{}", self.name.bold());
+
+        let kind = match (&self.kind, self.date_format) {
+            (None, _) => "<empty>".dimmed().to_string(),
+            (Some(kind), Some(format)) => format!("{} ({})", kind, format),
+            (Some(kind), None) => kind.to_string(),
+        };
+
+        print_line("type", kind);
+
+        if self.types.len() > 1 {
+            print_line("types", self.types.join(", "));
+        }
+
+        print_line(
+            "nulls",
+            format!(
+                "{} ({})",
+                util::format_number(self.count_empty),
+                percent(self.null_ratio.unwrap_or(0.0))
+            ),
+        );
+
+        if let Some(distinctness) = self.distinctness {
+            print_line(
+                "distinct",
+                format!(
+                    "{} ({})",
+                    util::format_number(self.cardinality),
+                    percent(distinctness)
+                ),
+            );
+        }
+
+        if let Some((min, max)) = self.length_extent {
+            print_line("length", format!("{} to {}", min, max));
+        }
+
+        if let Some((min, max)) = self.extent {
+            print_line(
+                "range",
+                format!(
+                    "{} to {} (median {})",
+                    min,
+                    max,
+                    self.median.as_deref().unwrap_or("")
+                ),
+            );
+        }
+
+        if !self.shapes.is_empty() {
+            print_line(
+                "shapes",
+                self.shapes
+                    .iter()
+                    .map(|(shape, count)| {
+                        format!("{} ({})", shape, percent(*count as f64 / self.count as f64))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+
+        if !self.samples.is_empty() {
+            print_line("samples", self.samples.join(", "));
+        }
+
+        println!();
+    }
+}
+
+static USAGE: &str = "
+Profile the columns of a CSV file, reporting for each of them its most
+likely type (int, float, boolean, date, url, email or string), its ratio of
+empty values, its number of distinct values, the extent of its string lengths,
+its most frequent value shapes and some sample values.
+
+When the most likely type is a date, the command will also try to report the
+format shared by all values of the column. When the column is numeric, its min,
+max & median values will also be reported.
+
+Value shapes are computed by masking uppercase letters as \"A\", other letters
+as \"a\" and digits as \"9\", so that \"AB-123\" has shape \"AA-999\". Repeated
+mask characters can be squeezed using the --collapse flag, e.g. \"Hello world\"
+will then have shape \"Aa a\".
+
+Since computing exact cardinalities & medians requires storing all values in
+memory, you can use the -a/--approx flag to rely on HyperLogLog & t-digest
+sketches instead, in constant memory.
+
+Here is what the CSV output will look like:
+
+field        - Name of the profiled column
+type         - Most likely type of the column
+types        - List of all types witnessed in the column
+date_format  - Format shared by all dates of the column, if any
+count        - Number of non-empty values contained by the column
+count_empty  - Number of empty values contained by the column
+null_ratio   - Ratio of empty values contained by the column
+cardinality  - Number of distinct non-empty values
+distinctness - Ratio of distinct values over non-empty values
+min_length   - Minimum number of characters of non-empty values
+max_length   - Maximum number of characters of non-empty values
+min          - Minimum value, if the column is numeric
+max          - Maximum value, if the column is numeric
+median       - Median value, if the column is numeric
+shapes       - List of most frequent value shapes
+samples      - List of the first distinct values
+
+Lists are joined using a pipe character (\"|\") by default, which can be
+changed using the --sep flag if your values may contain pipes.
+
+Usage:
+    xan profile [options] [<input>]
+    xan profile --help
+
+profile options:
+    -s, --select <arg>     Select a subset of columns to profile.
+                           See 'xan select --help' for the format details.
+    -a, --approx           Approximate cardinalities & medians using sketches,
+                           to run in constant memory.
+    -R, --report           Print a human-readable report in the terminal
+                           instead of CSV output.
+    --shapes <n>           Number of most frequent value shapes to report.
+                           [default: 3]
+    --samples <n>          Number of distinct sample values to report.
+                           [default: 3]
+    --collapse             Squeeze repeated characters of value shapes.
+    --sep <sep>            Separator used to join lists of types, shapes &
+                           samples in the CSV output. [default: |]
+    --cols <num>           Width of the report in terminal columns.
+                           Defaults to using all your terminal's width.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will NOT be interpreted
+                           as column names.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_approx: bool,
+    flag_report: bool,
+    flag_shapes: usize,
+    flag_samples: usize,
+    flag_collapse: bool,
+    flag_sep: String,
+    flag_cols: Option<usize>,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_report && args.flag_output.is_some() {
+        Err("-R/--report cannot be used with -o/--output!")?;
+    }
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select.clone());
+
+    let mut rdr = rconf.reader()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let field_names: Vec<Vec<u8>> = if args.flag_no_headers {
+        sel.iter()
+            .map(|i| i.to_string().as_bytes().to_vec())
+            .collect()
+    } else {
+        sel.select(&headers).map(|h| h.to_vec()).collect()
+    };
+
+    let mut profiles = (0..sel.len())
+        .map(|_| Profile::new(args.flag_approx))
+        .collect::<Vec<_>>();
+
+    let mut record = csv::StringRecord::new();
+
+    while rdr.read_record(&mut record)? {
+        for (cell, profile) in sel.select_string_record(&record).zip(profiles.iter_mut()) {
+            profile.process(cell, &args);
+        }
+    }
+
+    let results = field_names
+        .iter()
+        .zip(profiles)
+        .map(|(name, profile)| profile.finalize(name, args.flag_shapes));
+
+    if args.flag_report {
+        let cols = util::acquire_term_cols(&args.flag_cols);
+
+        for result in results {
+            result.print_report(cols);
+        }
+
+        return Ok(());
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    wtr.write_byte_record(&ProfileResult::headers())?;
+
+    for result in results {
+        wtr.write_byte_record(&result.to_byte_record(&args.flag_sep))?;
+    }
+
+    Ok(wtr.flush()?)
+}
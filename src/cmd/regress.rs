@@ -0,0 +1,555 @@
+use indexmap::IndexSet;
+
+use crate::config::{Config, Delimiter};
+use crate::distributions::{normal_sf, student_t_two_sided};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Fit a regression model predicting a target column from one or more predictor
+columns of a CSV file.
+
+By default, an ordinary least squares linear regression will be fitted, but
+a logistic regression can be fitted instead using the -l, --logistic flag, in
+which case the target column must only contain 0 or 1 values.
+
+Predictors are expected to be numerical, unless given to -c, --categorical,
+in which case they will be one-hot encoded, using their first value (in order
+of appearance in the file) as the reference level.
+
+The command outputs one row per term of the model, with the following columns:
+
+    - term: name of the term, \"(intercept)\", the predictor's name, or
+      \"<predictor>=<level>\" for categorical predictors.
+    - coefficient: estimated coefficient of the term.
+    - std_error: standard error of the coefficient.
+    - statistic: t value of the coefficient for linear regressions, and its
+      z value (Wald statistic) for logistic ones.
+    - p_value: two-sided p-value of the statistic.
+    - n: number of rows used to fit the model.
+    - r_squared: coefficient of determination of linear regressions, or
+      McFadden's pseudo R² for logistic ones.
+    - adjusted_r_squared: adjusted version of the above.
+
+Rows having an empty value in the target or any predictor column are ignored.
+
+For instance, to predict \"price\" from \"surface\" & \"city\":
+
+    $ xan regress -c city price surface,city file.csv
+
+Using -F, --fitted, the model will instead be used to append a \"fitted\" &
+a \"residual\" column to each row of the file (the fitted value being a
+probability, for logistic regressions). Ignored rows will have empty values.
+
+Note that the whole file will be loaded into memory.
+
+Usage:
+    xan regress [options] <target> <predictors> [<input>]
+    xan regress --help
+
+regress options:
+    -l, --logistic            Fit a logistic regression instead of a linear one.
+    -c, --categorical <cols>  Predictors to one-hot encode. Must be a subset of
+                              the given predictors.
+    --no-intercept            Do not fit an intercept term.
+    -F, --fitted              Append fitted values & residuals to each row,
+                              instead of outputting the model's terms.
+    --max-iterations <n>      Maximum number of iterations used to fit logistic
+                              regressions. [default: 100]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_target: SelectColumns,
+    arg_predictors: SelectColumns,
+    arg_input: Option<String>,
+    flag_logistic: bool,
+    flag_categorical: Option<SelectColumns>,
+    flag_no_intercept: bool,
+    flag_fitted: bool,
+    flag_max_iterations: usize,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+const CONVERGENCE_TOLERANCE: f64 = 1e-10;
+
+type Matrix = Vec<Vec<f64>>;
+
+// NOTE: Gauss-Jordan elimination with partial pivoting. Returns None if the
+// matrix is singular.
+fn invert(matrix: &Matrix) -> Option<Matrix> {
+    let n = matrix.len();
+
+    let mut a = matrix.clone();
+    let mut inverse: Matrix = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    let scale = matrix
+        .iter()
+        .flatten()
+        .fold(0.0_f64, |max, v| max.max(v.abs()));
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))
+            .unwrap();
+
+        if a[pivot][col].abs() <= scale * 1e-12 {
+            return None;
+        }
+
+        a.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let p = a[col][col];
+
+        for j in 0..n {
+            a[col][j] /= p;
+            inverse[col][j] /= p;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+
+            let factor = a[row][col];
+
+            if factor == 0.0 {
+                continue;
+            }
+
+            for j in 0..n {
+                a[row][j] -= factor * a[col][j];
+                inverse[row][j] -= factor * inverse[col][j];
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+// NOTE: computes X'WX & X'Wz, where W is a diagonal matrix of weights
+fn weighted_normal_equations(x: &Matrix, z: &[f64], w: Option<&[f64]>) -> (Matrix, Vec<f64>) {
+    let p = x[0].len();
+
+    let mut xtx = vec![vec![0.0; p]; p];
+    let mut xtz = vec![0.0; p];
+
+    for (i, row) in x.iter().enumerate() {
+        let weight = w.map(|w| w[i]).unwrap_or(1.0);
+
+        for (j, xj) in row.iter().enumerate() {
+            let v = weight * xj;
+
+            xtz[j] += v * z[i];
+
+            for (cell, xk) in xtx[j].iter_mut().zip(row.iter()) {
+                *cell += v * xk;
+            }
+        }
+    }
+
+    (xtx, xtz)
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn log_likelihood(y: &[f64], probabilities: impl Iterator<Item = f64>) -> f64 {
+    y.iter()
+        .zip(probabilities)
+        .map(|(y, p)| {
+            let p = p.clamp(f64::MIN_POSITIVE, 1.0 - f64::EPSILON);
+            y * p.ln() + (1.0 - y) * (1.0 - p).ln()
+        })
+        .sum()
+}
+
+struct Model {
+    coefficients: Vec<f64>,
+    std_errors: Vec<f64>,
+    statistics: Vec<f64>,
+    p_values: Vec<f64>,
+    r_squared: f64,
+    adjusted_r_squared: f64,
+}
+
+fn fit_linear(x: &Matrix, y: &[f64], intercept: bool) -> CliResult<Model> {
+    let n = x.len();
+    let p = x[0].len();
+
+    if n <= p {
+        Err(format!(
+            "not enough rows ({}) to fit a model with {} terms!",
+            n, p
+        ))?;
+    }
+
+    let (xtx, xty) = weighted_normal_equations(x, y, None);
+    let inverse = invert(&xtx).ok_or("predictors are collinear, cannot fit the model!")?;
+
+    let coefficients = inverse.iter().map(|row| dot(row, &xty)).collect::<Vec<_>>();
+
+    let sse: f64 = x
+        .iter()
+        .zip(y.iter())
+        .map(|(row, y)| (y - dot(row, &coefficients)).powi(2))
+        .sum();
+
+    let sst: f64 = if intercept {
+        let mean = y.iter().sum::<f64>() / n as f64;
+        y.iter().map(|y| (y - mean).powi(2)).sum()
+    } else {
+        y.iter().map(|y| y * y).sum()
+    };
+
+    let df = (n - p) as f64;
+    let sigma2 = sse / df;
+
+    let std_errors = (0..p)
+        .map(|i| (sigma2 * inverse[i][i]).sqrt())
+        .collect::<Vec<_>>();
+    let statistics = coefficients
+        .iter()
+        .zip(std_errors.iter())
+        .map(|(c, se)| c / se)
+        .collect::<Vec<_>>();
+    let p_values = statistics
+        .iter()
+        .map(|t| student_t_two_sided(*t, df))
+        .collect();
+
+    let r_squared = 1.0 - sse / sst;
+    let adjusted_r_squared = 1.0 - (1.0 - r_squared) * (n - usize::from(intercept)) as f64 / df;
+
+    Ok(Model {
+        coefficients,
+        std_errors,
+        statistics,
+        p_values,
+        r_squared,
+        adjusted_r_squared,
+    })
+}
+
+// NOTE: fitted using iteratively reweighted least squares, i.e. Newton's method
+fn fit_logistic(x: &Matrix, y: &[f64], max_iterations: usize) -> CliResult<Model> {
+    let n = x.len();
+    let p = x[0].len();
+
+    if n <= p {
+        Err(format!(
+            "not enough rows ({}) to fit a model with {} terms!",
+            n, p
+        ))?;
+    }
+
+    let mut coefficients = vec![0.0; p];
+    let mut converged = false;
+    let mut inverse = Matrix::new();
+
+    for _ in 0..max_iterations {
+        let probabilities = x
+            .iter()
+            .map(|row| sigmoid(dot(row, &coefficients)))
+            .collect::<Vec<_>>();
+        let weights = probabilities
+            .iter()
+            .map(|p| p * (1.0 - p))
+            .collect::<Vec<_>>();
+        let errors = y
+            .iter()
+            .zip(probabilities.iter())
+            .map(|(y, p)| y - p)
+            .collect::<Vec<_>>();
+
+        let (hessian, _) = weighted_normal_equations(x, &errors, Some(&weights));
+        let (_, gradient) = weighted_normal_equations(x, &errors, None);
+
+        inverse = invert(&hessian).ok_or(
+            "predictors are collinear or perfectly separate the target, cannot fit the model!",
+        )?;
+
+        let step = inverse
+            .iter()
+            .map(|row| dot(row, &gradient))
+            .collect::<Vec<_>>();
+
+        for (c, s) in coefficients.iter_mut().zip(step.iter()) {
+            *c += s;
+        }
+
+        if step.iter().all(|s| s.abs() < CONVERGENCE_TOLERANCE) {
+            converged = true;
+            break;
+        }
+    }
+
+    if !converged {
+        Err(format!(
+            "logistic regression did not converge after {} iterations! The predictors might perfectly separate the target.",
+            max_iterations
+        ))?;
+    }
+
+    let std_errors = (0..p).map(|i| inverse[i][i].sqrt()).collect::<Vec<_>>();
+    let statistics = coefficients
+        .iter()
+        .zip(std_errors.iter())
+        .map(|(c, se)| c / se)
+        .collect::<Vec<_>>();
+    let p_values = statistics
+        .iter()
+        .map(|z| (2.0 * normal_sf(z.abs())).min(1.0))
+        .collect();
+
+    let mean = y.iter().sum::<f64>() / n as f64;
+    let ll = log_likelihood(y, x.iter().map(|row| sigmoid(dot(row, &coefficients))));
+    let ll_null = log_likelihood(y, std::iter::repeat(mean));
+
+    Ok(Model {
+        coefficients,
+        std_errors,
+        statistics,
+        p_values,
+        r_squared: 1.0 - ll / ll_null,
+        adjusted_r_squared: 1.0 - (ll - p as f64) / ll_null,
+    })
+}
+
+enum Predictor {
+    Numeric(usize),
+    Categorical(usize, IndexSet<Vec<u8>>),
+}
+
+impl Predictor {
+    fn index(&self) -> usize {
+        match self {
+            Self::Numeric(i) | Self::Categorical(i, _) => *i,
+        }
+    }
+
+    fn encode(&self, cell: &[u8], row: &mut Vec<f64>) -> CliResult<()> {
+        match self {
+            Self::Numeric(_) => row.push(parse_number(cell)?),
+            Self::Categorical(_, levels) => {
+                row.extend(
+                    levels
+                        .iter()
+                        .skip(1)
+                        .map(|level| if level == cell { 1.0 } else { 0.0 }),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_number(cell: &[u8]) -> CliResult<f64> {
+    Ok(fast_float::parse::<f64, &[u8]>(cell).map_err(|_| {
+        format!(
+            "could not parse \"{}\" as a number!",
+            String::from_utf8_lossy(cell)
+        )
+    })?)
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+
+    let target_index = args
+        .arg_target
+        .single_selection(&headers, !args.flag_no_headers)?;
+    let predictors_sel = args
+        .arg_predictors
+        .selection(&headers, !args.flag_no_headers)?;
+    let categorical_sel = args
+        .flag_categorical
+        .as_ref()
+        .map(|cols| cols.selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    if let Some(sel) = &categorical_sel {
+        if sel.iter().any(|i| !predictors_sel.contains(*i)) {
+            Err("-c, --categorical columns must also be given as predictors!")?;
+        }
+    }
+
+    let mut predictors = predictors_sel
+        .iter()
+        .map(|i| {
+            if categorical_sel.as_ref().is_some_and(|sel| sel.contains(*i)) {
+                Predictor::Categorical(*i, IndexSet::new())
+            } else {
+                Predictor::Numeric(*i)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let is_valid = |record: &csv::ByteRecord| -> bool {
+        !record[target_index].is_empty() && predictors_sel.iter().all(|i| !record[*i].is_empty())
+    };
+
+    let records = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+    // Collecting the levels of categorical predictors
+    for record in records.iter().filter(|record| is_valid(record)) {
+        for predictor in predictors.iter_mut() {
+            if let Predictor::Categorical(i, levels) = predictor {
+                if !levels.contains(&record[*i]) {
+                    levels.insert(record[*i].to_vec());
+                }
+            }
+        }
+    }
+
+    // Building the design matrix
+    let mut x = Matrix::new();
+    let mut y: Vec<f64> = Vec::new();
+
+    for record in records.iter().filter(|record| is_valid(record)) {
+        let target = parse_number(&record[target_index])?;
+
+        if args.flag_logistic && target != 0.0 && target != 1.0 {
+            Err(format!(
+                "target of a logistic regression should be 0 or 1, but found {}!",
+                String::from_utf8_lossy(&record[target_index])
+            ))?;
+        }
+
+        let mut row = Vec::new();
+
+        if !args.flag_no_intercept {
+            row.push(1.0);
+        }
+
+        for predictor in predictors.iter() {
+            predictor.encode(&record[predictor.index()], &mut row)?;
+        }
+
+        x.push(row);
+        y.push(target);
+    }
+
+    if x.is_empty() || x[0].is_empty() {
+        Err("nothing to fit!")?;
+    }
+
+    let model = if args.flag_logistic {
+        fit_logistic(&x, &y, args.flag_max_iterations)?
+    } else {
+        fit_linear(&x, &y, !args.flag_no_intercept)?
+    };
+
+    if args.flag_fitted {
+        if !args.flag_no_headers {
+            let mut output_headers = headers.clone();
+            output_headers.push_field(b"fitted");
+            output_headers.push_field(b"residual");
+
+            wtr.write_byte_record(&output_headers)?;
+        }
+
+        let mut rows = x.iter().zip(y.iter());
+
+        for record in records.iter() {
+            let mut output_record = record.clone();
+
+            if is_valid(record) {
+                let (row, target) = rows.next().unwrap();
+
+                let mut fitted = dot(row, &model.coefficients);
+
+                if args.flag_logistic {
+                    fitted = sigmoid(fitted);
+                }
+
+                output_record.push_field(fitted.to_string().as_bytes());
+                output_record.push_field((target - fitted).to_string().as_bytes());
+            } else {
+                output_record.push_field(b"");
+                output_record.push_field(b"");
+            }
+
+            wtr.write_byte_record(&output_record)?;
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    let mut terms: Vec<Vec<u8>> = Vec::new();
+
+    if !args.flag_no_intercept {
+        terms.push(b"(intercept)".to_vec());
+    }
+
+    for predictor in predictors.iter() {
+        let name = &headers[predictor.index()];
+
+        match predictor {
+            Predictor::Numeric(_) => terms.push(name.to_vec()),
+            Predictor::Categorical(_, levels) => {
+                terms.extend(
+                    levels
+                        .iter()
+                        .skip(1)
+                        .map(|level| [name, b"=", level].concat()),
+                );
+            }
+        }
+    }
+
+    wtr.write_record([
+        "term",
+        "coefficient",
+        "std_error",
+        "statistic",
+        "p_value",
+        "n",
+        "r_squared",
+        "adjusted_r_squared",
+    ])?;
+
+    for (i, term) in terms.iter().enumerate() {
+        wtr.write_record([
+            term.as_slice(),
+            model.coefficients[i].to_string().as_bytes(),
+            model.std_errors[i].to_string().as_bytes(),
+            model.statistics[i].to_string().as_bytes(),
+            model.p_values[i].to_string().as_bytes(),
+            y.len().to_string().as_bytes(),
+            model.r_squared.to_string().as_bytes(),
+            model.adjusted_r_squared.to_string().as_bytes(),
+        ])?;
+    }
+
+    Ok(wtr.flush()?)
+}
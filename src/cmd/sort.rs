@@ -5,10 +5,13 @@ use bytesize::MB;
 use ext_sort::{buffer::mem::MemoryLimitedBufferBuilder, ExternalSorter, ExternalSorterBuilder};
 use rayon::slice::ParallelSliceMut;
 
+use crate::collation::Collation;
 use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::moonblade::Program;
 use crate::select::{SelectColumns, Selection};
 use crate::util::{self, DeepSizedByteRecord};
-use crate::CliResult;
+use crate::{CliError, CliResult};
 
 use self::Number::{Float, Int};
 
@@ -46,6 +49,26 @@ This requires reading all of the data into memory, unless
 using the -e/--external flag, which will be slower and fallback
 to using disk space.
 
+Rows can also be sorted on multiple keys, each one having its own direction
+and comparison type, using the -B/--by flag. Keys are separated by commas and
+can be followed by modifiers: \"asc\" (the default) or \"desc\" for the
+direction, and \"lex\" (the default), \"numeric\", \"natural\" or \"date\"
+for the comparison type. For instance, to sort by country, then by decreasing
+population:
+
+    $ xan sort -B 'country, population desc numeric' cities.csv
+
+Natural comparison treats sequences of digits as numbers, so that \"v2\" comes
+before \"v10\", while date comparison parses cells as datetimes. Cells that
+cannot be parsed as numbers or datetimes will be put first.
+
+Keys can also be computed by evaluating expressions, using the --expr flag:
+
+    $ xan sort --expr -B 'len(name) desc numeric, name' people.csv
+
+For a quick review of the capabilities of the expression language,
+check out the `xan help cheatsheet` command.
+
 Usage:
     xan sort [options] [<input>]
 
@@ -55,6 +78,18 @@ sort options:
                               See 'xan select --help' for the format details.
     -N, --numeric             Compare according to string numerical value
     -R, --reverse             Reverse order
+    -B, --by <keys>           Sort on the given keys, with their own direction and
+                              comparison type. See above for format details.
+                              Cannot be used with -s/--select, -N/--numeric or -C/--cells.
+    --expr                    When set, keys given to -B/--by will be evaluated as
+                              expressions instead of being parsed as column selections.
+    --natural                 Compare strings naturally, i.e. treating sequences of
+                              digits as numbers, so that \"file9\" comes before \"file10\".
+    --collate <locale>        Compare strings using Unicode collation rules tailored
+                              for the given locale, e.g. \"fr\" or \"en\". This means
+                              case & accents will only be used to break ties.
+                              Unknown locales fall back to the root collation.
+                              Can be combined with --natural.
     -c, --count <name>        Number of times the line was consecutively duplicated.
                               Needs a column name. Can only be used with --uniq.
     -u, --uniq                When set, identical consecutive lines will be dropped
@@ -92,6 +127,10 @@ struct Args {
     flag_select: SelectColumns,
     flag_numeric: bool,
     flag_reverse: bool,
+    flag_by: Option<String>,
+    flag_expr: bool,
+    flag_natural: bool,
+    flag_collate: Option<String>,
     flag_count: Option<String>,
     flag_output: Option<String>,
     flag_no_headers: bool,
@@ -109,6 +148,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let numeric = args.flag_numeric;
     let reverse = args.flag_reverse;
+
+    if args.flag_by.is_some() && (!args.flag_select.is_empty() || numeric || args.flag_cells) {
+        Err("-B/--by cannot be used with -s/--select, -N/--numeric or -C/--cells!")?;
+    }
+
+    if args.flag_natural && (args.flag_by.is_some() || numeric) {
+        Err("--natural cannot be used with -B/--by or -N/--numeric!")?;
+    }
+
+    if args.flag_cells && (args.flag_natural || args.flag_collate.is_some()) {
+        Err("-C/--cells cannot be used with --natural or --collate!")?;
+    }
+
+    if args.flag_expr && args.flag_by.is_none() {
+        Err("--expr can only be used with -B/--by!")?;
+    }
+
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers)
@@ -124,10 +180,60 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
 
+    let locale = args.flag_collate.as_deref();
+
+    let sort_keys = if let Some(spec) = &args.flag_by {
+        Some(SortKeys::parse(
+            spec,
+            &headers,
+            args.flag_no_headers,
+            args.flag_expr,
+            reverse,
+            locale,
+        )?)
+    } else if args.flag_natural || locale.is_some() {
+        let kind = if args.flag_natural {
+            KeyKind::Natural
+        } else if numeric {
+            KeyKind::Numeric
+        } else {
+            KeyKind::Lexicographic
+        };
+
+        Some(SortKeys::from_selection(&sel, kind, reverse, locale)?)
+    } else {
+        None
+    };
+
+    // NOTE: records may be extended with computed cells when sorting on keys
+    let width = headers.len();
+
     // Checking order
     if args.flag_check {
         let mut record = csv::ByteRecord::new();
 
+        if let Some(by) = &sort_keys {
+            let mut last: Option<csv::ByteRecord> = None;
+            let mut i: usize = 0;
+
+            while rdr.read_byte_record(&mut record)? {
+                by.compute(i, &mut record)?;
+                i += 1;
+
+                if let Some(last_record) = &last {
+                    if by.comparator.cmp(&record, last_record).is_lt() {
+                        Err("file is NOT sorted!")?;
+                    }
+                }
+
+                last = Some(record.clone());
+            }
+
+            println!("file is correctly sorted!");
+
+            return Ok(());
+        }
+
         let mut last: Option<Vec<Vec<u8>>> = None;
 
         while rdr.read_byte_record(&mut record)? {
@@ -252,30 +358,69 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             util::CsvExternalChunk,
         > = sorter_builder.build().unwrap();
 
+        let mut compute_error: Option<CliError> = None;
+
+        let records = rdr.byte_records().enumerate().map_while(|(i, result)| {
+            let mut record = match result {
+                Ok(record) => record,
+                Err(err) => return Some(Err(err)),
+            };
+
+            if let Some(by) = &sort_keys {
+                if let Err(err) = by.compute(i, &mut record) {
+                    compute_error = Some(err);
+                    return None;
+                }
+            }
+
+            Some(Ok(DeepSizedByteRecord(record)))
+        });
+
+        let comparator = sort_keys.as_ref().map(|by| &by.comparator);
+        let sel = &sel;
+
         let sorted = sorter
-            .sort_by(
-                rdr.byte_records()
-                    .map(|result| result.map(DeepSizedByteRecord)),
-                |r1, r2| {
-                    let a = sel.select(r1.as_ref());
-                    let b = sel.select(r2.as_ref());
-
-                    match (numeric, reverse) {
-                        (false, false) => iter_cmp(a, b),
-                        (true, false) => iter_cmp_num(a, b),
-                        (false, true) => iter_cmp(b, a),
-                        (true, true) => iter_cmp_num(b, a),
-                    }
-                },
-            )
+            .sort_by(records, move |r1, r2| {
+                if let Some(comparator) = comparator {
+                    return comparator.cmp(r1.as_ref(), r2.as_ref());
+                }
+
+                let a = sel.select(r1.as_ref());
+                let b = sel.select(r2.as_ref());
+
+                match (numeric, reverse) {
+                    (false, false) => iter_cmp(a, b),
+                    (true, false) => iter_cmp_num(a, b),
+                    (false, true) => iter_cmp(b, a),
+                    (true, true) => iter_cmp_num(b, a),
+                }
+            })
             .unwrap()
             .map(|result| result.unwrap().into_inner());
 
+        if let Some(err) = compute_error {
+            return Err(err);
+        }
+
         Box::new(sorted)
     } else {
         let mut all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
 
-        if args.flag_unstable {
+        if let Some(by) = &sort_keys {
+            for (i, record) in all.iter_mut().enumerate() {
+                by.compute(i, record)?;
+            }
+
+            let comparator = &by.comparator;
+            let compare = |r1: &csv::ByteRecord, r2: &csv::ByteRecord| comparator.cmp(r1, r2);
+
+            match (args.flag_unstable, args.flag_parallel) {
+                (true, true) => all.par_sort_unstable_by(compare),
+                (true, false) => all.sort_unstable_by(compare),
+                (false, true) => all.par_sort_by(compare),
+                (false, false) => all.sort_by(compare),
+            }
+        } else if args.flag_unstable {
             if args.flag_parallel {
                 sort_by!(all, par_sort_unstable_by, sel, numeric, reverse);
             } else {
@@ -301,6 +446,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
+    let same_group = |r1: &csv::ByteRecord, r2: &csv::ByteRecord| match &sort_keys {
+        Some(by) => by.comparator.cmp(r1, r2),
+        None => iter_cmp(sel.select(r1), sel.select(r2)),
+    };
+
     let mut prev: Option<csv::ByteRecord> = None;
     let mut counter: u64 = 1;
     let mut line_buffer: Option<csv::ByteRecord> = None;
@@ -308,7 +458,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     for r in all.into_iter() {
         if args.flag_uniq {
             match prev {
-                Some(other_r) => match iter_cmp(sel.select(&r), sel.select(&other_r)) {
+                Some(other_r) => match same_group(&r, &other_r) {
                     cmp::Ordering::Equal => {
                         if !count.is_none() {
                             counter += 1;
@@ -316,12 +466,13 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     }
                     _ => {
                         if let Some(mut to_flush) = line_buffer {
+                            to_flush.truncate(width);
                             to_flush.push_field(counter.to_string().as_bytes());
                             wtr.write_byte_record(&to_flush)?;
                             line_buffer = Some(r.clone());
                             counter = 1;
                         } else {
-                            wtr.write_byte_record(&r)?;
+                            wtr.write_record(r.iter().take(width))?;
                         }
                     }
                 },
@@ -329,17 +480,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     if !count.is_none() {
                         line_buffer = Some(r.clone());
                     } else {
-                        wtr.write_byte_record(&r)?;
+                        wtr.write_record(r.iter().take(width))?;
                     }
                 }
             }
 
             prev = Some(r);
         } else {
-            wtr.write_byte_record(&r)?;
+            wtr.write_record(r.iter().take(width))?;
         }
     }
     if let Some(mut to_flush) = line_buffer {
+        to_flush.truncate(width);
         to_flush.push_field(counter.to_string().as_bytes());
         wtr.write_byte_record(&to_flush)?;
     }
@@ -421,6 +573,287 @@ where
     xs.next().and_then(parse_num)
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum KeyKind {
+    Lexicographic,
+    Numeric,
+    Natural,
+    Date,
+}
+
+// NOTE: a sort key always points to a cell of the sorted records. Cells that
+// must be computed, i.e. the results of expressions & datetimes, which are
+// normalized as timestamps to be compared numerically, are appended to the
+// records before sorting and truncated before writing them.
+struct SortKey {
+    index: usize,
+    kind: KeyKind,
+    reverse: bool,
+}
+
+struct KeyComparator {
+    keys: Vec<SortKey>,
+    lexicographic: Option<Collation>,
+    natural: Collation,
+}
+
+impl KeyComparator {
+    fn new(keys: Vec<SortKey>, locale: Option<&str>) -> CliResult<Self> {
+        Ok(Self {
+            keys,
+            lexicographic: Collation::new(false, locale)?,
+            natural: Collation::new(true, locale)?.unwrap(),
+        })
+    }
+
+    fn cmp(&self, r1: &csv::ByteRecord, r2: &csv::ByteRecord) -> cmp::Ordering {
+        for key in self.keys.iter() {
+            let a = &r1[key.index];
+            let b = &r2[key.index];
+
+            let ordering = match key.kind {
+                KeyKind::Lexicographic => match &self.lexicographic {
+                    Some(collation) => collation.cmp(a, b),
+                    None => a.cmp(b),
+                },
+                KeyKind::Numeric | KeyKind::Date => {
+                    iter_cmp_num(std::iter::once(a), std::iter::once(b))
+                }
+                KeyKind::Natural => self.natural.cmp(a, b),
+            };
+
+            match ordering {
+                cmp::Ordering::Equal => continue,
+                non_eq => {
+                    return if key.reverse {
+                        non_eq.reverse()
+                    } else {
+                        non_eq
+                    }
+                }
+            }
+        }
+
+        cmp::Ordering::Equal
+    }
+}
+
+enum CellSource {
+    Column(usize),
+    Expression(Program),
+}
+
+struct ComputedCell {
+    source: CellSource,
+    date: bool,
+}
+
+pub struct SortKeys {
+    comparator: KeyComparator,
+    cells: Vec<ComputedCell>,
+}
+
+impl SortKeys {
+    fn from_selection(
+        sel: &Selection,
+        kind: KeyKind,
+        reverse: bool,
+        locale: Option<&str>,
+    ) -> CliResult<Self> {
+        let keys = sel
+            .iter()
+            .map(|i| SortKey {
+                index: *i,
+                kind,
+                reverse,
+            })
+            .collect();
+
+        Ok(Self {
+            comparator: KeyComparator::new(keys, locale)?,
+            cells: Vec::new(),
+        })
+    }
+
+    // NOTE: the global `reverse` flag flips the direction of every key.
+    pub fn parse(
+        spec: &str,
+        headers: &csv::ByteRecord,
+        no_headers: bool,
+        expr: bool,
+        reverse: bool,
+        locale: Option<&str>,
+    ) -> CliResult<Self> {
+        let mut keys = Vec::new();
+        let mut cells = Vec::new();
+
+        for key in split_sort_keys(spec) {
+            let (target, kind, key_reverse) = parse_sort_key(key)?;
+            let reverse = key_reverse != reverse;
+            let date = kind == KeyKind::Date;
+
+            if expr {
+                keys.push(SortKey {
+                    index: headers.len() + cells.len(),
+                    kind,
+                    reverse,
+                });
+
+                cells.push(ComputedCell {
+                    source: CellSource::Expression(Program::parse(target, headers)?),
+                    date,
+                });
+
+                continue;
+            }
+
+            let sel = SelectColumns::parse(target)?.selection(headers, !no_headers)?;
+
+            for i in sel.iter().copied() {
+                if date {
+                    keys.push(SortKey {
+                        index: headers.len() + cells.len(),
+                        kind,
+                        reverse,
+                    });
+
+                    cells.push(ComputedCell {
+                        source: CellSource::Column(i),
+                        date,
+                    });
+                } else {
+                    keys.push(SortKey {
+                        index: i,
+                        kind,
+                        reverse,
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            comparator: KeyComparator::new(keys, locale)?,
+            cells,
+        })
+    }
+
+    pub fn cmp(&self, r1: &csv::ByteRecord, r2: &csv::ByteRecord) -> cmp::Ordering {
+        self.comparator.cmp(r1, r2)
+    }
+
+    pub fn compute(&self, index: usize, record: &mut csv::ByteRecord) -> CliResult<()> {
+        for cell in self.cells.iter() {
+            let value = match &cell.source {
+                CellSource::Column(i) => record[*i].to_vec(),
+                CellSource::Expression(program) => program
+                    .run_with_record(index, record)?
+                    .serialize_as_bytes()
+                    .into_owned(),
+            };
+
+            if cell.date {
+                let timestamp = dates::parse_zoned(&String::from_utf8_lossy(&value), None, None)
+                    .ok()
+                    .map(|zoned| zoned.timestamp().as_nanosecond().to_string());
+
+                record.push_field(timestamp.as_ref().map(|t| t.as_bytes()).unwrap_or(b""));
+            } else {
+                record.push_field(&value);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// NOTE: keys are separated by commas, but we need to take care of commas
+// found in quoted column names & in expressions, e.g. in function calls.
+fn split_sort_keys(spec: &str) -> Vec<&str> {
+    let mut keys = Vec::new();
+    let mut depth: usize = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in spec.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+
+            continue;
+        }
+
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                keys.push(&spec[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    keys.push(&spec[start..]);
+
+    keys
+}
+
+fn parse_sort_key(key: &str) -> CliResult<(&str, KeyKind, bool)> {
+    let mut target = key.trim();
+    let mut kind: Option<KeyKind> = None;
+    let mut reverse: Option<bool> = None;
+
+    while let Some((head, modifier)) = target.rsplit_once(char::is_whitespace) {
+        match modifier {
+            "asc" | "desc" => {
+                if reverse.is_some() {
+                    Err(format!(
+                        "sort key \"{}\" has more than one direction!",
+                        key.trim()
+                    ))?;
+                }
+
+                reverse = Some(modifier == "desc");
+            }
+            "lex" | "numeric" | "natural" | "date" => {
+                if kind.is_some() {
+                    Err(format!(
+                        "sort key \"{}\" has more than one comparison type!",
+                        key.trim()
+                    ))?;
+                }
+
+                kind = Some(match modifier {
+                    "lex" => KeyKind::Lexicographic,
+                    "numeric" => KeyKind::Numeric,
+                    "natural" => KeyKind::Natural,
+                    _ => KeyKind::Date,
+                });
+            }
+            _ => break,
+        }
+
+        target = head.trim_end();
+    }
+
+    if target.is_empty() {
+        Err(format!("invalid sort key \"{}\"!", key.trim()))?;
+    }
+
+    Ok((
+        target,
+        kind.unwrap_or(KeyKind::Lexicographic),
+        reverse.unwrap_or(false),
+    ))
+}
+
 // Standard comparable byte record abstraction
 pub struct ComparableByteRecord<'a> {
     record: csv::ByteRecord,
@@ -498,3 +931,47 @@ impl cmp::PartialEq for NumericallyComparableByteRecord<'_> {
 }
 
 impl cmp::Eq for NumericallyComparableByteRecord<'_> {}
+
+// Collated byte record abstraction
+pub struct CollatedComparableByteRecord<'a> {
+    record: csv::ByteRecord,
+    sel: &'a Selection,
+    collation: &'a Collation,
+}
+
+impl<'a> CollatedComparableByteRecord<'a> {
+    pub fn new(record: csv::ByteRecord, sel: &'a Selection, collation: &'a Collation) -> Self {
+        CollatedComparableByteRecord {
+            record,
+            sel,
+            collation,
+        }
+    }
+
+    pub fn as_byte_record(&self) -> &csv::ByteRecord {
+        &self.record
+    }
+}
+
+impl cmp::Ord for CollatedComparableByteRecord<'_> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        let s1 = self.sel.select(&self.record);
+        let s2 = other.sel.select(&other.record);
+
+        self.collation.iter_cmp(s1, s2)
+    }
+}
+
+impl cmp::PartialOrd for CollatedComparableByteRecord<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl cmp::PartialEq for CollatedComparableByteRecord<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl cmp::Eq for CollatedComparableByteRecord<'_> {}
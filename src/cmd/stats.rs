@@ -16,6 +16,12 @@ set of statistics corresponds to statistics that can be computed efficiently on
 stream of data in constant memory, but more can be selected using flags documented
 hereafter.
 
+Statistics can also be weighted using the -w, --weight flag, in which case
+counts, sums, means, variances & quartiles (exact or approximated) will be
+computed as if each row was repeated as many times as its weight. Weights can
+be any non-negative number. Cardinality, modes, extents & lengths are not
+affected by weights.
+
 If you have more specific needs or want to perform custom aggregations, please be
 sure to check the `xan agg` command instead.
 
@@ -57,6 +63,8 @@ stats options:
                            into 'xan stats' will disable the use of indexing.
     -g, --groupby <cols>   If given, will compute stats per group as defined by
                            the given column selection.
+    -w, --weight <column>  Column containing row weights. See above for
+                           the statistics taking them into account.
     -A, --all              Shorthand for -cq.
     -c, --cardinality      Show cardinality and modes.
                            This requires storing all CSV data in memory.
@@ -81,6 +89,7 @@ struct Args {
     arg_input: Option<String>,
     flag_select: SelectColumns,
     flag_groupby: Option<SelectColumns>,
+    flag_weight: Option<SelectColumns>,
     flag_all: bool,
     flag_cardinality: bool,
     flag_quartiles: bool,
@@ -99,6 +108,10 @@ impl Args {
             stats.include_nulls();
         }
 
+        if self.flag_weight.is_some() {
+            stats.use_weights();
+        }
+
         if self.flag_all || self.flag_cardinality {
             stats.compute_frequencies();
         }
@@ -139,6 +152,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         sel.subtract(gsel);
     }
 
+    // Nor the weight column
+    let weight_index = match &args.flag_weight {
+        Some(col) => {
+            let index = col.single_selection(&headers, !args.flag_no_headers)?;
+            sel.subtract(&col.selection(&headers, !args.flag_no_headers)?);
+            Some(index)
+        }
+        None => None,
+    };
+
+    let parse_weight = |record: &csv::ByteRecord| -> CliResult<f64> {
+        Ok(match weight_index {
+            Some(index) => util::parse_weight(&record[index])?,
+            None => 1.0,
+        })
+    };
+
     // Nothing was selected
     if sel.is_empty() {
         return Ok(());
@@ -169,6 +199,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
         while rdr.read_byte_record(&mut record)? {
             let group_key: Vec<_> = gsel.select(&record).map(|cell| cell.to_vec()).collect();
+            let weight = parse_weight(&record)?;
 
             groups.insert_with_or_else(
                 group_key,
@@ -176,14 +207,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     let mut fields = (0..sel.len()).map(|_| args.new_stats()).collect::<Vec<_>>();
 
                     for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
-                        stats.process(cell);
+                        stats.process_with_weight(cell, weight);
                     }
 
                     fields
                 },
                 |fields| {
                     for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
-                        stats.process(cell);
+                        stats.process_with_weight(cell, weight);
                     }
                 },
             );
@@ -214,8 +245,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut record = csv::ByteRecord::new();
 
     while rdr.read_byte_record(&mut record)? {
+        let weight = parse_weight(&record)?;
+
         for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
-            stats.process(cell);
+            stats.process_with_weight(cell, weight);
         }
     }
 
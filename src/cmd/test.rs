@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use crate::config::{Config, Delimiter};
+use crate::distributions::{chi2_sf, f_sf, kolmogorov_sf, normal_sf, student_t_two_sided};
+use crate::moonblade::agg::Welford;
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Run statistical hypothesis tests comparing the values of a column across the
+groups defined by another column, and output a single CSV row containing the
+test's statistic, degrees of freedom, p-value & effect size.
+
+Available tests:
+
+    - welch: Welch's t-test, comparing the means of a numerical column across
+      exactly 2 groups, without assuming equal variances. The statistic is
+      positive when the mean of the first group (in order of appearance in the
+      file) is higher. Effect size is Cohen's d, using the pooled standard
+      deviation.
+    - mann-whitney: Mann-Whitney U test, comparing the distributions of a
+      numerical column across exactly 2 groups. The statistic is the U of the
+      first group & the p-value is computed using a normal approximation with
+      tie & continuity corrections. Effect size is the rank-biserial
+      correlation.
+    - ks: two-sample Kolmogorov-Smirnov test, comparing the distributions of a
+      numerical column across exactly 2 groups. The statistic is the maximum
+      distance between both empirical distribution functions & the p-value is
+      computed using the asymptotic Kolmogorov distribution.
+    - anova: one-way ANOVA, comparing the means of a numerical column across 2 or
+      more groups. The statistic is F, with two degrees of freedom: between &
+      within groups. Effect size is eta squared.
+    - chi2: chi-square test of independence between two categorical columns,
+      without continuity correction. Effect size is Cramér's V.
+
+For instance, to test whether the mean \"price\" is different across two
+\"category\" values:
+
+    $ xan test welch price category file.csv
+
+Rows having an empty value in any of the considered columns are ignored.
+
+Usage:
+    xan test welch [options] <column> <group> [<input>]
+    xan test mann-whitney [options] <column> <group> [<input>]
+    xan test ks [options] <column> <group> [<input>]
+    xan test anova [options] <column> <group> [<input>]
+    xan test chi2 [options] <column> <group> [<input>]
+    xan test --help
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    cmd_welch: bool,
+    cmd_mann_whitney: bool,
+    cmd_ks: bool,
+    cmd_anova: bool,
+    cmd_chi2: bool,
+    arg_column: SelectColumns,
+    arg_group: SelectColumns,
+    arg_input: Option<String>,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+impl Args {
+    fn test_name(&self) -> &str {
+        if self.cmd_welch {
+            "welch"
+        } else if self.cmd_mann_whitney {
+            "mann-whitney"
+        } else if self.cmd_ks {
+            "ks"
+        } else if self.cmd_anova {
+            "anova"
+        } else {
+            "chi2"
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TestResult {
+    n: usize,
+    statistic: f64,
+    df: Option<f64>,
+    df2: Option<f64>,
+    p_value: f64,
+    effect_size: Option<f64>,
+}
+
+impl TestResult {
+    fn headers() -> csv::ByteRecord {
+        csv::ByteRecord::from(vec![
+            "test",
+            "n",
+            "statistic",
+            "df",
+            "df2",
+            "p_value",
+            "effect_size",
+        ])
+    }
+
+    fn to_byte_record(&self, name: &str) -> csv::ByteRecord {
+        let format = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        csv::ByteRecord::from(vec![
+            name.to_string(),
+            self.n.to_string(),
+            self.statistic.to_string(),
+            format(self.df),
+            format(self.df2),
+            self.p_value.to_string(),
+            format(self.effect_size),
+        ])
+    }
+}
+
+fn parse_value(cell: &[u8]) -> CliResult<f64> {
+    Ok(fast_float::parse::<f64, &[u8]>(cell).map_err(|_| {
+        format!(
+            "could not parse \"{}\" as a number!",
+            String::from_utf8_lossy(cell)
+        )
+    })?)
+}
+
+fn welford(values: &[f64]) -> Welford {
+    let mut welford = Welford::new();
+
+    for value in values {
+        welford.add(*value);
+    }
+
+    welford
+}
+
+fn two_groups(groups: &IndexMap<Vec<u8>, Vec<f64>>) -> CliResult<(&[f64], &[f64])> {
+    if groups.len() != 2 {
+        Err(format!(
+            "this test expects exactly 2 groups, but found {}!",
+            groups.len()
+        ))?;
+    }
+
+    Ok((&groups[0], &groups[1]))
+}
+
+fn welch(a: &[f64], b: &[f64]) -> CliResult<TestResult> {
+    if a.len() < 2 || b.len() < 2 {
+        Err("welch test requires at least 2 values per group!")?;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let (w1, w2) = (welford(a), welford(b));
+    let (m1, m2) = (w1.mean().unwrap(), w2.mean().unwrap());
+    let (v1, v2) = (w1.sample_variance().unwrap(), w2.sample_variance().unwrap());
+
+    let se1 = v1 / n1;
+    let se2 = v2 / n2;
+
+    let statistic = (m1 - m2) / (se1 + se2).sqrt();
+    let df = (se1 + se2).powi(2) / (se1.powi(2) / (n1 - 1.0) + se2.powi(2) / (n2 - 1.0));
+
+    let pooled_sd = (((n1 - 1.0) * v1 + (n2 - 1.0) * v2) / (n1 + n2 - 2.0)).sqrt();
+
+    Ok(TestResult {
+        n: a.len() + b.len(),
+        statistic,
+        df: Some(df),
+        df2: None,
+        p_value: student_t_two_sided(statistic, df),
+        effect_size: Some((m1 - m2) / pooled_sd),
+    })
+}
+
+fn mann_whitney(a: &[f64], b: &[f64]) -> CliResult<TestResult> {
+    if a.is_empty() || b.is_empty() {
+        Err("mann-whitney test requires at least 1 value per group!")?;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+
+    let values = a.iter().chain(b.iter()).copied().collect::<Vec<_>>();
+    let (ranks, ties) = util::average_ranks(&values);
+
+    let r1: f64 = ranks[..a.len()].iter().sum();
+    let u1 = r1 - n1 * (n1 + 1.0) / 2.0;
+    let u2 = n1 * n2 - u1;
+
+    let mu = n1 * n2 / 2.0;
+    let sigma = (n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)))).sqrt();
+
+    let p_value = if sigma == 0.0 {
+        1.0
+    } else {
+        let z = (u1.max(u2) - mu - 0.5) / sigma;
+        (2.0 * normal_sf(z)).min(1.0)
+    };
+
+    Ok(TestResult {
+        n: a.len() + b.len(),
+        statistic: u1,
+        df: None,
+        df2: None,
+        p_value,
+        effect_size: Some(2.0 * u1 / (n1 * n2) - 1.0),
+    })
+}
+
+fn kolmogorov_smirnov(a: &[f64], b: &[f64]) -> CliResult<TestResult> {
+    if a.is_empty() || b.is_empty() {
+        Err("ks test requires at least 1 value per group!")?;
+    }
+
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+
+    a.sort_by(|x, y| x.total_cmp(y));
+    b.sort_by(|x, y| x.total_cmp(y));
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+
+    let mut i = 0;
+    let mut j = 0;
+    let mut statistic: f64 = 0.0;
+
+    while i < a.len() && j < b.len() {
+        let value = a[i].min(b[j]);
+
+        while i < a.len() && a[i] == value {
+            i += 1;
+        }
+
+        while j < b.len() && b[j] == value {
+            j += 1;
+        }
+
+        statistic = statistic.max((i as f64 / n1 - j as f64 / n2).abs());
+    }
+
+    let en = (n1 * n2 / (n1 + n2)).sqrt();
+
+    Ok(TestResult {
+        n: a.len() + b.len(),
+        statistic,
+        df: None,
+        df2: None,
+        p_value: kolmogorov_sf((en + 0.12 + 0.11 / en) * statistic),
+        effect_size: None,
+    })
+}
+
+fn anova(groups: &IndexMap<Vec<u8>, Vec<f64>>) -> CliResult<TestResult> {
+    let k = groups.len();
+    let n: usize = groups.values().map(|values| values.len()).sum();
+
+    if k < 2 {
+        Err("anova requires at least 2 groups!")?;
+    }
+
+    if n <= k {
+        Err("anova requires more values than groups!")?;
+    }
+
+    let grand_mean = groups.values().flatten().sum::<f64>() / n as f64;
+
+    let mut ss_between = 0.0;
+    let mut ss_within = 0.0;
+
+    for values in groups.values() {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+
+        ss_between += values.len() as f64 * (mean - grand_mean).powi(2);
+        ss_within += values.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+    }
+
+    let df1 = (k - 1) as f64;
+    let df2 = (n - k) as f64;
+
+    let statistic = (ss_between / df1) / (ss_within / df2);
+
+    Ok(TestResult {
+        n,
+        statistic,
+        df: Some(df1),
+        df2: Some(df2),
+        p_value: f_sf(statistic, df1, df2),
+        effect_size: Some(ss_between / (ss_between + ss_within)),
+    })
+}
+
+fn chi2(
+    rows: usize,
+    columns: usize,
+    counts: &HashMap<(usize, usize), u64>,
+) -> CliResult<TestResult> {
+    if rows < 2 || columns < 2 {
+        Err("chi2 test requires at least 2 distinct values in both columns!")?;
+    }
+
+    let mut row_totals = vec![0.0; rows];
+    let mut column_totals = vec![0.0; columns];
+    let mut n = 0.0;
+
+    for ((i, j), count) in counts.iter() {
+        let count = *count as f64;
+
+        row_totals[*i] += count;
+        column_totals[*j] += count;
+        n += count;
+    }
+
+    let mut statistic = 0.0;
+
+    for (i, row_total) in row_totals.iter().enumerate() {
+        for (j, column_total) in column_totals.iter().enumerate() {
+            let observed = counts.get(&(i, j)).copied().unwrap_or(0) as f64;
+            let expected = row_total * column_total / n;
+
+            statistic += (observed - expected).powi(2) / expected;
+        }
+    }
+
+    let df = ((rows - 1) * (columns - 1)) as f64;
+
+    Ok(TestResult {
+        n: n as usize,
+        statistic,
+        df: Some(df),
+        df2: None,
+        p_value: chi2_sf(statistic, df),
+        effect_size: Some((statistic / (n * (rows.min(columns) - 1) as f64)).sqrt()),
+    })
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let column_index = args
+        .arg_column
+        .single_selection(&headers, !args.flag_no_headers)?;
+    let group_index = args
+        .arg_group
+        .single_selection(&headers, !args.flag_no_headers)?;
+
+    let mut record = csv::ByteRecord::new();
+
+    let result = if args.cmd_chi2 {
+        let mut row_values: IndexMap<Vec<u8>, ()> = IndexMap::new();
+        let mut column_values: IndexMap<Vec<u8>, ()> = IndexMap::new();
+        let mut counts: HashMap<(usize, usize), u64> = HashMap::new();
+
+        while rdr.read_byte_record(&mut record)? {
+            let (row_value, column_value) = (&record[column_index], &record[group_index]);
+
+            if row_value.is_empty() || column_value.is_empty() {
+                continue;
+            }
+
+            let i = row_values.insert_full(row_value.to_vec(), ()).0;
+            let j = column_values.insert_full(column_value.to_vec(), ()).0;
+
+            counts
+                .entry((i, j))
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+        }
+
+        chi2(row_values.len(), column_values.len(), &counts)?
+    } else {
+        let mut groups: IndexMap<Vec<u8>, Vec<f64>> = IndexMap::new();
+
+        while rdr.read_byte_record(&mut record)? {
+            let (cell, group) = (&record[column_index], &record[group_index]);
+
+            if cell.is_empty() || group.is_empty() {
+                continue;
+            }
+
+            groups
+                .entry(group.to_vec())
+                .or_default()
+                .push(parse_value(cell)?);
+        }
+
+        if args.cmd_anova {
+            anova(&groups)?
+        } else {
+            let (a, b) = two_groups(&groups)?;
+
+            if args.cmd_welch {
+                welch(a, b)?
+            } else if args.cmd_mann_whitney {
+                mann_whitney(a, b)?
+            } else {
+                kolmogorov_smirnov(a, b)?
+            }
+        }
+    };
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    wtr.write_byte_record(&TestResult::headers())?;
+    wtr.write_byte_record(&result.to_byte_record(args.test_name()))?;
+
+    Ok(wtr.flush()?)
+}
@@ -1,8 +1,9 @@
-use std::cmp::Reverse;
+use std::cmp::{Ordering, Reverse};
 use std::num::NonZeroUsize;
 
 use ordered_float::NotNan;
 
+use crate::collation::Collation;
 use crate::collections::{
     ClusteredInsertHashmap, FixedReverseHeapMap, FixedReverseHeapMapWithTies,
 };
@@ -21,6 +22,13 @@ Runs in O(N * log k) time, consuming only O(k) memory.
 Note that rows whose selected cell is empty or cannot be
 parsed as a number will be ignored.
 
+Values can also be ranked as strings, using the --natural or --collate
+flags, e.g. to find the latest versions of some software:
+
+    $ xan top version --natural -l 3 releases.csv
+
+Empty cells will still be ignored in this case.
+
 Usage:
     xan top <column> [options] [<input>]
     xan top --help
@@ -29,6 +37,13 @@ dedup options:
     -l, --limit <n>       Number of top items to return. Cannot be < 1.
                           [default: 10]
     -R, --reverse         Reverse order.
+    --natural             Rank values as strings compared naturally, i.e. treating
+                          sequences of digits as numbers, so that \"file10\" ranks
+                          above \"file9\".
+    --collate <locale>    Rank values as strings compared using Unicode collation
+                          rules tailored for the given locale, e.g. \"fr\" or \"en\".
+                          Unknown locales fall back to the root collation.
+                          Can be combined with --natural.
     -g, --groupby <cols>  Return top n values per group, represented
                           by the values in given columns.
     -r, --rank <col>      Name of a rank column to prepend.
@@ -47,6 +62,31 @@ Common options:
 #[derive(PartialEq, PartialOrd, Ord, Eq)]
 struct Forward<T>(T);
 
+struct CollatedValue<'a> {
+    value: Vec<u8>,
+    collation: &'a Collation,
+}
+
+impl Ord for CollatedValue<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.collation.cmp(&self.value, &other.value)
+    }
+}
+
+impl PartialOrd for CollatedValue<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for CollatedValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for CollatedValue<'_> {}
+
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
@@ -56,6 +96,8 @@ struct Args {
     flag_delimiter: Option<Delimiter>,
     flag_limit: NonZeroUsize,
     flag_reverse: bool,
+    flag_natural: bool,
+    flag_collate: Option<String>,
     flag_groupby: Option<SelectColumns>,
     flag_rank: Option<String>,
     flag_ties: bool,
@@ -63,6 +105,7 @@ struct Args {
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    let collation = Collation::new(args.flag_natural, args.flag_collate.as_deref())?;
 
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
@@ -89,17 +132,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     }
 
     macro_rules! run {
-        ($heap:ident, $type:ident) => {{
+        ($heap:ident, $type:ident, $score:ty, $parse:expr) => {{
+            let parse = $parse;
             let mut record = csv::ByteRecord::new();
-            let mut heap = $heap::<$type<NotNan<f64>>, csv::ByteRecord>::with_capacity(
-                usize::from(args.flag_limit),
-            );
+            let mut heap = $heap::<$type<$score>, csv::ByteRecord>::with_capacity(usize::from(
+                args.flag_limit,
+            ));
 
             while rdr.read_byte_record(&mut record)? {
-                if let Ok(score) = std::str::from_utf8(&record[score_col])
-                    .unwrap_or("")
-                    .parse::<NotNan<f64>>()
-                {
+                if let Some(score) = parse(&record[score_col]) {
                     heap.push_with($type(score), || record.clone());
                 }
             }
@@ -115,34 +156,24 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     }
 
     macro_rules! run_groupby {
-        ($heap:ident, $type:ident, $sel:ident) => {{
+        ($heap:ident, $type:ident, $sel:ident, $score:ty, $parse:expr) => {{
+            let parse = $parse;
             let mut record = csv::ByteRecord::new();
             let mut groups: ClusteredInsertHashmap<
                 GroupKey,
-                $heap<$type<NotNan<f64>>, csv::ByteRecord>,
+                $heap<$type<$score>, csv::ByteRecord>,
             > = ClusteredInsertHashmap::new();
 
             while rdr.read_byte_record(&mut record)? {
-                if let Ok(score) = std::str::from_utf8(&record[score_col])
-                    .unwrap_or("")
-                    .parse::<NotNan<f64>>()
-                {
+                if let Some(score) = parse(&record[score_col]) {
                     let group = $sel
                         .select(&record)
                         .map(|cell| cell.to_vec())
                         .collect::<Vec<_>>();
 
-                    groups.insert_with_or_else(
-                        group,
-                        || {
-                            let mut heap = $heap::with_capacity(usize::from(args.flag_limit));
-                            heap.push_with($type(score), || record.clone());
-                            heap
-                        },
-                        |heap| {
-                            heap.push_with($type(score), || record.clone());
-                        },
-                    );
+                    groups
+                        .insert_with(group, || $heap::with_capacity(usize::from(args.flag_limit)))
+                        .push_with($type(score), || record.clone());
                 }
             }
 
@@ -158,16 +189,44 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }};
     }
 
-    match (args.flag_reverse, args.flag_ties, groupby_sel_opt) {
-        (true, false, None) => run!(FixedReverseHeapMap, Reverse),
-        (false, false, None) => run!(FixedReverseHeapMap, Forward),
-        (true, false, Some(sel)) => run_groupby!(FixedReverseHeapMap, Reverse, sel),
-        (false, false, Some(sel)) => run_groupby!(FixedReverseHeapMap, Forward, sel),
-        (true, true, None) => run!(FixedReverseHeapMapWithTies, Reverse),
-        (false, true, None) => run!(FixedReverseHeapMapWithTies, Forward),
-        (true, true, Some(sel)) => run_groupby!(FixedReverseHeapMapWithTies, Reverse, sel),
-        (false, true, Some(sel)) => run_groupby!(FixedReverseHeapMapWithTies, Forward, sel),
-    };
+    macro_rules! dispatch {
+        ($score:ty, $parse:expr) => {
+            match (args.flag_reverse, args.flag_ties, groupby_sel_opt) {
+                (true, false, None) => run!(FixedReverseHeapMap, Reverse, $score, $parse),
+                (false, false, None) => run!(FixedReverseHeapMap, Forward, $score, $parse),
+                (true, false, Some(sel)) => {
+                    run_groupby!(FixedReverseHeapMap, Reverse, sel, $score, $parse)
+                }
+                (false, false, Some(sel)) => {
+                    run_groupby!(FixedReverseHeapMap, Forward, sel, $score, $parse)
+                }
+                (true, true, None) => run!(FixedReverseHeapMapWithTies, Reverse, $score, $parse),
+                (false, true, None) => run!(FixedReverseHeapMapWithTies, Forward, $score, $parse),
+                (true, true, Some(sel)) => {
+                    run_groupby!(FixedReverseHeapMapWithTies, Reverse, sel, $score, $parse)
+                }
+                (false, true, Some(sel)) => {
+                    run_groupby!(FixedReverseHeapMapWithTies, Forward, sel, $score, $parse)
+                }
+            }
+        };
+    }
+
+    if let Some(collation) = &collation {
+        dispatch!(CollatedValue<'_>, |cell: &[u8]| {
+            (!cell.is_empty()).then(|| CollatedValue {
+                value: cell.to_vec(),
+                collation,
+            })
+        });
+    } else {
+        dispatch!(NotNan<f64>, |cell: &[u8]| {
+            std::str::from_utf8(cell)
+                .unwrap_or("")
+                .parse::<NotNan<f64>>()
+                .ok()
+        });
+    }
 
     Ok(wtr.flush()?)
 }
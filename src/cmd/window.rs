@@ -0,0 +1,219 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use crate::cmd::sort::SortKeys;
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+use crate::moonblade::WindowAggregationProgram;
+
+static USAGE: &str = "
+Compute window functions over the rows of a CSV file, such as running totals,
+rolling means, ranks or row-to-row deltas, and add their results as new columns.
+
+Rows are considered in the order of the file, unless using the -O, --order-by
+flag, in which case they will be sorted within each partition. Note that this
+requires reading the whole file into memory. Rows will always be written in the
+same order as the input file. Windows can also be partitioned using
+the -g, --groupby flag, in which case each group will be processed
+independently, without needing the file to be sorted on the group columns.
+
+For instance, to compute a running total of a \"count\" column, along with
+the value of the previous row, per user:
+
+    $ xan window -g user 'cumsum(count) as total, lag(count) as previous' file.csv
+
+To compute a rolling mean over the 7 last rows:
+
+    $ xan window 'rolling_mean(7, count) as mean' file.csv
+
+To compute a rolling sum over the last 30 days, given a \"date\" column:
+
+    $ xan window -t date 'rolling_sum(\"30d\", count) as sum' file.csv
+
+To rank the rows of each user by decreasing count:
+
+    $ xan window -g user -O 'count desc numeric' 'rank() as rank' file.csv
+
+Available window functions:
+
+    - row_number(): 1-based index of the row in its partition.
+    - rank(): rank of the row in its partition, according to the keys given
+      to -O, --order-by. Tied rows get the same rank & leave gaps.
+    - dense_rank(): same as rank but without gaps.
+    - lag(<expr>, <offset>?, <default>?): value of <expr> for the row <offset>
+      rows before, defaulting to 1. Will return <default> or an empty value if
+      there is no such row.
+    - lead(<expr>, <offset>?, <default>?): same as lag, but for the rows after.
+      Note that this requires buffering rows until the following rows of their
+      partition are known.
+    - cumsum(<expr>): cumulative sum of <expr>.
+    - cummin(<expr>): cumulative minimum of <expr>.
+    - cummax(<expr>): cumulative maximum of <expr>.
+    - rolling_<agg>(<window>, ...): any aggregation function, e.g. mean, median
+      or count, computed over a rolling window, that can either be a number of
+      rows (including current one) or a duration string, e.g. \"7d\" or \"2h\",
+      in which case rows whose time, as given by -t, --time, is within this
+      duration of the current row's time will be considered. Rows must then be
+      sorted by time within each partition, e.g. using -O, --order-by, else
+      an error will be raised. Sums, means, counts & variances are updated
+      incrementally, while other functions are recomputed over the window.
+
+Cumulative & rolling functions can also be restricted to some rows using an
+'if' clause, e.g. `cumsum(count) if status eq \"ok\"`.
+
+For a list of available aggregation functions, use `xan help aggs`.
+
+Usage:
+    xan window [options] <expression> [<input>]
+    xan window --help
+
+window options:
+    -g, --groupby <cols>   Columns partitioning the windows.
+    -O, --order-by <keys>  Sort the rows of each partition on the given keys,
+                           using the same format as `xan sort -B`, e.g.
+                           \"date date\" or \"count desc numeric\".
+    -t, --time <col>       Column containing the time of the rows, used by
+                           rolling functions over a duration window.
+
+Common options:
+    -h, --help               Display this message
+    -o, --output <file>      Write output to <file> instead of stdout.
+    -n, --no-headers         When set, the first row will not be evaled
+                             as headers.
+    -d, --delimiter <arg>    The field delimiter for reading CSV data.
+                             Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_expression: String,
+    arg_input: Option<String>,
+    flag_groupby: Option<SelectColumns>,
+    flag_order_by: Option<String>,
+    flag_time: Option<SelectColumns>,
+    flag_no_headers: bool,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let groupby_sel = args
+        .flag_groupby
+        .map(|cols| cols.selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    let time_column = args
+        .flag_time
+        .map(|col| col.single_selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    let mut program = WindowAggregationProgram::parse(
+        &args.arg_expression,
+        &headers,
+        time_column,
+        args.flag_order_by.is_some(),
+    )?;
+
+    if !args.flag_no_headers {
+        let mut output_headers = headers.clone();
+        output_headers.extend(program.headers());
+
+        wtr.write_byte_record(&output_headers)?;
+    }
+
+    let collect_group = |record: &csv::ByteRecord| {
+        groupby_sel
+            .as_ref()
+            .map(|sel| sel.collect(record))
+            .unwrap_or_default()
+    };
+
+    if let Some(spec) = &args.flag_order_by {
+        let keys = SortKeys::parse(spec, &headers, args.flag_no_headers, false, false, None)?;
+
+        // NOTE: computed key cells are appended to the records, so we keep
+        // track of their original length to be able to truncate them.
+        let mut rows = Vec::new();
+
+        for (index, result) in rdr.into_byte_records().enumerate() {
+            let mut record = result?;
+            let len = record.len();
+            keys.compute(index, &mut record)?;
+
+            rows.push((collect_group(&record), index, len, record));
+        }
+
+        // NOTE: sorting by group first so that partitions are contiguous, which
+        // makes it easy to find rows tied with the previous one.
+        rows.sort_by(|(g1, _, _, r1), (g2, _, _, r2)| g1.cmp(g2).then_with(|| keys.cmp(r1, r2)));
+
+        let tied = (0..rows.len())
+            .map(|i| {
+                i > 0
+                    && rows[i - 1].0 == rows[i].0
+                    && keys.cmp(&rows[i - 1].3, &rows[i].3) == Ordering::Equal
+            })
+            .collect::<Vec<_>>();
+
+        // NOTE: rows are processed in sorted order, but written in their
+        // original order, hence the need to remember where they belong.
+        let mut order = VecDeque::with_capacity(rows.len());
+        let mut output: Vec<Option<csv::ByteRecord>> = vec![None; rows.len()];
+
+        for ((group, index, len, mut record), tied) in rows.into_iter().zip(tied) {
+            record.truncate(len);
+            program.run_with_record(group, index, tied, &record)?;
+            order.push_back(index);
+
+            while let Some(output_record) = program.pop() {
+                output[order.pop_front().unwrap()] = Some(output_record);
+            }
+        }
+
+        program.finish();
+
+        while let Some(output_record) = program.pop() {
+            output[order.pop_front().unwrap()] = Some(output_record);
+        }
+
+        for output_record in output.into_iter().flatten() {
+            wtr.write_byte_record(&output_record)?;
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        program.run_with_record(collect_group(&record), index, false, &record)?;
+
+        while let Some(output_record) = program.pop() {
+            wtr.write_byte_record(&output_record)?;
+        }
+
+        index += 1;
+    }
+
+    program.finish();
+
+    while let Some(output_record) = program.pop() {
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -0,0 +1,170 @@
+use std::cmp::Ordering;
+
+use icu_collator::{Collator, CollatorOptions, Numeric};
+use icu_provider::DataLocale;
+
+/// Order `a` and `b` "naturally", i.e. comparing sequences of ascii digits
+/// as numbers, so that "v2" comes before "v10".
+pub fn natural_cmp(a: &[u8], b: &[u8]) -> Ordering {
+    let mut i = 0;
+    let mut j = 0;
+
+    loop {
+        match (a.get(i), b.get(j)) {
+            (None, None) => return Ordering::Equal,
+            (None, _) => return Ordering::Less,
+            (_, None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let start_i = i;
+                let start_j = j;
+
+                while i < a.len() && a[i].is_ascii_digit() {
+                    i += 1;
+                }
+
+                while j < b.len() && b[j].is_ascii_digit() {
+                    j += 1;
+                }
+
+                let x = trim_leading_zeros(&a[start_i..i]);
+                let y = trim_leading_zeros(&b[start_j..j]);
+
+                match x.len().cmp(&y.len()).then_with(|| x.cmp(y)) {
+                    Ordering::Equal => (),
+                    non_eq => return non_eq,
+                }
+            }
+            (Some(x), Some(y)) => match x.cmp(y) {
+                Ordering::Equal => {
+                    i += 1;
+                    j += 1;
+                }
+                non_eq => return non_eq,
+            },
+        }
+    }
+}
+
+fn trim_leading_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|d| **d == b'0').count();
+
+    &digits[zeros..]
+}
+
+// NOTE: unicode collation relies on the Unicode Collation Algorithm, as
+// tailored by CLDR for the given locale, which means case & accents are only
+// used to break ties between strings that would otherwise be equal. Ties
+// remaining after that, e.g. "file9" & "file09" in natural order, are broken
+// using byte order, so that the ordering stays total & deterministic.
+pub enum Collation {
+    Natural,
+    Unicode(Box<Collator>),
+}
+
+impl Collation {
+    pub fn new(natural: bool, locale: Option<&str>) -> Result<Option<Self>, String> {
+        let locale = match locale {
+            None => return Ok(natural.then_some(Self::Natural)),
+            Some(l) => l,
+        };
+
+        let data_locale = locale
+            .parse::<DataLocale>()
+            .map_err(|_| format!("invalid collation locale \"{}\"!", locale))?;
+
+        let mut options = CollatorOptions::new();
+
+        if natural {
+            options.numeric = Some(Numeric::On);
+        }
+
+        // NOTE: collation data is compiled in, and locales without any
+        // tailoring, e.g. "en" but also unknown ones such as "zz", fall back
+        // to the root collation, so this cannot fail.
+        let collator =
+            Collator::try_new(&data_locale, options).expect("collation data should be compiled in");
+
+        Ok(Some(Self::Unicode(Box::new(collator))))
+    }
+
+    pub fn cmp(&self, a: &[u8], b: &[u8]) -> Ordering {
+        match self {
+            Self::Natural => natural_cmp(a, b).then_with(|| a.cmp(b)),
+            Self::Unicode(collator) => collator.compare_utf8(a, b).then_with(|| a.cmp(b)),
+        }
+    }
+
+    pub fn iter_cmp<'a, L, R>(&self, mut a: L, mut b: R) -> Ordering
+    where
+        L: Iterator<Item = &'a [u8]>,
+        R: Iterator<Item = &'a [u8]>,
+    {
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, _) => return Ordering::Less,
+                (_, None) => return Ordering::Greater,
+                (Some(x), Some(y)) => match self.cmp(x, y) {
+                    Ordering::Equal => (),
+                    non_eq => return non_eq,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(collation: &Collation, strings: &[&'static str]) -> Vec<&'static str> {
+        let mut strings = strings.to_vec();
+        strings.sort_by(|a, b| collation.cmp(a.as_bytes(), b.as_bytes()));
+        strings
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let collation = Collation::new(true, None).unwrap().unwrap();
+
+        assert_eq!(
+            sorted(&collation, &["file10", "file9", "file1", "file09b", "f"]),
+            vec!["f", "file1", "file9", "file09b", "file10"]
+        );
+        assert_eq!(
+            sorted(&collation, &["v1.10.0", "v1.2.0", "v1.2"]),
+            vec!["v1.2", "v1.2.0", "v1.10.0"]
+        );
+        assert_eq!(collation.cmp(b"file9", b"file09"), Ordering::Greater,);
+        assert_eq!(collation.cmp(b"a01", b"a1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_unicode_collation() {
+        let collation = Collation::new(false, Some("fr")).unwrap().unwrap();
+
+        assert_eq!(
+            sorted(
+                &collation,
+                &["zèbre", "Été", "eta", "été", "Ete", "abricot"]
+            ),
+            vec!["abricot", "eta", "Ete", "été", "Été", "zèbre"]
+        );
+
+        let collation = Collation::new(true, Some("en")).unwrap().unwrap();
+
+        assert_eq!(
+            sorted(&collation, &["Item 10", "item 9", "Item 1"]),
+            vec!["Item 1", "item 9", "Item 10"]
+        );
+
+        assert!(Collation::new(false, Some("not a locale!")).is_err());
+
+        // Unknown locales fall back to the root collation
+        let root = Collation::new(false, Some("und")).unwrap().unwrap();
+        let unknown = Collation::new(false, Some("zz")).unwrap().unwrap();
+        let strings = ["zèbre", "Été", "eta", "été", "Ete", "abricot"];
+
+        assert_eq!(sorted(&unknown, &strings), sorted(&root, &strings));
+    }
+}
@@ -0,0 +1,208 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+// NOTE: we derive the second hash from the first one, as per Kirsch &
+// Mitzenmacher's "Less Hashing, Same Performance" scheme.
+fn hash_pair<T: Hash + ?Sized>(value: &T) -> (u64, u64) {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    let h1 = hasher.finish();
+
+    // splitmix64 finalizer
+    let mut h2 = h1.wrapping_add(0x9e3779b97f4a7c15);
+    h2 = (h2 ^ (h2 >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    h2 = (h2 ^ (h2 >> 27)).wrapping_mul(0x94d049bb133111eb);
+    h2 ^= h2 >> 31;
+
+    (h1, h2 | 1)
+}
+
+fn optimal_num_bits(capacity: usize, error_rate: f64) -> u64 {
+    let ln2 = std::f64::consts::LN_2;
+
+    (-(capacity as f64) * error_rate.ln() / (ln2 * ln2)).ceil() as u64
+}
+
+#[derive(Debug)]
+struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    capacity: usize,
+    len: usize,
+}
+
+impl BloomFilter {
+    fn new(capacity: usize, error_rate: f64, max_bytes: Option<usize>) -> Self {
+        let mut num_bits = optimal_num_bits(capacity, error_rate);
+
+        if let Some(max) = max_bytes {
+            num_bits = num_bits.min(max as u64 * 8);
+        }
+
+        let num_words = num_bits.div_ceil(64).max(1);
+        let num_bits = num_words * 64;
+
+        let num_hashes = ((num_bits as f64 / capacity as f64) * std::f64::consts::LN_2)
+            .round()
+            .max(1.0) as u32;
+
+        Self {
+            bits: vec![0; num_words as usize],
+            num_bits,
+            num_hashes,
+            capacity,
+            len: 0,
+        }
+    }
+
+    fn bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    fn positions(&self, (h1, h2): (u64, u64)) -> impl Iterator<Item = u64> + '_ {
+        (0..self.num_hashes as u64)
+            .map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+
+    fn contains(&self, hashes: (u64, u64)) -> bool {
+        self.positions(hashes)
+            .all(|p| self.bits[(p / 64) as usize] & (1 << (p % 64)) != 0)
+    }
+
+    fn insert(&mut self, hashes: (u64, u64)) {
+        let positions = self.positions(hashes).collect::<Vec<_>>();
+
+        for p in positions {
+            self.bits[(p / 64) as usize] |= 1 << (p % 64);
+        }
+
+        self.len += 1;
+    }
+
+    fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    fn estimated_false_positive_rate(&self) -> f64 {
+        let set_bits: u64 = self.bits.iter().map(|w| w.count_ones() as u64).sum();
+
+        (set_bits as f64 / self.num_bits as f64).powi(self.num_hashes as i32)
+    }
+}
+
+/// A scalable Bloom filter, as described by Almeida et al., adding new
+/// filters with tightening error rates whenever the current one is full, so
+/// that the compound error rate stays bounded by the target one.
+///
+/// When given a memory budget, the filter stops growing once the budget is
+/// exhausted and keeps inserting into its last filter, which means the actual
+/// error rate will rise above the target one.
+#[derive(Debug)]
+pub struct ScalableBloomFilter {
+    filters: Vec<BloomFilter>,
+    error_rate: f64,
+    max_bytes: Option<usize>,
+    saturated: bool,
+}
+
+const INITIAL_CAPACITY: usize = 1 << 16;
+const GROWTH_FACTOR: usize = 2;
+const TIGHTENING_RATIO: f64 = 0.5;
+
+impl ScalableBloomFilter {
+    pub fn new(error_rate: f64, max_bytes: Option<usize>) -> Self {
+        let mut filter = Self {
+            filters: Vec::new(),
+            error_rate,
+            max_bytes,
+            saturated: false,
+        };
+
+        filter.grow();
+
+        filter
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.filters.iter().map(|f| f.bytes()).sum()
+    }
+
+    fn grow(&mut self) {
+        let i = self.filters.len();
+
+        let capacity = INITIAL_CAPACITY * GROWTH_FACTOR.pow(i as u32);
+        let error_rate =
+            self.error_rate * (1.0 - TIGHTENING_RATIO) * TIGHTENING_RATIO.powi(i as i32);
+
+        let remaining_bytes = self.max_bytes.map(|max| max.saturating_sub(self.bytes()));
+
+        if let Some(remaining) = remaining_bytes {
+            if optimal_num_bits(capacity, error_rate).div_ceil(8) > remaining as u64 {
+                self.saturated = true;
+
+                // NOTE: the first filter is always created, even if truncated
+                if i > 0 {
+                    return;
+                }
+            }
+        }
+
+        self.filters
+            .push(BloomFilter::new(capacity, error_rate, remaining_bytes));
+    }
+
+    /// Insert the given value and return whether it was (probably) not
+    /// already in the filter.
+    pub fn insert<T: Hash + ?Sized>(&mut self, value: &T) -> bool {
+        let hashes = hash_pair(value);
+
+        if self.filters.iter().any(|f| f.contains(hashes)) {
+            return false;
+        }
+
+        let last = self.filters.last_mut().unwrap();
+        last.insert(hashes);
+
+        if last.is_full() && !self.saturated {
+            self.grow();
+        }
+
+        true
+    }
+
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        1.0 - self
+            .filters
+            .iter()
+            .map(|f| 1.0 - f.estimated_false_positive_rate())
+            .product::<f64>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalable_bloom_filter() {
+        let mut filter = ScalableBloomFilter::new(0.001, None);
+
+        let false_positives = (0..300_000).filter(|i| !filter.insert(i)).count();
+
+        assert!(filter.filters.len() > 1);
+        assert!(!filter.saturated);
+        assert!(false_positives < 300);
+        assert!((0..300_000).all(|i| !filter.insert(&i)));
+        assert!(filter.estimated_false_positive_rate() < 0.002);
+
+        let mut filter = ScalableBloomFilter::new(0.001, Some(1024));
+
+        for i in 0..10_000 {
+            filter.insert(&i);
+        }
+
+        assert_eq!(filter.bytes(), 1024);
+        assert!(filter.saturated);
+        assert!(filter.estimated_false_positive_rate() > 0.1);
+    }
+}
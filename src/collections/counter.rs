@@ -1,4 +1,4 @@
-use std::cmp::Reverse;
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
 use std::hash::Hash;
 
@@ -26,6 +26,14 @@ impl<K: Eq + Hash + Send + Ord> ExactCounter<K> {
     }
 
     pub fn into_total_and_sorted_vec(self, parallel: bool) -> (u64, Vec<(K, u64)>) {
+        self.into_total_and_sorted_vec_by(parallel, |a, b| a.cmp(b))
+    }
+
+    // NOTE: `compare` is used to order keys having the same count.
+    pub fn into_total_and_sorted_vec_by<F>(self, parallel: bool, compare: F) -> (u64, Vec<(K, u64)>)
+    where
+        F: Fn(&K, &K) -> Ordering + Sync,
+    {
         let mut total: u64 = 0;
 
         let mut items = self
@@ -38,9 +46,12 @@ impl<K: Eq + Hash + Send + Ord> ExactCounter<K> {
             .collect::<Vec<_>>();
 
         if parallel {
-            items.par_sort_unstable_by(|a, b| a.1.cmp(&b.1).reverse().then_with(|| a.0.cmp(&b.0)));
+            items.par_sort_unstable_by(|a, b| {
+                a.1.cmp(&b.1).reverse().then_with(|| compare(&a.0, &b.0))
+            });
         } else {
-            items.sort_unstable_by(|a, b| a.1.cmp(&b.1).reverse().then_with(|| a.0.cmp(&b.0)));
+            items
+                .sort_unstable_by(|a, b| a.1.cmp(&b.1).reverse().then_with(|| compare(&a.0, &b.0)));
         }
 
         (total, items)
@@ -112,9 +123,72 @@ impl<K: Eq + Hash + Send + Ord> ApproxCounter<K> {
     }
 }
 
+// NOTE: weights are summed instead of counting occurrences, and keys are
+// always fully sorted so that the total is summed in the same order as the
+// items, avoiding spurious float remainders.
+pub struct WeightedCounter<K: Eq + Hash + Send + Ord> {
+    map: HashMap<K, f64>,
+}
+
+impl<K: Eq + Hash + Send + Ord> WeightedCounter<K> {
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, key: K, weight: f64) {
+        if weight == 0.0 {
+            return;
+        }
+
+        self.map
+            .entry(key)
+            .and_modify(|total| *total += weight)
+            .or_insert(weight);
+    }
+
+    pub fn into_total_and_items_by<F>(
+        self,
+        limit: Option<usize>,
+        parallel: bool,
+        compare: F,
+    ) -> (f64, Vec<(K, f64)>)
+    where
+        F: Fn(&K, &K) -> Ordering + Sync,
+    {
+        let mut items = self.map.into_iter().collect::<Vec<_>>();
+
+        let cmp =
+            |a: &(K, f64), b: &(K, f64)| b.1.total_cmp(&a.1).then_with(|| compare(&a.0, &b.0));
+
+        if parallel {
+            items.par_sort_unstable_by(cmp);
+        } else {
+            items.sort_unstable_by(cmp);
+        }
+
+        let total = items.iter().map(|(_, w)| w).sum();
+
+        if let Some(k) = limit {
+            items.truncate(k);
+        }
+
+        (total, items)
+    }
+}
+
+fn into_float_counts<K>((total, items): (u64, Vec<(K, u64)>)) -> (f64, Vec<(K, f64)>) {
+    (
+        total as f64,
+        items.into_iter().map(|(k, c)| (k, c as f64)).collect(),
+    )
+}
+
 pub enum Counter<K: Eq + Hash + Send + Ord> {
     Exact(ExactCounter<K>),
     Approximate(Box<ApproxCounter<K>>),
+    Weighted(WeightedCounter<K>),
 }
 
 impl<K: Eq + Hash + Send + Ord> Counter<K> {
@@ -125,7 +199,12 @@ impl<K: Eq + Hash + Send + Ord> Counter<K> {
         }
     }
 
-    pub fn add(&mut self, key: K) {
+    pub fn weighted() -> Self {
+        Self::Weighted(WeightedCounter::new())
+    }
+
+    // NOTE: the weight is ignored by non-weighted counters.
+    pub fn add_with_weight(&mut self, key: K, weight: f64) {
         match self {
             Self::Exact(inner) => {
                 inner.add(key);
@@ -133,6 +212,9 @@ impl<K: Eq + Hash + Send + Ord> Counter<K> {
             Self::Approximate(inner) => {
                 inner.add(key);
             }
+            Self::Weighted(inner) => {
+                inner.add(key, weight);
+            }
         }
     }
 
@@ -140,10 +222,44 @@ impl<K: Eq + Hash + Send + Ord> Counter<K> {
         self,
         limit: Option<usize>,
         parallel: bool,
-    ) -> (u64, Vec<(K, u64)>) {
+    ) -> (f64, Vec<(K, f64)>) {
+        match self {
+            Self::Exact(inner) => into_float_counts(inner.into_total_and_items(limit, parallel)),
+            Self::Approximate(inner) => into_float_counts(inner.into_total_and_top()),
+            Self::Weighted(inner) => {
+                inner.into_total_and_items_by(limit, parallel, |a, b| a.cmp(b))
+            }
+        }
+    }
+
+    // NOTE: since keys are compared using a custom function, we cannot rely
+    // on a heap to find the top items and need to sort all of them instead.
+    pub fn into_total_and_items_by<F>(
+        self,
+        limit: Option<usize>,
+        parallel: bool,
+        compare: F,
+    ) -> (f64, Vec<(K, f64)>)
+    where
+        F: Fn(&K, &K) -> Ordering + Sync,
+    {
         match self {
-            Self::Exact(inner) => inner.into_total_and_items(limit, parallel),
-            Self::Approximate(inner) => inner.into_total_and_top(),
+            Self::Exact(inner) => {
+                let (total, mut items) = inner.into_total_and_sorted_vec_by(parallel, compare);
+
+                if let Some(k) = limit {
+                    items.truncate(k);
+                }
+
+                into_float_counts((total, items))
+            }
+            Self::Approximate(inner) => {
+                let (total, mut items) = inner.into_total_and_top();
+                items.sort_by(|a, b| a.1.cmp(&b.1).reverse().then_with(|| compare(&a.0, &b.0)));
+
+                into_float_counts((total, items))
+            }
+            Self::Weighted(inner) => inner.into_total_and_items_by(limit, parallel, compare),
         }
     }
 }
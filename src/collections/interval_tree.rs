@@ -0,0 +1,133 @@
+// A static interval tree, implicitly laid over a sorted array of intervals.
+// Each node of the implicit balanced tree (i.e. the middle of any given
+// slice of the array) stores the maximum end of its subtree, which makes
+// it possible to prune whole subtrees while querying.
+// Intervals are considered closed, i.e. [start, end].
+#[derive(Debug)]
+pub struct IntervalTree<K, V> {
+    intervals: Vec<(K, K, V)>,
+    max_ends: Vec<K>,
+}
+
+impl<K: Copy + PartialOrd, V> IntervalTree<K, V> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+            max_ends: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, start: K, end: K, value: V) {
+        self.intervals.push((start, end, value));
+    }
+
+    fn index_max_ends(&mut self, lo: usize, hi: usize) -> Option<K> {
+        if lo >= hi {
+            return None;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let mut max_end = self.intervals[mid].1;
+
+        for child_max_end in [
+            self.index_max_ends(lo, mid),
+            self.index_max_ends(mid + 1, hi),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if child_max_end > max_end {
+                max_end = child_max_end;
+            }
+        }
+
+        self.max_ends[mid] = max_end;
+
+        Some(max_end)
+    }
+
+    // NOTE: the sort being stable, intervals starting at the same point
+    // will be yielded in insertion order.
+    pub fn build(&mut self) {
+        self.intervals
+            .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        self.max_ends = self.intervals.iter().map(|(_, end, _)| *end).collect();
+        self.index_max_ends(0, self.intervals.len());
+    }
+
+    fn collect_overlapping<'a>(
+        &'a self,
+        lo: usize,
+        hi: usize,
+        start: K,
+        end: K,
+        matches: &mut Vec<&'a (K, K, V)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+
+        if self.max_ends[mid] < start {
+            return;
+        }
+
+        self.collect_overlapping(lo, mid, start, end, matches);
+
+        let interval = &self.intervals[mid];
+
+        if interval.0 > end {
+            return;
+        }
+
+        if interval.1 >= start {
+            matches.push(interval);
+        }
+
+        self.collect_overlapping(mid + 1, hi, start, end, matches);
+    }
+
+    // NOTE: `build` must have been called before querying the tree.
+    pub fn overlapping(&self, start: K, end: K) -> Vec<&(K, K, V)> {
+        let mut matches = Vec::new();
+
+        self.collect_overlapping(0, self.intervals.len(), start, end, &mut matches);
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_tree() {
+        let mut tree = IntervalTree::new();
+
+        tree.insert(15, 20, "a");
+        tree.insert(10, 30, "b");
+        tree.insert(17, 19, "c");
+        tree.insert(5, 20, "d");
+        tree.insert(12, 15, "e");
+        tree.insert(30, 40, "f");
+
+        tree.build();
+
+        let values = |start, end| {
+            tree.overlapping(start, end)
+                .into_iter()
+                .map(|(_, _, v)| *v)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(values(0, 4), Vec::<&str>::new());
+        assert_eq!(values(6, 7), vec!["d"]);
+        assert_eq!(values(14, 16), vec!["d", "b", "e", "a"]);
+        assert_eq!(values(30, 30), vec!["b", "f"]);
+        assert_eq!(values(21, 29), vec!["b"]);
+        assert_eq!(values(41, 50), Vec::<&str>::new());
+    }
+}
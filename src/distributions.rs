@@ -0,0 +1,273 @@
+// NOTE: the special functions hereafter are adapted from "Numerical Recipes in C",
+// W. H. Press et al., 2nd edition, chapter 6. They are used to compute the
+// p-values of statistical tests.
+use std::f64::consts::PI;
+
+const EPSILON: f64 = 1e-15;
+const FPMIN: f64 = 1e-300;
+const MAX_ITERATIONS: usize = 1000;
+
+// Lanczos approximation, with g = 7
+const LANCZOS_COEFFICIENTS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.5203681218851,
+    -1259.1392167224028,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507343278686905,
+    -0.13857109526572012,
+    9.984_369_578_019_572e-6,
+    1.5056327351493116e-7,
+];
+
+pub fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+
+    let mut a = LANCZOS_COEFFICIENTS[0];
+
+    for (i, c) in LANCZOS_COEFFICIENTS.iter().enumerate().skip(1) {
+        a += c / (x + i as f64);
+    }
+
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+fn gamma_series(a: f64, x: f64) -> f64 {
+    let mut ap = a;
+    let mut delta = 1.0 / a;
+    let mut sum = delta;
+
+    for _ in 0..MAX_ITERATIONS {
+        ap += 1.0;
+        delta *= x / ap;
+        sum += delta;
+
+        if delta.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+fn gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / FPMIN;
+    let mut d = 1.0 / b;
+    let mut h = d;
+
+    for i in 1..MAX_ITERATIONS {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+
+        d = an * d + b;
+
+        if d.abs() < FPMIN {
+            d = FPMIN;
+        }
+
+        c = b + an / c;
+
+        if c.abs() < FPMIN {
+            c = FPMIN;
+        }
+
+        d = 1.0 / d;
+
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+// Regularized upper incomplete gamma function Q(a, x)
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_continued_fraction(a, x)
+    }
+}
+
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let qab = a + b;
+    let qap = a + 1.0;
+    let qam = a - 1.0;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+
+    if d.abs() < FPMIN {
+        d = FPMIN;
+    }
+
+    d = 1.0 / d;
+
+    let mut h = d;
+
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+
+        d = 1.0 + aa * d;
+
+        if d.abs() < FPMIN {
+            d = FPMIN;
+        }
+
+        c = 1.0 + aa / c;
+
+        if c.abs() < FPMIN {
+            c = FPMIN;
+        }
+
+        d = 1.0 / d;
+        h *= d * c;
+
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+
+        d = 1.0 + aa * d;
+
+        if d.abs() < FPMIN {
+            d = FPMIN;
+        }
+
+        c = 1.0 + aa / c;
+
+        if c.abs() < FPMIN {
+            c = FPMIN;
+        }
+
+        d = 1.0 / d;
+
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+// Regularized incomplete beta function I_x(a, b)
+pub fn beta_i(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let bt = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    if x < (a + 1.0) / (a + b + 2.0) {
+        bt * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - bt * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+// Survival function, i.e. P(Z > z), of the standard normal distribution
+pub fn normal_sf(z: f64) -> f64 {
+    let x = z / std::f64::consts::SQRT_2;
+
+    let erfc = if x >= 0.0 {
+        gamma_q(0.5, x * x)
+    } else {
+        2.0 - gamma_q(0.5, x * x)
+    };
+
+    0.5 * erfc
+}
+
+// Two-sided p-value of Student's t distribution, i.e. P(|T| > |t|)
+pub fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    beta_i(df / 2.0, 0.5, df / (df + t * t))
+}
+
+// Survival function of the chi-squared distribution
+pub fn chi2_sf(x: f64, df: f64) -> f64 {
+    gamma_q(df / 2.0, x / 2.0)
+}
+
+// Survival function of the F distribution
+pub fn f_sf(f: f64, df1: f64, df2: f64) -> f64 {
+    if f <= 0.0 {
+        return 1.0;
+    }
+
+    beta_i(df2 / 2.0, df1 / 2.0, df2 / (df2 + df1 * f))
+}
+
+// Survival function of the Kolmogorov distribution
+pub fn kolmogorov_sf(lambda: f64) -> f64 {
+    let a2 = -2.0 * lambda * lambda;
+
+    let mut sign = 2.0;
+    let mut sum = 0.0;
+    let mut previous_term = 0.0;
+
+    for j in 1..=100 {
+        let j = j as f64;
+        let term = sign * (a2 * j * j).exp();
+
+        sum += term;
+
+        if term.abs() <= 0.001 * previous_term || term.abs() <= 1e-8 * sum {
+            return sum.clamp(0.0, 1.0);
+        }
+
+        sign = -sign;
+        previous_term = term.abs();
+    }
+
+    // NOTE: the series does not converge for small lambdas
+    1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_ln_gamma() {
+        assert_close(ln_gamma(1.0), 0.0);
+        assert_close(ln_gamma(5.0), 24.0_f64.ln());
+        assert_close(ln_gamma(0.5), PI.sqrt().ln());
+    }
+
+    #[test]
+    fn test_distributions() {
+        assert_close(normal_sf(0.0), 0.5);
+        assert_close(normal_sf(1.959963984540054), 0.025);
+        assert_close(normal_sf(-1.959963984540054), 0.975);
+        assert_close(student_t_two_sided(2.228138851986274, 10.0), 0.05);
+        assert_close(student_t_two_sided(0.0, 10.0), 1.0);
+        assert_close(chi2_sf(3.841458820694124, 1.0), 0.05);
+        assert_close(chi2_sf(9.487729036781154, 4.0), 0.05);
+        assert_close(f_sf(4.256494729093748, 2.0, 9.0), 0.05);
+        assert_close(kolmogorov_sf(1.3580986393225505), 0.05);
+        assert_close(kolmogorov_sf(0.01), 1.0);
+    }
+}
@@ -11,11 +11,14 @@ use colored::Colorize;
 use docopt::Docopt;
 
 mod cmd;
+mod collation;
 mod collections;
 mod config;
 mod dates;
+mod distributions;
 mod graph;
 mod json;
+mod minhash;
 mod moonblade;
 mod ratatui;
 mod read;
@@ -57,15 +60,20 @@ macro_rules! command_list {
     frequency (freq) Show frequency tables
     groupby          Aggregate data by groups of a CSV file
     stats            Compute basic statistics
+    profile          Profile column types, nulls, shapes & more
     agg              Aggregate data from CSV file
     bins             Dispatch numeric columns into bins
+    outliers         Detect & flag outliers in numeric columns
+    test             Run statistical hypothesis tests
+    regress          Fit linear & logistic regressions
 
 ## Combine multiple CSV files
-    cat         Concatenate by row or column
-    join        Join CSV files
-    regex-join  Fuzzy join CSV files using regex patterns
-    url-join    Join CSV files on url prefixes
-    merge       Merge multiple similar already sorted CSV files
+    cat           Concatenate by row or column
+    join          Join CSV files
+    regex-join    Fuzzy join CSV files using regex patterns
+    url-join      Join CSV files on url prefixes
+    interval-join Join CSV files on overlapping intervals
+    merge         Merge multiple similar already sorted CSV files
 
 ## Add, transform, drop and move columns
     select      Select columns from a CSV file
@@ -76,6 +84,7 @@ macro_rules! command_list {
     flatmap     Emit one row per value yielded by an expression evaluated for each CSV row
     fill        Fill empty cells
     blank       Blank down contiguous identical cell values
+    window      Compute window functions, e.g. lag, rank or rolling aggregates
 
 ## Format, convert & recombobulate
     behead        Drop header from CSV file
@@ -90,6 +99,8 @@ macro_rules! command_list {
     scrape        Scrape HTML into CSV data
     reverse       Reverse rows of CSV data
     transpose (t) Transpose CSV file
+    pivot         Reshape CSV data from long to wide
+    melt          Reshape CSV data from wide to long
 
 ## Split a CSV file into multiple
     split       Split CSV data into chunks
@@ -263,19 +274,26 @@ enum Command {
     Hist,
     Implode,
     Input,
+    #[serde(rename = "interval-join")]
+    IntervalJoin,
     Join,
     Map,
     Matrix,
+    Melt,
     Merge,
     Network,
+    Outliers,
     P,
     Parallel,
     Partition,
+    Pivot,
     Plot,
+    Profile,
     Progress,
     Range,
     #[serde(rename = "regex-join")]
     RegexJoin,
+    Regress,
     Rename,
     Reverse,
     Sample,
@@ -287,6 +305,7 @@ enum Command {
     Sort,
     Split,
     Stats,
+    Test,
     To,
     Tokenize,
     Top,
@@ -298,6 +317,7 @@ enum Command {
     V,
     View,
     Vocab,
+    Window,
 }
 
 impl Command {
@@ -347,17 +367,23 @@ impl Command {
             Command::Hist => cmd::hist::run(argv),
             Command::Implode => cmd::implode::run(argv),
             Command::Input => cmd::input::run(argv),
+            Command::IntervalJoin => cmd::interval_join::run(argv),
             Command::Join => cmd::join::run(argv),
             Command::Network => cmd::network::run(argv),
+            Command::Outliers => cmd::outliers::run(argv),
             Command::Map => cmd::map::run(argv),
             Command::Matrix => cmd::matrix::run(argv),
+            Command::Melt => cmd::melt::run(argv),
             Command::Merge => cmd::merge::run(argv),
             Command::Parallel | Command::P => cmd::parallel::run(argv),
             Command::Partition => cmd::partition::run(argv),
+            Command::Pivot => cmd::pivot::run(argv),
             Command::Plot => cmd::plot::run(argv),
+            Command::Profile => cmd::profile::run(argv),
             Command::Progress => cmd::progress::run(argv),
             Command::Range => cmd::range::run(argv),
             Command::RegexJoin => cmd::regex_join::run(argv),
+            Command::Regress => cmd::regress::run(argv),
             Command::Rename => cmd::rename::run(argv),
             Command::Reverse => cmd::reverse::run(argv),
             Command::Sample => cmd::sample::run(argv),
@@ -369,6 +395,7 @@ impl Command {
             Command::Sort => cmd::sort::run(argv),
             Command::Split => cmd::split::run(argv),
             Command::Stats => cmd::stats::run(argv),
+            Command::Test => cmd::test::run(argv),
             Command::To => cmd::to::run(argv),
             Command::Tokenize => cmd::tokenize::run(argv),
             Command::Top => cmd::top::run(argv),
@@ -377,6 +404,7 @@ impl Command {
             Command::UrlJoin => cmd::url_join::run(argv),
             Command::View | Command::V => cmd::view::run(argv),
             Command::Vocab => cmd::vocab::run(argv),
+            Command::Window => cmd::window::run(argv),
         }
     }
 }
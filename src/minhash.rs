@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use rand_seeder::Seeder;
+
+const MERSENNE_PRIME: u64 = (1 << 61) - 1;
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Shingling {
+    Chars(usize),
+    Words(usize),
+}
+
+impl Shingling {
+    // NOTE: texts shorter than the shingle size are considered as a single
+    // shingle, so that they can still be compared with one another.
+    pub fn hashes(&self, text: &str) -> Vec<u64> {
+        match self {
+            Self::Chars(k) => {
+                let mut boundaries = text.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+                boundaries.push(text.len());
+
+                if boundaries.len() <= *k {
+                    return vec![hash(text)];
+                }
+
+                boundaries
+                    .windows(k + 1)
+                    .map(|w| hash(&text[w[0]..w[*k]]))
+                    .collect()
+            }
+            Self::Words(k) => {
+                let words = text.split_whitespace().collect::<Vec<_>>();
+
+                if words.len() <= *k {
+                    return vec![hash(&words)];
+                }
+
+                words.windows(*k).map(hash).collect()
+            }
+        }
+    }
+}
+
+// NOTE: permutations are simulated using universal hashing over a mersenne
+// prime. They are generated from a fixed seed so that signatures remain
+// stable across runs.
+pub struct MinHash {
+    permutations: Vec<(u64, u64)>,
+}
+
+impl MinHash {
+    pub fn new(num_permutations: usize) -> Self {
+        let mut rng: ChaCha8Rng = Seeder::from("minhash").into_rng();
+
+        let permutations = (0..num_permutations)
+            .map(|_| {
+                (
+                    rng.random_range(1..MERSENNE_PRIME),
+                    rng.random_range(0..MERSENNE_PRIME),
+                )
+            })
+            .collect();
+
+        Self { permutations }
+    }
+
+    pub fn signature(&self, hashes: &[u64]) -> Vec<u64> {
+        self.permutations
+            .iter()
+            .map(|(a, b)| {
+                hashes
+                    .iter()
+                    .map(|h| {
+                        ((*a as u128 * (*h % MERSENNE_PRIME) as u128 + *b as u128)
+                            % MERSENNE_PRIME as u128) as u64
+                    })
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect()
+    }
+}
+
+/// Estimate the Jaccard similarity of the sets whose MinHash signatures
+/// are given.
+pub fn estimate_jaccard(a: &[u64], b: &[u64]) -> f64 {
+    let equal = a.iter().zip(b.iter()).filter(|(x, y)| x == y).count();
+
+    equal as f64 / a.len() as f64
+}
+
+fn integrate<F: Fn(f64) -> f64>(f: F, start: f64, end: f64) -> f64 {
+    let steps = 100;
+    let step = (end - start) / steps as f64;
+
+    (0..steps)
+        .map(|i| f(start + (i as f64 + 0.5) * step) * step)
+        .sum()
+}
+
+// NOTE: this finds the number of bands & rows per band minimizing the
+// probabilities of false positives & false negatives wrt the given threshold.
+fn optimal_lsh_params(num_permutations: usize, threshold: f64) -> (usize, usize) {
+    let mut best = (1, num_permutations);
+    let mut min_error = f64::INFINITY;
+
+    for bands in 1..=num_permutations {
+        for rows in 1..=(num_permutations / bands) {
+            let probability = |s: f64| 1.0 - (1.0 - s.powi(rows as i32)).powi(bands as i32);
+
+            let false_positives = integrate(probability, 0.0, threshold);
+            let false_negatives = integrate(|s| 1.0 - probability(s), threshold, 1.0);
+
+            let error = false_positives + false_negatives;
+
+            if error < min_error {
+                min_error = error;
+                best = (bands, rows);
+            }
+        }
+    }
+
+    best
+}
+
+/// Locality-sensitive hashing index over MinHash signatures, bucketing items
+/// whose signatures are identical over at least one band.
+pub struct LshIndex {
+    rows: usize,
+    bands: Vec<HashMap<u64, Vec<usize>>>,
+}
+
+impl LshIndex {
+    pub fn new(num_permutations: usize, threshold: f64) -> Self {
+        let (bands, rows) = optimal_lsh_params(num_permutations, threshold);
+
+        Self {
+            rows,
+            bands: (0..bands).map(|_| HashMap::new()).collect(),
+        }
+    }
+
+    pub fn insert(&mut self, id: usize, signature: &[u64]) {
+        for (band, bucket) in signature.chunks(self.rows).zip(self.bands.iter_mut()) {
+            bucket.entry(hash(band)).or_default().push(id);
+        }
+    }
+
+    pub fn candidates(&self, signature: &[u64]) -> Vec<usize> {
+        let mut candidates = Vec::new();
+
+        for (band, bucket) in signature.chunks(self.rows).zip(self.bands.iter()) {
+            if let Some(ids) = bucket.get(&hash(band)) {
+                candidates.extend(ids);
+            }
+        }
+
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shingling() {
+        assert_eq!(Shingling::Chars(3).hashes("abcd").len(), 2);
+        assert_eq!(Shingling::Chars(3).hashes("ab").len(), 1);
+        assert_eq!(Shingling::Chars(2).hashes("été").len(), 2);
+        assert_eq!(Shingling::Words(2).hashes("the cat is  here").len(), 3);
+        assert_eq!(
+            Shingling::Words(2).hashes("the  cat"),
+            Shingling::Words(2).hashes("the cat")
+        );
+    }
+
+    #[test]
+    fn test_minhash_lsh() {
+        let minhash = MinHash::new(128);
+        let shingling = Shingling::Chars(3);
+        let signature = |text: &str| minhash.signature(&shingling.hashes(text));
+
+        let a = signature("the quick brown fox jumps over the lazy dog");
+        let b = signature("the quick brown fox jumps over the lazy dog!");
+        let c = signature("lorem ipsum dolor sit amet, consectetur adipiscing");
+
+        assert_eq!(estimate_jaccard(&a, &a), 1.0);
+        assert!(estimate_jaccard(&a, &b) > 0.8);
+        assert!(estimate_jaccard(&a, &c) < 0.2);
+
+        let mut index = LshIndex::new(128, 0.8);
+        index.insert(0, &a);
+        index.insert(1, &c);
+
+        assert_eq!(index.candidates(&b), vec![0]);
+    }
+}
@@ -1,4 +1,4 @@
-use tdigest::TDigest;
+use tdigest::{Centroid, TDigest};
 
 const DIGEST_SIZE: usize = 100;
 const BUFFER_SIZE: usize = 512;
@@ -7,6 +7,7 @@ const BUFFER_SIZE: usize = 512;
 pub struct ApproxQuantiles {
     digest: Option<TDigest>,
     buffer: Vec<f64>,
+    weighted_buffer: Vec<(f64, f64)>,
 }
 
 impl ApproxQuantiles {
@@ -14,27 +15,58 @@ impl ApproxQuantiles {
         Self {
             digest: Some(TDigest::new_with_size(DIGEST_SIZE)),
             buffer: Vec::with_capacity(BUFFER_SIZE),
+            weighted_buffer: Vec::new(),
         }
     }
 
     pub fn clear(&mut self) {
         self.digest = Some(TDigest::new_with_size(DIGEST_SIZE));
         self.buffer.clear();
+        self.weighted_buffer.clear();
     }
 
     pub fn flush(&mut self) {
-        if self.buffer.is_empty() {
-            return;
+        if !self.buffer.is_empty() {
+            self.digest = Some(
+                self.digest
+                    .as_mut()
+                    .unwrap()
+                    .merge_unsorted(self.buffer.clone()),
+            );
+
+            self.buffer.clear();
         }
 
-        self.digest = Some(
-            self.digest
-                .as_mut()
-                .unwrap()
-                .merge_unsorted(self.buffer.clone()),
-        );
+        if !self.weighted_buffer.is_empty() {
+            self.weighted_buffer.sort_by(|a, b| a.0.total_cmp(&b.0));
 
-        self.buffer.clear();
+            // NOTE: each weighted value becomes its own centroid, before being
+            // compressed when merged into the current digest.
+            let mut sum = 0.0;
+            let mut count = 0.0;
+
+            let centroids = self
+                .weighted_buffer
+                .iter()
+                .map(|(value, weight)| {
+                    sum += value * weight;
+                    count += weight;
+
+                    Centroid::new(*value, *weight)
+                })
+                .collect::<Vec<_>>();
+
+            let min = self.weighted_buffer[0].0;
+            let max = self.weighted_buffer[self.weighted_buffer.len() - 1].0;
+            let size = centroids.len();
+
+            self.digest = Some(TDigest::merge_digests(vec![
+                self.digest.take().unwrap(),
+                TDigest::new(centroids, sum, count, max, min, size),
+            ]));
+
+            self.weighted_buffer.clear();
+        }
     }
 
     pub fn add(&mut self, value: f64) {
@@ -45,6 +77,18 @@ impl ApproxQuantiles {
         }
     }
 
+    pub fn add_weighted(&mut self, value: f64, weight: f64) {
+        if weight == 0.0 {
+            return;
+        }
+
+        self.weighted_buffer.push((value, weight));
+
+        if self.weighted_buffer.len() == BUFFER_SIZE {
+            self.flush();
+        }
+    }
+
     pub fn finalize(&mut self) {
         self.flush();
     }
@@ -56,6 +100,7 @@ impl ApproxQuantiles {
     pub fn merge(&mut self, other: Self) {
         self.flush();
         self.buffer = other.buffer;
+        self.weighted_buffer = other.weighted_buffer;
         self.digest = Some(TDigest::merge_digests(vec![
             self.digest.take().unwrap(),
             other.digest.unwrap(),
@@ -33,6 +33,14 @@ impl Count {
         self.falsey += 1
     }
 
+    pub fn remove(&mut self, truthy: bool) {
+        if truthy {
+            self.truthy -= 1;
+        } else {
+            self.falsey -= 1;
+        }
+    }
+
     pub fn get_truthy(&self) -> usize {
         self.truthy
     }
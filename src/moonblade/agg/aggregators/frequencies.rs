@@ -98,6 +98,30 @@ impl Frequencies {
             .collect()
     }
 
+    pub fn contains(&self, value: &str) -> bool {
+        self.counter.contains_key(value)
+    }
+
+    // NOTE: Shannon entropy, in bits
+    pub fn entropy(&self) -> Option<f64> {
+        let total = self.counter.values().sum::<u64>() as f64;
+
+        if total == 0.0 {
+            return None;
+        }
+
+        Some(
+            -self
+                .counter
+                .values()
+                .map(|count| {
+                    let p = *count as f64 / total;
+                    p * p.log2()
+                })
+                .sum::<f64>(),
+        )
+    }
+
     pub fn cardinality(&self) -> usize {
         self.counter.len()
     }
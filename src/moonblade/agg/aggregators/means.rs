@@ -0,0 +1,87 @@
+// NOTE: geometric & harmonic means are only defined for positive numbers
+// here, with the exception of the geometric mean of a series containing zeros,
+// which will be zero.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeometricHarmonicMeans {
+    count: usize,
+    log_sum: f64,
+    inverse_sum: f64,
+    has_zero: bool,
+    has_negative: bool,
+}
+
+impl GeometricHarmonicMeans {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn add(&mut self, value: f64) {
+        self.count += 1;
+
+        if value < 0.0 {
+            self.has_negative = true;
+        } else if value == 0.0 {
+            self.has_zero = true;
+        } else {
+            self.log_sum += value.ln();
+            self.inverse_sum += 1.0 / value;
+        }
+    }
+
+    pub fn geometric_mean(&self) -> Option<f64> {
+        if self.count == 0 || self.has_negative {
+            return None;
+        }
+
+        if self.has_zero {
+            return Some(0.0);
+        }
+
+        Some((self.log_sum / self.count as f64).exp())
+    }
+
+    pub fn harmonic_mean(&self) -> Option<f64> {
+        if self.count == 0 || self.has_negative || self.has_zero {
+            return None;
+        }
+
+        Some(self.count as f64 / self.inverse_sum)
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.log_sum += other.log_sum;
+        self.inverse_sum += other.inverse_sum;
+        self.has_zero = self.has_zero || other.has_zero;
+        self.has_negative = self.has_negative || other.has_negative;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geometric_harmonic_means() {
+        let mut means = GeometricHarmonicMeans::new();
+
+        assert_eq!(means.geometric_mean(), None);
+        assert_eq!(means.harmonic_mean(), None);
+
+        for n in [1.0, 2.0, 4.0] {
+            means.add(n);
+        }
+
+        assert!((means.geometric_mean().unwrap() - 2.0).abs() < 1e-12);
+        assert!((means.harmonic_mean().unwrap() - 12.0 / 7.0).abs() < 1e-12);
+
+        means.add(0.0);
+
+        assert_eq!(means.geometric_mean(), Some(0.0));
+        assert_eq!(means.harmonic_mean(), None);
+    }
+}
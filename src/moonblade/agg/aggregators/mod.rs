@@ -6,6 +6,7 @@ mod dates;
 mod extent;
 mod first_last;
 mod frequencies;
+mod means;
 mod numbers;
 mod sum;
 mod types;
@@ -20,8 +21,9 @@ pub use dates::ZonedExtent;
 pub use extent::{ArgExtent, ArgTop, Extent, LexicographicExtent, NumericExtent};
 pub use first_last::{First, Last};
 pub use frequencies::Frequencies;
-pub use numbers::{MedianType, Numbers};
+pub use means::GeometricHarmonicMeans;
+pub use numbers::{MedianType, Numbers, WeightedNumbers};
 pub use sum::Sum;
 pub use types::Types;
-pub use values::Values;
-pub use welford::{CovarianceWelford, Welford};
+pub use values::{OrderKey, OrderedValues, Values};
+pub use welford::{CovarianceWelford, Moments, WeightedWelford, Welford};
@@ -116,7 +116,7 @@ impl Numbers {
         let n = &self.numbers;
         let l = n.len();
 
-        if !(0.0..=1.0).contains(&p) {
+        if l == 0 || !(0.0..=1.0).contains(&p) {
             None
         } else if p == 1.0 {
             Some(n[l - 1])
@@ -139,6 +139,56 @@ impl Numbers {
         }
     }
 
+    // NOTE: median absolute deviation, using an interpolated median
+    pub fn mad(&self) -> Option<f64> {
+        let median = self.median(&MedianType::Interpolation)?.as_float();
+
+        let mut deviations = self
+            .numbers
+            .iter()
+            .map(|n| (n.as_float() - median).abs())
+            .collect::<Vec<_>>();
+
+        deviations.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let count = deviations.len();
+        let midpoint = count / 2;
+
+        Some(if count % 2 == 1 {
+            deviations[midpoint]
+        } else {
+            (deviations[midpoint - 1] + deviations[midpoint]) / 2.0
+        })
+    }
+
+    // NOTE: numbers must be non-negative & are expected to be sorted.
+    // Ref: https://en.wikipedia.org/wiki/Gini_coefficient#Alternative_expressions
+    pub fn gini(&self) -> Option<f64> {
+        let count = self.numbers.len();
+
+        if count == 0 || self.numbers[0].as_float() < 0.0 {
+            return None;
+        }
+
+        let mut sum = 0.0;
+        let mut weighted_sum = 0.0;
+
+        for (i, n) in self.numbers.iter().enumerate() {
+            let n = n.as_float();
+
+            sum += n;
+            weighted_sum += (i + 1) as f64 * n;
+        }
+
+        if sum == 0.0 {
+            return None;
+        }
+
+        let count = count as f64;
+
+        Some((2.0 * weighted_sum) / (count * sum) - (count + 1.0) / count)
+    }
+
     pub fn sparkline(&self, bins: usize) -> String {
         if self.numbers.is_empty() {
             return " ".repeat(bins);
@@ -183,6 +233,83 @@ impl Numbers {
     }
 }
 
+// NOTE: weights are understood as frequencies, i.e. quantiles are computed as
+// if each value was repeated as many times as its weight, using the same
+// inclusive method as `Numbers::quantiles`.
+#[derive(Debug, Clone)]
+pub struct WeightedNumbers {
+    numbers: Vec<(f64, f64)>,
+    total: f64,
+}
+
+impl WeightedNumbers {
+    pub fn new() -> Self {
+        Self {
+            numbers: Vec::new(),
+            total: 0.0,
+        }
+    }
+
+    pub fn add(&mut self, value: f64, weight: f64) {
+        if weight == 0.0 {
+            return;
+        }
+
+        self.numbers.push((value, weight));
+        self.total += weight;
+    }
+
+    pub fn finalize(&mut self, parallel: bool) {
+        let cmp = |a: &(f64, f64), b: &(f64, f64)| a.0.total_cmp(&b.0);
+
+        if parallel {
+            self.numbers.par_sort_unstable_by(cmp);
+        } else {
+            self.numbers.sort_unstable_by(cmp);
+        }
+    }
+
+    fn value_at(&self, position: f64) -> f64 {
+        let mut cumulative = 0.0;
+
+        for (value, weight) in self.numbers.iter() {
+            cumulative += weight;
+
+            if position < cumulative {
+                return *value;
+            }
+        }
+
+        self.numbers[self.numbers.len() - 1].0
+    }
+
+    pub fn quantile(&self, p: f64) -> Option<f64> {
+        if self.numbers.len() < 2 || !(0.0..=1.0).contains(&p) {
+            return None;
+        }
+
+        let position = p * (self.total - 1.0).max(0.0);
+        let index = position.floor();
+
+        let low = self.value_at(index);
+        let high = self.value_at(index + 1.0);
+
+        Some(low + (position - index) * (high - low))
+    }
+
+    pub fn quartiles(&self) -> Option<Vec<f64>> {
+        [0.25, 0.5, 0.75]
+            .into_iter()
+            .map(|p| self.quantile(p))
+            .collect()
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.numbers.extend(other.numbers);
+        self.total += other.total;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,4 +438,33 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_weighted_quartiles() {
+        let mut unit = WeightedNumbers::new();
+
+        for n in [5.0, 1.0, 3.0] {
+            unit.add(n, 1.0);
+        }
+
+        unit.finalize(false);
+
+        assert_eq!(unit.quartiles(), Some(vec![2.0, 3.0, 4.0]));
+
+        let mut weighted = WeightedNumbers::new();
+        weighted.add(3.0, 1.0);
+        weighted.add(1.0, 2.0);
+        weighted.add(5.0, 1.0);
+        weighted.add(8.0, 0.0);
+        weighted.finalize(false);
+
+        // Same as the quartiles of [1, 1, 3, 5]
+        assert_eq!(weighted.quartiles(), Some(vec![1.0, 2.0, 3.5]));
+
+        let mut lone = WeightedNumbers::new();
+        lone.add(4.0, 3.0);
+        lone.finalize(false);
+
+        assert_eq!(lone.quartiles(), None);
+    }
 }
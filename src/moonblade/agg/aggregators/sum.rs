@@ -74,6 +74,18 @@ impl Sum {
         }
     }
 
+    // NOTE: removing a value is done by adding its opposite, which is exact
+    // for integers but may accumulate some error for floats.
+    pub fn remove(&mut self, value: DynamicNumber) {
+        match value {
+            DynamicNumber::Float(f) => self.add(DynamicNumber::Float(-f)),
+            DynamicNumber::Integer(i) => match i.checked_neg() {
+                Some(n) => self.add(DynamicNumber::Integer(n)),
+                None => self.current = None,
+            },
+        }
+    }
+
     pub fn get(&self) -> Option<DynamicNumber> {
         // NOTE: f64 overflow is a little bit more subtle
         match self.current {
@@ -119,4 +131,19 @@ mod tests {
 
         assert_eq!(sum.get(), Some(DynamicNumber::Float(10005.85987)));
     }
+
+    #[test]
+    fn test_remove() {
+        let mut sum = Sum::new();
+        sum.add(DynamicNumber::Integer(3));
+        sum.add(DynamicNumber::Integer(4));
+        sum.remove(DynamicNumber::Integer(3));
+
+        assert_eq!(sum.get(), Some(DynamicNumber::Integer(4)));
+
+        sum.add(DynamicNumber::Float(0.5));
+        sum.remove(DynamicNumber::Integer(4));
+
+        assert_eq!(sum.get(), Some(DynamicNumber::Float(0.5)));
+    }
 }
@@ -1,3 +1,7 @@
+use std::cmp::Ordering;
+
+use crate::moonblade::types::DynamicNumber;
+
 #[derive(Debug, Clone)]
 pub struct Values {
     values: Vec<String>,
@@ -24,3 +28,65 @@ impl Values {
         self.values.extend(other.values);
     }
 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderKey {
+    Number(DynamicNumber),
+    String(String),
+}
+
+impl OrderKey {
+    // NOTE: numbers are sorted before strings
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            (Self::Number(_), Self::String(_)) => Ordering::Less,
+            (Self::String(_), Self::Number(_)) => Ordering::Greater,
+            (Self::String(a), Self::String(b)) => a.cmp(b),
+        }
+    }
+}
+
+// NOTE: values are kept along with their original row index so that ties,
+// or the absence of ordering key, fallback to the original order.
+#[derive(Debug, Clone)]
+pub struct OrderedValues {
+    values: Vec<(Option<OrderKey>, usize, String)>,
+}
+
+impl OrderedValues {
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear()
+    }
+
+    pub fn add(&mut self, key: Option<OrderKey>, index: usize, string: String) {
+        self.values.push((key, index, string));
+    }
+
+    pub fn finalize(&mut self) {
+        self.values.sort_by(|(k1, i1, _), (k2, i2, _)| {
+            let ordering = match (k1, k2) {
+                (Some(k1), Some(k2)) => k1.compare(k2),
+                _ => Ordering::Equal,
+            };
+
+            ordering.then_with(|| i1.cmp(i2))
+        });
+    }
+
+    pub fn join(&self, separator: &str) -> String {
+        self.values
+            .iter()
+            .map(|(_, _, string)| string.as_str())
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.values.extend(other.values);
+    }
+}
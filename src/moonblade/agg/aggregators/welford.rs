@@ -32,6 +32,24 @@ impl Welford {
         self.m2 = m2;
     }
 
+    // NOTE: this reverts the update performed by `add`, so that the
+    // statistics of a sliding window can be maintained in constant time.
+    pub fn remove(&mut self, value: f64) {
+        if self.count <= 1 {
+            self.clear();
+            return;
+        }
+
+        let count = self.count - 1;
+        let delta = value - self.mean;
+        let mean = self.mean - delta / count as f64;
+        let delta2 = value - mean;
+
+        self.count = count;
+        self.mean = mean;
+        self.m2 = (self.m2 - delta * delta2).max(0.0);
+    }
+
     pub fn mean(&self) -> Option<f64> {
         if self.count == 0 {
             return None;
@@ -213,6 +231,174 @@ impl CovarianceWelford {
     }
 }
 
+// NOTE: this is West's weighted incremental algorithm, where counts are
+// replaced by the sum of weights.
+// Ref: https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Weighted_incremental_algorithm
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeightedWelford {
+    weight_sum: f64,
+    mean: f64,
+    s: f64,
+}
+
+impl WeightedWelford {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.weight_sum = 0.0;
+        self.mean = 0.0;
+        self.s = 0.0;
+    }
+
+    pub fn add(&mut self, value: f64, weight: f64) {
+        if weight == 0.0 {
+            return;
+        }
+
+        self.weight_sum += weight;
+
+        let mean_old = self.mean;
+        self.mean += (weight / self.weight_sum) * (value - mean_old);
+        self.s += weight * (value - mean_old) * (value - self.mean);
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.weight_sum == 0.0 {
+            return None;
+        }
+
+        Some(self.mean)
+    }
+
+    pub fn variance(&self) -> Option<f64> {
+        if self.weight_sum == 0.0 {
+            return None;
+        }
+
+        Some(self.s / self.weight_sum)
+    }
+
+    pub fn stdev(&self) -> Option<f64> {
+        self.variance().map(|v| v.sqrt())
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        if other.weight_sum == 0.0 {
+            return;
+        }
+
+        if self.weight_sum == 0.0 {
+            *self = other;
+            return;
+        }
+
+        let total = self.weight_sum + other.weight_sum;
+        let mean_diff_squared = (self.mean - other.mean).powi(2);
+
+        self.mean = ((self.weight_sum * self.mean) + (other.weight_sum * other.mean)) / total;
+        self.s =
+            self.s + other.s + ((self.weight_sum * other.weight_sum * mean_diff_squared) / total);
+        self.weight_sum = total;
+    }
+}
+
+// NOTE: this extends Welford's algorithm to the third & fourth central moments.
+// Ref: https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Higher-order_statistics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Moments {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+}
+
+impl Moments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn add(&mut self, value: f64) {
+        let n1 = self.count as f64;
+        self.count += 1;
+        let n = self.count as f64;
+
+        let delta = value - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term1 = delta * delta_n * n1;
+
+        self.mean += delta_n;
+        self.m4 += term1 * delta_n2 * (n * n - 3.0 * n + 3.0) + 6.0 * delta_n2 * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term1 * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term1;
+    }
+
+    // NOTE: this is the population skewness, i.e. Fisher's moment coefficient
+    pub fn skewness(&self) -> Option<f64> {
+        if self.count == 0 || self.m2 == 0.0 {
+            return None;
+        }
+
+        Some((self.count as f64).sqrt() * self.m3 / self.m2.powf(1.5))
+    }
+
+    // NOTE: this is the population excess kurtosis, i.e. 0 for a normal distribution
+    pub fn kurtosis(&self) -> Option<f64> {
+        if self.count == 0 || self.m2 == 0.0 {
+            return None;
+        }
+
+        Some((self.count as f64) * self.m4 / (self.m2 * self.m2) - 3.0)
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        if other.count == 0 {
+            return;
+        }
+
+        if self.count == 0 {
+            *self = other;
+            return;
+        }
+
+        let na = self.count as f64;
+        let nb = other.count as f64;
+        let n = na + nb;
+
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+        let delta3 = delta2 * delta;
+        let delta4 = delta2 * delta2;
+
+        let m2 = self.m2 + other.m2 + delta2 * na * nb / n;
+
+        let m3 = self.m3
+            + other.m3
+            + delta3 * na * nb * (na - nb) / (n * n)
+            + 3.0 * delta * (na * other.m2 - nb * self.m2) / n;
+
+        let m4 = self.m4
+            + other.m4
+            + delta4 * na * nb * (na * na - na * nb + nb * nb) / (n * n * n)
+            + 6.0 * delta2 * (na * na * other.m2 + nb * nb * self.m2) / (n * n)
+            + 4.0 * delta * (na * other.m3 - nb * self.m3) / n;
+
+        self.mean = (na * self.mean + nb * other.mean) / n;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+        self.count += other.count;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,4 +484,89 @@ mod tests {
         assert_eq!(welford, welford_left);
         assert_eq!(covariance_welford, covariance_left);
     }
+
+    #[test]
+    fn test_weighted_welford() {
+        let mut weighted = WeightedWelford::new();
+        let mut welford = Welford::new();
+
+        // NOTE: integer weights should be equivalent to repeated values
+        for (x, w) in [(1.0, 1.0), (2.0, 3.0), (5.0, 2.0)] {
+            weighted.add(x, w);
+
+            for _ in 0..(w as usize) {
+                welford.add(x);
+            }
+        }
+
+        assert!((weighted.mean().unwrap() - welford.mean().unwrap()).abs() < 1e-12);
+        assert!((weighted.variance().unwrap() - welford.variance().unwrap()).abs() < 1e-12);
+
+        let mut left = WeightedWelford::new();
+        let mut right = WeightedWelford::new();
+        left.add(1.0, 1.0);
+        left.add(2.0, 3.0);
+        right.add(5.0, 2.0);
+        left.merge(right);
+
+        assert!((left.mean().unwrap() - weighted.mean().unwrap()).abs() < 1e-12);
+        assert!((left.variance().unwrap() - weighted.variance().unwrap()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_moments() {
+        let numbers = [2.0, 8.0, 0.0, 4.0, 1.0, 9.0, 9.0, 0.0];
+
+        let mut moments = Moments::new();
+
+        for n in numbers {
+            moments.add(n);
+        }
+
+        assert!((moments.skewness().unwrap() - 0.2650554122698573).abs() < 1e-12);
+        assert!((moments.kurtosis().unwrap() - -1.6660010752838508).abs() < 1e-12);
+
+        let mut left = Moments::new();
+        let mut right = Moments::new();
+
+        for n in &numbers[..3] {
+            left.add(*n);
+        }
+
+        for n in &numbers[3..] {
+            right.add(*n);
+        }
+
+        left.merge(right);
+
+        assert!((left.skewness().unwrap() - moments.skewness().unwrap()).abs() < 1e-12);
+        assert!((left.kurtosis().unwrap() - moments.kurtosis().unwrap()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut welford = Welford::new();
+
+        for n in [1.0, 2.0, 3.0, 4.0] {
+            welford.add(n);
+        }
+
+        welford.remove(1.0);
+
+        let mut expected = Welford::new();
+
+        for n in [2.0, 3.0, 4.0] {
+            expected.add(n);
+        }
+
+        assert_eq!(welford.count, expected.count);
+        assert!((welford.mean - expected.mean).abs() < 1e-12);
+        assert!((welford.m2 - expected.m2).abs() < 1e-12);
+
+        for n in [2.0, 3.0, 4.0] {
+            welford.remove(n);
+        }
+
+        assert_eq!(welford, Welford::new());
+    }
 }
@@ -1,7 +1,9 @@
 pub mod aggregators;
+mod outliers;
 mod program;
 mod stats;
 
 pub use aggregators::{CovarianceWelford, Welford};
-pub use program::{AggregationProgram, GroupAggregationProgram};
+pub use outliers::{OutlierFences, OutlierMethod, OutlierStats};
+pub use program::{AggregationProgram, EvaluatedRow, GroupAggregationProgram};
 pub use stats::Stats;
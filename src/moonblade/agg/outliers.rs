@@ -0,0 +1,158 @@
+use super::aggregators::{ApproxQuantiles, MedianType, Numbers, Welford};
+use crate::moonblade::types::DynamicNumber;
+
+// NOTE: scale of the median absolute deviation, so that it can be used as a
+// consistent estimator of the standard deviation of normally distributed data.
+const MAD_SCALE: f64 = 1.4826;
+
+#[derive(Debug, Clone, Copy)]
+pub enum OutlierMethod {
+    Iqr,
+    ZScore,
+    Mad,
+    Percentile(f64, f64),
+}
+
+impl OutlierMethod {
+    pub fn default_threshold(&self) -> f64 {
+        match self {
+            Self::Iqr => 1.5,
+            Self::ZScore => 3.0,
+            Self::Mad => 3.5,
+            Self::Percentile(_, _) => 0.0,
+        }
+    }
+
+    pub fn has_scores(&self) -> bool {
+        !matches!(self, Self::Percentile(_, _))
+    }
+}
+
+// NOTE: values are considered outliers when they are lower than `low` or
+// higher than `high` by more than `threshold` times `scale`. Scores are the
+// signed distance to the nearest bound, expressed in `scale` units.
+#[derive(Debug, Clone)]
+pub struct OutlierFences {
+    low: f64,
+    high: f64,
+    scale: f64,
+}
+
+impl OutlierFences {
+    pub fn score(&self, value: f64) -> Option<f64> {
+        if self.scale == 0.0 {
+            return None;
+        }
+
+        Some(if value < self.low {
+            (value - self.low) / self.scale
+        } else if value > self.high {
+            (value - self.high) / self.scale
+        } else {
+            0.0
+        })
+    }
+
+    pub fn is_outlier(&self, value: f64, threshold: f64) -> bool {
+        value < self.low - threshold * self.scale || value > self.high + threshold * self.scale
+    }
+}
+
+#[derive(Debug)]
+enum OutlierAggregator {
+    Numbers(Numbers),
+    Welford(Welford),
+    ApproxQuantiles(Box<ApproxQuantiles>),
+}
+
+#[derive(Debug)]
+pub struct OutlierStats {
+    method: OutlierMethod,
+    aggregator: OutlierAggregator,
+}
+
+impl OutlierStats {
+    pub fn new(method: OutlierMethod, approx: bool) -> Self {
+        let aggregator = match method {
+            OutlierMethod::ZScore => OutlierAggregator::Welford(Welford::new()),
+            _ if approx => OutlierAggregator::ApproxQuantiles(Box::new(ApproxQuantiles::new())),
+            _ => OutlierAggregator::Numbers(Numbers::new()),
+        };
+
+        Self { method, aggregator }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        match &mut self.aggregator {
+            OutlierAggregator::Numbers(numbers) => numbers.add(DynamicNumber::Float(value)),
+            OutlierAggregator::Welford(welford) => welford.add(value),
+            OutlierAggregator::ApproxQuantiles(approx_quantiles) => approx_quantiles.add(value),
+        }
+    }
+
+    // NOTE: this is cheap enough to be called after each value when using
+    // approximate quantiles, but will sort all the values when using exact ones.
+    pub fn fences(&mut self) -> Option<OutlierFences> {
+        match &mut self.aggregator {
+            OutlierAggregator::Welford(welford) => {
+                let mean = welford.mean()?;
+
+                Some(OutlierFences {
+                    low: mean,
+                    high: mean,
+                    scale: welford.stdev()?,
+                })
+            }
+            OutlierAggregator::ApproxQuantiles(approx_quantiles) => {
+                approx_quantiles.finalize();
+
+                let (low, high) = match self.method {
+                    OutlierMethod::Percentile(lower, upper) => (lower, upper),
+                    _ => (0.25, 0.75),
+                };
+
+                let low = approx_quantiles.get(low);
+                let high = approx_quantiles.get(high);
+
+                let scale = match self.method {
+                    OutlierMethod::Percentile(_, _) => 0.0,
+                    _ => high - low,
+                };
+
+                Some(OutlierFences { low, high, scale })
+            }
+            OutlierAggregator::Numbers(numbers) => {
+                numbers.finalize(false);
+
+                match self.method {
+                    OutlierMethod::Iqr => {
+                        let quartiles = numbers.quartiles()?;
+                        let low = quartiles[0].as_float();
+                        let high = quartiles[2].as_float();
+
+                        Some(OutlierFences {
+                            low,
+                            high,
+                            scale: high - low,
+                        })
+                    }
+                    OutlierMethod::Mad => {
+                        let median = numbers.median(&MedianType::Interpolation)?.as_float();
+
+                        Some(OutlierFences {
+                            low: median,
+                            high: median,
+                            scale: numbers.mad()? * MAD_SCALE,
+                        })
+                    }
+                    OutlierMethod::Percentile(lower, upper) => Some(OutlierFences {
+                        low: numbers.quantile(lower)?.as_float(),
+                        high: numbers.quantile(upper)?.as_float(),
+                        scale: 0.0,
+                    }),
+                    OutlierMethod::ZScore => unreachable!(),
+                }
+            }
+        }
+    }
+}
@@ -5,8 +5,9 @@ use jiff::{civil::DateTime, Unit};
 
 use super::aggregators::{
     AllAny, ApproxCardinality, ApproxQuantiles, ArgExtent, ArgTop, Count, CovarianceWelford, First,
-    Frequencies, Last, LexicographicExtent, MedianType, Numbers, NumericExtent, Sum, Types, Values,
-    Welford, ZonedExtent,
+    Frequencies, GeometricHarmonicMeans, Last, LexicographicExtent, MedianType, Moments, Numbers,
+    NumericExtent, OrderKey, OrderedValues, Sum, Types, Values, WeightedWelford, Welford,
+    ZonedExtent,
 };
 use crate::collections::ClusteredInsertHashmap;
 use crate::moonblade::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
@@ -14,7 +15,7 @@ use crate::moonblade::interpreter::{
     concretize_expression, eval_expression, ConcreteExpr, EvaluationContext,
 };
 use crate::moonblade::parser::{parse_aggregations, Aggregations};
-use crate::moonblade::types::{DynamicNumber, DynamicValue, FunctionArguments};
+use crate::moonblade::types::{Arity, DynamicNumber, DynamicValue, FunctionArguments};
 
 // NOTE: we are boxing some ones to avoid going over size=64
 #[derive(Debug, Clone)]
@@ -30,12 +31,16 @@ enum Aggregator {
     First(First),
     Last(Last),
     Values(Values),
+    OrderedValues(OrderedValues),
     LexicographicExtent(LexicographicExtent),
     Frequencies(Frequencies),
+    GeometricHarmonicMeans(GeometricHarmonicMeans),
+    Moments(Moments),
     Numbers(Numbers),
     Sum(Sum),
     Types(Types),
     Welford(Welford),
+    WeightedWelford(WeightedWelford),
     ZonedExtent(Box<ZonedExtent>),
 }
 
@@ -55,12 +60,16 @@ impl Aggregator {
             First(inner) => inner.clear(),
             Last(inner) => inner.clear(),
             Values(inner) => inner.clear(),
+            OrderedValues(inner) => inner.clear(),
             LexicographicExtent(inner) => inner.clear(),
             Frequencies(inner) => inner.clear(),
+            GeometricHarmonicMeans(inner) => inner.clear(),
+            Moments(inner) => inner.clear(),
             Numbers(inner) => inner.clear(),
             Sum(inner) => inner.clear(),
             Types(inner) => inner.clear(),
             Welford(inner) => inner.clear(),
+            WeightedWelford(inner) => inner.clear(),
             ZonedExtent(inner) => inner.clear(),
         }
     }
@@ -80,14 +89,20 @@ impl Aggregator {
             (First(inner), First(other_inner)) => inner.merge(other_inner),
             (Last(inner), Last(other_inner)) => inner.merge(other_inner),
             (Values(inner), Values(other_inner)) => inner.merge(other_inner),
+            (OrderedValues(inner), OrderedValues(other_inner)) => inner.merge(other_inner),
             (LexicographicExtent(inner), LexicographicExtent(other_inner)) => {
                 inner.merge(other_inner)
             }
             (Frequencies(inner), Frequencies(other_inner)) => inner.merge(other_inner),
+            (GeometricHarmonicMeans(inner), GeometricHarmonicMeans(other_inner)) => {
+                inner.merge(other_inner)
+            }
+            (Moments(inner), Moments(other_inner)) => inner.merge(other_inner),
             (Numbers(inner), Numbers(other_inner)) => inner.merge(other_inner),
             (Sum(inner), Sum(other_inner)) => inner.merge(other_inner),
             (Types(inner), Types(other_inner)) => inner.merge(other_inner),
             (Welford(inner), Welford(other_inner)) => inner.merge(other_inner),
+            (WeightedWelford(inner), WeightedWelford(other_inner)) => inner.merge(other_inner),
             (ZonedExtent(inner), ZonedExtent(other_inner)) => inner.merge(*other_inner),
             _ => unreachable!(),
         }
@@ -104,6 +119,9 @@ impl Aggregator {
             Self::Numbers(inner) => {
                 inner.finalize(parallel);
             }
+            Self::OrderedValues(inner) => {
+                inner.finalize();
+            }
             _ => (),
         }
     }
@@ -175,6 +193,9 @@ impl Aggregator {
             (ConcreteAggregationMethod::DistinctValues(separator), Self::Frequencies(inner)) => {
                 DynamicValue::from(inner.join(separator))
             }
+            (ConcreteAggregationMethod::Entropy, Self::Frequencies(inner)) => {
+                DynamicValue::from(inner.entropy())
+            }
             (ConcreteAggregationMethod::First, Self::First(inner)) => {
                 DynamicValue::from(inner.first())
             }
@@ -215,6 +236,21 @@ impl Aggregator {
             (ConcreteAggregationMethod::Mean, Self::Welford(inner)) => {
                 DynamicValue::from(inner.mean())
             }
+            (ConcreteAggregationMethod::GeometricMean, Self::GeometricHarmonicMeans(inner)) => {
+                DynamicValue::from(inner.geometric_mean())
+            }
+            (ConcreteAggregationMethod::HarmonicMean, Self::GeometricHarmonicMeans(inner)) => {
+                DynamicValue::from(inner.harmonic_mean())
+            }
+            (ConcreteAggregationMethod::Gini, Self::Numbers(inner)) => {
+                DynamicValue::from(inner.gini())
+            }
+            (ConcreteAggregationMethod::Kurtosis, Self::Moments(inner)) => {
+                DynamicValue::from(inner.kurtosis())
+            }
+            (ConcreteAggregationMethod::Mad, Self::Numbers(inner)) => {
+                DynamicValue::from(inner.mad())
+            }
             (ConcreteAggregationMethod::Median(median_type), Self::Numbers(inner)) => {
                 DynamicValue::from(inner.median(median_type))
             }
@@ -261,9 +297,15 @@ impl Aggregator {
                 ConcreteAggregationMethod::MostCommonValues(k, separator),
                 Self::Frequencies(inner),
             ) => DynamicValue::from(inner.most_common(*k).join(separator)),
+            (ConcreteAggregationMethod::Skewness, Self::Moments(inner)) => {
+                DynamicValue::from(inner.skewness())
+            }
             (ConcreteAggregationMethod::Sparkline(bins), Self::Numbers(inner)) => {
                 DynamicValue::from(inner.sparkline(*bins))
             }
+            (ConcreteAggregationMethod::StringAgg(separator), Self::OrderedValues(inner)) => {
+                DynamicValue::from(inner.join(separator))
+            }
             (ConcreteAggregationMethod::Sum, Self::Sum(inner)) => DynamicValue::from(inner.get()),
             (ConcreteAggregationMethod::VarPop, Self::Welford(inner)) => {
                 DynamicValue::from(inner.variance())
@@ -295,6 +337,15 @@ impl Aggregator {
             (ConcreteAggregationMethod::Values(separator), Self::Values(inner)) => {
                 DynamicValue::from(inner.join(separator))
             }
+            (ConcreteAggregationMethod::WeightedMean, Self::WeightedWelford(inner)) => {
+                DynamicValue::from(inner.mean())
+            }
+            (ConcreteAggregationMethod::WeightedVar, Self::WeightedWelford(inner)) => {
+                DynamicValue::from(inner.variance())
+            }
+            (ConcreteAggregationMethod::WeightedStddev, Self::WeightedWelford(inner)) => {
+                DynamicValue::from(inner.stdev())
+            }
             _ => unreachable!(),
         })
     }
@@ -462,12 +513,21 @@ impl CompositeAggregator {
             ConcreteAggregationMethod::Median(_)
             | ConcreteAggregationMethod::Quantile(_)
             | ConcreteAggregationMethod::Quartile(_)
-            | ConcreteAggregationMethod::Sparkline(_) => {
+            | ConcreteAggregationMethod::Sparkline(_)
+            | ConcreteAggregationMethod::Gini
+            | ConcreteAggregationMethod::Mad => {
                 upsert_aggregator!(Numbers)
             }
+            ConcreteAggregationMethod::GeometricMean | ConcreteAggregationMethod::HarmonicMean => {
+                upsert_aggregator!(GeometricHarmonicMeans)
+            }
+            ConcreteAggregationMethod::Skewness | ConcreteAggregationMethod::Kurtosis => {
+                upsert_aggregator!(Moments)
+            }
             ConcreteAggregationMethod::Mode
             | ConcreteAggregationMethod::Modes(_)
             | ConcreteAggregationMethod::Cardinality
+            | ConcreteAggregationMethod::Entropy
             | ConcreteAggregationMethod::DistinctValues(_)
             | ConcreteAggregationMethod::MostCommonCounts(_, _)
             | ConcreteAggregationMethod::MostCommonValues(_, _) => {
@@ -489,6 +549,14 @@ impl CompositeAggregator {
             ConcreteAggregationMethod::Values(_) => {
                 upsert_aggregator!(Values)
             }
+            ConcreteAggregationMethod::StringAgg(_) => {
+                upsert_aggregator!(OrderedValues)
+            }
+            ConcreteAggregationMethod::WeightedMean
+            | ConcreteAggregationMethod::WeightedVar
+            | ConcreteAggregationMethod::WeightedStddev => {
+                upsert_aggregator!(WeightedWelford)
+            }
         }
     }
 
@@ -517,7 +585,9 @@ impl CompositeAggregator {
                     Aggregator::Count(count) => {
                         count.add(value.is_truthy());
                     }
-                    Aggregator::CovarianceWelford(_) => unreachable!(),
+                    Aggregator::CovarianceWelford(_) | Aggregator::WeightedWelford(_) => {
+                        unreachable!()
+                    }
                     Aggregator::NumericExtent(extent) => {
                         if !value.is_nullish() {
                             extent.add(value.try_as_number()?);
@@ -558,6 +628,16 @@ impl CompositeAggregator {
                             frequencies.add(value.try_as_str()?.into_owned());
                         }
                     }
+                    Aggregator::GeometricHarmonicMeans(means) => {
+                        if !value.is_nullish() {
+                            means.add(value.try_as_f64()?);
+                        }
+                    }
+                    Aggregator::Moments(moments) => {
+                        if !value.is_nullish() {
+                            moments.add(value.try_as_f64()?);
+                        }
+                    }
                     Aggregator::Numbers(numbers) => {
                         if !value.is_nullish() {
                             numbers.add(value.try_as_number()?);
@@ -596,6 +676,11 @@ impl CompositeAggregator {
                             values.add(value.try_as_str()?.into_owned());
                         }
                     }
+                    Aggregator::OrderedValues(values) => {
+                        if !value.is_nullish() {
+                            values.add(None, index, value.try_as_str()?.into_owned());
+                        }
+                    }
                 },
                 None => match method {
                     Aggregator::Count(count) => {
@@ -609,14 +694,80 @@ impl CompositeAggregator {
         Ok(())
     }
 
+    // NOTE: only some aggregators are able to revert the processing of a
+    // value, which is used to maintain rolling windows in constant time.
+    fn is_invertible(&self) -> bool {
+        self.methods.iter().all(|method| {
+            matches!(
+                method,
+                Aggregator::Count(_) | Aggregator::Sum(_) | Aggregator::Welford(_)
+            )
+        })
+    }
+
+    fn remove_value(&mut self, value_opt: Option<&DynamicValue>) -> Result<(), EvaluationError> {
+        for method in self.methods.iter_mut() {
+            match value_opt {
+                Some(value) => match method {
+                    Aggregator::Count(count) => {
+                        count.remove(value.is_truthy());
+                    }
+                    Aggregator::Sum(sum) => {
+                        if !value.is_nullish() {
+                            sum.remove(value.try_as_number()?);
+                        }
+                    }
+                    Aggregator::Welford(variance) => {
+                        if !value.is_nullish() {
+                            variance.remove(value.try_as_f64()?);
+                        }
+                    }
+                    _ => unreachable!(),
+                },
+                None => match method {
+                    Aggregator::Count(count) => {
+                        count.remove(true);
+                    }
+                    _ => unreachable!(),
+                },
+            }
+        }
+
+        Ok(())
+    }
+
     fn process_pair(
         &mut self,
-        _index: usize,
+        index: usize,
         first: DynamicValue,
         second: DynamicValue,
     ) -> Result<(), EvaluationError> {
         for method in self.methods.iter_mut() {
             match method {
+                Aggregator::WeightedWelford(weighted_welford) => {
+                    if !first.is_nullish() && !second.is_nullish() {
+                        let weight = second.try_as_f64()?;
+
+                        if weight < 0.0 {
+                            return Err(EvaluationError::Custom(format!(
+                                "weights cannot be negative, but got {}",
+                                weight
+                            )));
+                        }
+
+                        weighted_welford.add(first.try_as_f64()?, weight);
+                    }
+                }
+                Aggregator::OrderedValues(values) => {
+                    if !first.is_nullish() {
+                        let key = match second.try_as_number() {
+                            Ok(number) => OrderKey::Number(number),
+                            Err(_) => OrderKey::String(second.try_as_str()?.into_owned()),
+                        };
+
+                        values.add(Some(key), index, first.try_as_str()?.into_owned());
+                    }
+                }
                 Aggregator::CovarianceWelford(covariance_welford) => {
                     match (first.is_nullish(), second.is_nullish()) {
                         (true, false) | (false, true) => return Err(EvaluationError::Custom("unaligned series where given to covariance or correlation functions (both series must have the same number of data points)".to_string())),
@@ -718,11 +869,17 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
             Ok(DistinctValues(cast_as_separator(args.first())?))
         }),
         "earliest" => (FunctionArguments::unary(), |_| Ok(Earliest)),
+        "entropy" => (FunctionArguments::unary(), |_| Ok(Entropy)),
         "first" => (FunctionArguments::unary(), |_| Ok(First)),
+        "geometric_mean" => (FunctionArguments::unary(), |_| Ok(GeometricMean)),
+        "gini" => (FunctionArguments::unary(), |_| Ok(Gini)),
+        "harmonic_mean" => (FunctionArguments::unary(), |_| Ok(HarmonicMean)),
+        "kurtosis" => (FunctionArguments::unary(), |_| Ok(Kurtosis)),
         "latest" => (FunctionArguments::unary(), |_| Ok(Latest)),
         "last" => (FunctionArguments::unary(), |_| Ok(Last)),
         "lex_first" => (FunctionArguments::unary(), |_| Ok(LexFirst)),
         "lex_last" => (FunctionArguments::unary(), |_| Ok(LexLast)),
+        "mad" => (FunctionArguments::unary(), |_| Ok(Mad)),
         "min" => (FunctionArguments::unary(), |_| Ok(Min)),
         "max" => (FunctionArguments::unary(), |_| Ok(Max)),
         "avg" | "mean" => (FunctionArguments::unary(), |_| Ok(Mean)),
@@ -763,6 +920,7 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         "var" | "var_pop" => (FunctionArguments::unary(), |_| Ok(VarPop)),
         "var_sample" => (FunctionArguments::unary(), |_| Ok(VarSample)),
         "ratio" => (FunctionArguments::unary(), |_| Ok(Ratio)),
+        "skewness" => (FunctionArguments::unary(), |_| Ok(Skewness)),
         "sparkline" => (FunctionArguments::with_range(1..=2), |args| {
             Ok(Sparkline(match args.first() {
                 Some(arg) => cast_as_static_value(arg, DynamicValue::try_as_usize)?,
@@ -771,6 +929,9 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         }),
         "stddev" | "stddev_pop" => (FunctionArguments::unary(), |_| Ok(StddevPop)),
         "stddev_sample" => (FunctionArguments::unary(), |_| Ok(StddevSample)),
+        "string_agg" => (FunctionArguments::with_range(1..=2), |args| {
+            Ok(StringAgg(cast_as_separator(args.first())?))
+        }),
         "sum" => (FunctionArguments::unary(), |_| Ok(Sum)),
         "top" => (FunctionArguments::with_range(1..=3), |args| {
             Ok(Top(
@@ -780,6 +941,9 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         }),
         "type" => (FunctionArguments::unary(), |_| Ok(Type)),
         "types" => (FunctionArguments::unary(), |_| Ok(Types)),
+        "weighted_mean" => (FunctionArguments::unary(), |_| Ok(WeightedMean)),
+        "weighted_stddev" => (FunctionArguments::unary(), |_| Ok(WeightedStddev)),
+        "weighted_var" => (FunctionArguments::unary(), |_| Ok(WeightedVar)),
         _ => return None,
     })
 }
@@ -801,11 +965,17 @@ enum ConcreteAggregationMethod {
     CovarianceSample,
     DistinctValues(String),
     Earliest,
+    Entropy,
     First,
+    GeometricMean,
+    Gini,
+    HarmonicMean,
+    Kurtosis,
     Latest,
     Last,
     LexFirst,
     LexLast,
+    Mad,
     Min,
     Max,
     Mean,
@@ -818,9 +988,14 @@ enum ConcreteAggregationMethod {
     Quartile(usize),
     Quantile(f64),
     Ratio,
+    Skewness,
     Sparkline(usize),
+    StringAgg(String),
     Sum,
     Values(String),
+    WeightedMean,
+    WeightedVar,
+    WeightedStddev,
     VarPop,
     VarSample,
     StddevPop,
@@ -853,11 +1028,18 @@ struct ConcreteAggregation {
     method: ConcreteAggregationMethod,
     expr: Option<ConcreteExpr>,
     pair_expr: Option<ConcreteExpr>,
+    filter: Option<ConcreteExpr>,
 }
 
+type ExecutionKey<'a> = (
+    &'a Option<ConcreteExpr>,
+    &'a Option<ConcreteExpr>,
+    &'a Option<ConcreteExpr>,
+);
+
 impl ConcreteAggregation {
-    fn key(&self) -> (&Option<ConcreteExpr>, &Option<ConcreteExpr>) {
-        (&self.expr, &self.pair_expr)
+    fn key(&self) -> ExecutionKey<'_> {
+        (&self.expr, &self.pair_expr, &self.filter)
     }
 }
 
@@ -882,17 +1064,33 @@ fn concretize_aggregations(
             .map(|arg| concretize_expression(arg.clone(), headers, None))
             .transpose()?;
 
+        // NOTE: string_agg's optional ordering expression is handled as a
+        // pair expression, so we move it before the separator.
+        if aggregation.func_name == "string_agg" && aggregation.args.len() == 3 {
+            aggregation.args.swap(1, 2);
+        }
+
         let mut skip: usize = 1;
 
-        let pair_expr = if aggregation.args.len() > 1
-            && [
-                "covariance",
-                "covariance_pop",
-                "covariance_sample",
-                "correlation",
-            ]
-            .contains(&aggregation.func_name.as_str())
-        {
+        let has_pair = match aggregation.func_name.as_str() {
+            "covariance" | "covariance_pop" | "covariance_sample" | "correlation"
+            | "weighted_mean" | "weighted_var" | "weighted_stddev" => {
+                Arity::Strict(2)
+                    .validate(aggregation.args.len())
+                    .map_err(|invalid_arity| {
+                        ConcretizationError::InvalidArity(
+                            aggregation.func_name.clone(),
+                            invalid_arity,
+                        )
+                    })?;
+
+                true
+            }
+            "string_agg" => aggregation.args.len() > 2,
+            _ => false,
+        };
+
+        let pair_expr = if has_pair {
             skip = 2;
             Some(concretize_expression(
                 aggregation.args.get(1).unwrap().clone(),
@@ -911,11 +1109,17 @@ fn concretize_aggregations(
 
         let method = ConcreteAggregationMethod::parse(&aggregation.func_name, &args)?;
 
+        let filter = aggregation
+            .filter
+            .map(|condition| concretize_expression(condition, headers, None))
+            .transpose()?;
+
         let concrete_aggregation = ConcreteAggregation {
             agg_name: aggregation.agg_name,
             method,
             expr,
             pair_expr,
+            filter,
         };
 
         concrete_aggregations.push(concrete_aggregation);
@@ -933,17 +1137,19 @@ fn prepare(code: &str, headers: &ByteRecord) -> Result<ConcreteAggregations, Con
 
 // NOTE: each execution unit is iterated upon linearly to aggregate values
 // all while running a minimum number of operations (batched by 1. expression
-// keys and 2. composite aggregation atom).
+// keys and 2. composite aggregation atom). Aggregations using a filter clause
+// are keyed by their condition also, so that they get their own aggregators.
 #[derive(Debug, Clone)]
 struct PlannerExecutionUnit {
     expr: Option<ConcreteExpr>,
     pair_expr: Option<ConcreteExpr>,
+    filter: Option<ConcreteExpr>,
     aggregator_blueprint: CompositeAggregator,
 }
 
 impl PlannerExecutionUnit {
-    fn key(&self) -> (&Option<ConcreteExpr>, &Option<ConcreteExpr>) {
-        (&self.expr, &self.pair_expr)
+    fn key(&self) -> ExecutionKey<'_> {
+        (&self.expr, &self.pair_expr, &self.filter)
     }
 }
 
@@ -992,6 +1198,7 @@ impl From<ConcreteAggregations> for ConcreteAggregationPlanner {
                 execution_plan.push(PlannerExecutionUnit {
                     expr: agg.expr,
                     pair_expr: agg.pair_expr,
+                    filter: agg.filter,
                     aggregator_blueprint,
                 });
 
@@ -1038,17 +1245,33 @@ impl ConcreteAggregationPlanner {
     }
 }
 
-// NOTE: parallelizing "horizontally" the planner's execution units does not
-// seem to yield any performance increase. I guess the overhead is greater than
-// the inner computation time.
-fn run_with_record_on_aggregators(
+/// Values of the expressions of an execution unit, evaluated on a single row,
+/// so that they can be buffered & processed later, e.g. by rolling windows.
+#[derive(Debug, Clone)]
+pub enum EvaluatedArguments {
+    Filtered,
+    Value(Option<DynamicValue>),
+    Pair(DynamicValue, DynamicValue),
+}
+
+pub type EvaluatedRow = Vec<EvaluatedArguments>;
+
+fn evaluate_arguments(
     planner: &ConcreteAggregationPlanner,
-    aggregators: &mut Vec<CompositeAggregator>,
     index: usize,
     record: &ByteRecord,
     context: &EvaluationContext,
-) -> Result<(), SpecifiedEvaluationError> {
-    for (unit, aggregator) in planner.execution_plan.iter().zip(aggregators) {
+) -> Result<EvaluatedRow, SpecifiedEvaluationError> {
+    let mut row = EvaluatedRow::with_capacity(planner.execution_plan.len());
+
+    for unit in planner.execution_plan.iter() {
+        if let Some(filter) = &unit.filter {
+            if !eval_expression(filter, Some(index), record, context)?.is_truthy() {
+                row.push(EvaluatedArguments::Filtered);
+                continue;
+            }
+        }
+
         let value = match &unit.expr {
             None => None,
             Some(expr) => Some(eval_expression(expr, Some(index), record, context)?),
@@ -1057,27 +1280,63 @@ fn run_with_record_on_aggregators(
         if let Some(pair_expr) = &unit.pair_expr {
             let second_value = eval_expression(pair_expr, Some(index), record, context)?;
 
-            return aggregator
-                .process_pair(index, value.unwrap(), second_value)
-                .map_err(|err| err.specify("<agg-expr>"));
+            row.push(EvaluatedArguments::Pair(value.unwrap(), second_value));
+            continue;
         }
 
-        if let Some(DynamicValue::List(list)) = value {
-            for v in Arc::into_inner(list).unwrap() {
+        row.push(EvaluatedArguments::Value(value));
+    }
+
+    Ok(row)
+}
+
+fn process_arguments(
+    aggregators: &mut [CompositeAggregator],
+    index: usize,
+    row: EvaluatedRow,
+    record: &ByteRecord,
+) -> Result<(), SpecifiedEvaluationError> {
+    for (arguments, aggregator) in row.into_iter().zip(aggregators) {
+        match arguments {
+            EvaluatedArguments::Filtered => continue,
+            EvaluatedArguments::Pair(value, second_value) => {
                 aggregator
-                    .process_value(index, Some(v), record)
+                    .process_pair(index, value, second_value)
+                    .map_err(|err| err.specify("<agg-expr>"))?;
+            }
+            EvaluatedArguments::Value(Some(DynamicValue::List(list))) => {
+                for v in Arc::try_unwrap(list).unwrap_or_else(|list| (*list).clone()) {
+                    aggregator
+                        .process_value(index, Some(v), record)
+                        .map_err(|err| err.specify("<agg-expr>"))?;
+                }
+            }
+            EvaluatedArguments::Value(value) => {
+                aggregator
+                    .process_value(index, value, record)
                     .map_err(|err| err.specify("<agg-expr>"))?;
             }
-        } else {
-            aggregator
-                .process_value(index, value, record)
-                .map_err(|err| err.specify("<agg-expr>"))?;
         }
     }
 
     Ok(())
 }
 
+// NOTE: parallelizing "horizontally" the planner's execution units does not
+// seem to yield any performance increase. I guess the overhead is greater than
+// the inner computation time.
+fn run_with_record_on_aggregators(
+    planner: &ConcreteAggregationPlanner,
+    aggregators: &mut [CompositeAggregator],
+    index: usize,
+    record: &ByteRecord,
+    context: &EvaluationContext,
+) -> Result<(), SpecifiedEvaluationError> {
+    let row = evaluate_arguments(planner, index, record, context)?;
+
+    process_arguments(aggregators, index, row, record)
+}
+
 #[derive(Debug, Clone)]
 pub struct AggregationProgram {
     aggregators: Vec<CompositeAggregator>,
@@ -1088,14 +1347,37 @@ pub struct AggregationProgram {
 impl AggregationProgram {
     pub fn parse(code: &str, headers: &ByteRecord) -> Result<Self, ConcretizationError> {
         let concrete_aggregations = prepare(code, headers)?;
+
+        Ok(Self::from_concrete_aggregations(
+            concrete_aggregations,
+            headers,
+        ))
+    }
+
+    pub fn from_aggregations(
+        aggregations: Aggregations,
+        headers: &ByteRecord,
+    ) -> Result<Self, ConcretizationError> {
+        let concrete_aggregations = concretize_aggregations(aggregations, headers)?;
+
+        Ok(Self::from_concrete_aggregations(
+            concrete_aggregations,
+            headers,
+        ))
+    }
+
+    fn from_concrete_aggregations(
+        concrete_aggregations: ConcreteAggregations,
+        headers: &ByteRecord,
+    ) -> Self {
         let planner = ConcreteAggregationPlanner::from(concrete_aggregations);
         let aggregators = planner.instantiate_aggregators();
 
-        Ok(Self {
+        Self {
             planner,
             aggregators,
             context: EvaluationContext::new(headers),
-        })
+        }
     }
 
     pub fn clear(&mut self) {
@@ -1126,6 +1408,61 @@ impl AggregationProgram {
         )
     }
 
+    pub fn evaluate(
+        &self,
+        index: usize,
+        record: &ByteRecord,
+    ) -> Result<EvaluatedRow, SpecifiedEvaluationError> {
+        evaluate_arguments(&self.planner, index, record, &self.context)
+    }
+
+    pub fn run_with_evaluated(
+        &mut self,
+        index: usize,
+        row: EvaluatedRow,
+        record: &ByteRecord,
+    ) -> Result<(), SpecifiedEvaluationError> {
+        process_arguments(&mut self.aggregators, index, row, record)
+    }
+
+    /// Whether already processed rows can be removed from the aggregators,
+    /// using `remove_evaluated`.
+    pub fn is_invertible(&self) -> bool {
+        self.aggregators
+            .iter()
+            .all(|aggregator| aggregator.is_invertible())
+    }
+
+    pub fn remove_evaluated(&mut self, row: &EvaluatedRow) -> Result<(), SpecifiedEvaluationError> {
+        for (arguments, aggregator) in row.iter().zip(self.aggregators.iter_mut()) {
+            let result = match arguments {
+                EvaluatedArguments::Filtered => continue,
+                EvaluatedArguments::Pair(_, _) => unreachable!(),
+                EvaluatedArguments::Value(Some(DynamicValue::List(list))) => list
+                    .iter()
+                    .try_for_each(|v| aggregator.remove_value(Some(v))),
+                EvaluatedArguments::Value(value) => aggregator.remove_value(value.as_ref()),
+            };
+
+            result.map_err(|err| err.specify("<agg-expr>"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the aggregators need to keep the processed records, e.g. to
+    /// evaluate the return expression of argmin, instead of their index only.
+    pub fn requires_records(&self) -> bool {
+        self.planner.output_plan.iter().any(|unit| {
+            matches!(
+                unit.agg_method,
+                ConcreteAggregationMethod::ArgMin(Some(_))
+                    | ConcreteAggregationMethod::ArgMax(Some(_))
+                    | ConcreteAggregationMethod::ArgTop(_, Some(_), _)
+            )
+        })
+    }
+
     pub fn headers(&self) -> impl Iterator<Item = &[u8]> {
         self.planner.headers()
     }
@@ -1150,7 +1487,9 @@ type GroupKey = Vec<Vec<u8>>;
 #[derive(Debug, Clone)]
 pub struct GroupAggregationProgram {
     planner: ConcreteAggregationPlanner,
-    groups: ClusteredInsertHashmap<GroupKey, Vec<CompositeAggregator>>,
+    // NOTE: we keep the index of the first row of each group alongside its
+    // aggregators, so that callers are able to restore first-seen order.
+    groups: ClusteredInsertHashmap<GroupKey, (usize, Vec<CompositeAggregator>)>,
     context: EvaluationContext,
 }
 
@@ -1166,12 +1505,26 @@ impl GroupAggregationProgram {
         })
     }
 
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.len() == 0
+    }
+
+    pub fn contains(&self, group: &GroupKey) -> bool {
+        self.groups.get(group).is_some()
+    }
+
     pub fn merge(&mut self, other: Self) {
-        for (key, other_aggregators) in other.groups.into_iter() {
+        for (key, other_entry) in other.groups.into_iter() {
             self.groups.insert_or_update_with(
                 key,
-                other_aggregators,
-                |self_aggregators, other_aggregators| {
+                other_entry,
+                |(self_index, self_aggregators), (other_index, other_aggregators)| {
+                    *self_index = (*self_index).min(other_index);
+
                     for (self_aggregator, other_aggregator) in
                         self_aggregators.iter_mut().zip(other_aggregators)
                     {
@@ -1190,13 +1543,37 @@ impl GroupAggregationProgram {
     ) -> Result<(), SpecifiedEvaluationError> {
         let planner = &self.planner;
 
-        let aggregators = self
+        let (_, aggregators) = self
             .groups
-            .insert_with(group, || planner.instantiate_aggregators());
+            .insert_with(group, || (index, planner.instantiate_aggregators()));
 
         run_with_record_on_aggregators(&self.planner, aggregators, index, record, &self.context)
     }
 
+    pub fn evaluate(
+        &self,
+        index: usize,
+        record: &ByteRecord,
+    ) -> Result<EvaluatedRow, SpecifiedEvaluationError> {
+        evaluate_arguments(&self.planner, index, record, &self.context)
+    }
+
+    pub fn run_with_evaluated(
+        &mut self,
+        group: GroupKey,
+        index: usize,
+        row: EvaluatedRow,
+        record: &ByteRecord,
+    ) -> Result<(), SpecifiedEvaluationError> {
+        let planner = &self.planner;
+
+        let (_, aggregators) = self
+            .groups
+            .insert_with(group, || (index, planner.instantiate_aggregators()));
+
+        process_arguments(aggregators, index, row, record)
+    }
+
     pub fn headers(&self) -> impl Iterator<Item = &[u8]> {
         self.planner.headers()
     }
@@ -1205,12 +1582,22 @@ impl GroupAggregationProgram {
         self,
         parallel: bool,
     ) -> impl Iterator<Item = Result<(GroupKey, ByteRecord), SpecifiedEvaluationError>> {
+        self.into_indexed_byte_records(parallel)
+            .map(|result| result.map(|(_, group, record)| (group, record)))
+    }
+
+    // NOTE: same as `into_byte_records`, but also yields the index of the
+    // first row seen for each group.
+    pub fn into_indexed_byte_records(
+        self,
+        parallel: bool,
+    ) -> impl Iterator<Item = Result<(usize, GroupKey, ByteRecord), SpecifiedEvaluationError>> {
         let planner = self.planner;
         let context = self.context;
 
         self.groups
             .into_iter()
-            .map(move |(group, mut aggregators)| {
+            .map(move |(group, (index, mut aggregators))| {
                 for aggregator in aggregators.iter_mut() {
                     aggregator.finalize(parallel);
                 }
@@ -1221,7 +1608,7 @@ impl GroupAggregationProgram {
                     record.push_field(&value?.serialize_as_bytes());
                 }
 
-                Ok((group, record))
+                Ok((index, group, record))
             })
     }
 }
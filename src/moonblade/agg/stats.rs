@@ -2,7 +2,7 @@ use csv::ByteRecord;
 
 use super::aggregators::{
     ApproxCardinality, ApproxQuantiles, Count, Extent, Frequencies, LexicographicExtent, Numbers,
-    NumericExtent, Sum, Types, Welford,
+    NumericExtent, Sum, Types, WeightedNumbers, WeightedWelford, Welford,
 };
 use crate::dates;
 use crate::moonblade::types::DynamicNumber;
@@ -13,6 +13,37 @@ fn map_to_field<T: ToString>(opt: Option<T>) -> Vec<u8> {
         .unwrap_or(b"".to_vec())
 }
 
+// NOTE: weighted counterparts of the aggregators used to compute counts,
+// means, variances & quantiles, when rows are given weights.
+#[derive(Debug)]
+struct Weights {
+    truthy: f64,
+    falsey: f64,
+    welford: WeightedWelford,
+    numbers: Option<WeightedNumbers>,
+}
+
+impl Weights {
+    fn new() -> Self {
+        Self {
+            truthy: 0.0,
+            falsey: 0.0,
+            welford: WeightedWelford::new(),
+            numbers: None,
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.truthy += other.truthy;
+        self.falsey += other.falsey;
+        self.welford.merge(other.welford);
+
+        if let Some(numbers) = &mut self.numbers {
+            numbers.merge(other.numbers.unwrap());
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Stats {
     nulls: bool,
@@ -27,6 +58,7 @@ pub struct Stats {
     numbers: Option<Numbers>,
     approx_cardinality: Option<Box<ApproxCardinality>>,
     approx_quantiles: Option<Box<ApproxQuantiles>>,
+    weights: Option<Box<Weights>>,
 }
 
 impl Stats {
@@ -44,6 +76,7 @@ impl Stats {
             numbers: None,
             approx_cardinality: None,
             approx_quantiles: None,
+            weights: None,
         }
     }
 
@@ -70,6 +103,10 @@ impl Stats {
         if let Some(approx_quantiles) = &mut self.approx_quantiles {
             approx_quantiles.merge(*other.approx_quantiles.unwrap());
         }
+
+        if let Some(weights) = &mut self.weights {
+            weights.merge(*other.weights.unwrap());
+        }
     }
 
     pub fn include_nulls(&mut self) {
@@ -81,7 +118,16 @@ impl Stats {
     }
 
     pub fn compute_numbers(&mut self) {
-        self.numbers = Some(Numbers::new());
+        match &mut self.weights {
+            Some(weights) => weights.numbers = Some(WeightedNumbers::new()),
+            None => self.numbers = Some(Numbers::new()),
+        }
+    }
+
+    // NOTE: must be called before `compute_numbers`, and rows must then be
+    // processed using `process_with_weight`.
+    pub fn use_weights(&mut self) {
+        self.weights = Some(Box::new(Weights::new()));
     }
 
     pub fn compute_approx(&mut self) {
@@ -100,7 +146,12 @@ impl Stats {
         headers.push_field(b"sum");
         headers.push_field(b"mean");
 
-        if self.numbers.is_some() {
+        if self.numbers.is_some()
+            || self
+                .weights
+                .as_ref()
+                .is_some_and(|weights| weights.numbers.is_some())
+        {
             headers.push_field(b"q1");
             headers.push_field(b"median");
             headers.push_field(b"q3");
@@ -136,8 +187,18 @@ impl Stats {
         let mut record = ByteRecord::new();
 
         record.push_field(name);
-        record.push_field(self.count.get_truthy().to_string().as_bytes());
-        record.push_field(self.count.get_falsey().to_string().as_bytes());
+
+        match &self.weights {
+            Some(weights) => {
+                record.push_field(weights.truthy.to_string().as_bytes());
+                record.push_field(weights.falsey.to_string().as_bytes());
+            }
+            None => {
+                record.push_field(self.count.get_truthy().to_string().as_bytes());
+                record.push_field(self.count.get_falsey().to_string().as_bytes());
+            }
+        }
+
         record.push_field(
             self.types
                 .most_likely_type()
@@ -146,15 +207,47 @@ impl Stats {
         );
         record.push_field(self.types.sorted_types().join("|").as_bytes());
         record.push_field(&map_to_field(self.sum.get()));
-        record.push_field(&map_to_field(self.welford.mean()));
 
-        if let Some(mut numbers) = self.numbers {
+        let (mean, variance, stdev) = match &self.weights {
+            Some(weights) => (
+                weights.welford.mean(),
+                weights.welford.variance(),
+                weights.welford.stdev(),
+            ),
+            None => (
+                self.welford.mean(),
+                self.welford.variance(),
+                self.welford.stdev(),
+            ),
+        };
+
+        record.push_field(&map_to_field(mean));
+
+        let quartiles = if let Some(mut numbers) = self.numbers {
+            numbers.finalize(false);
+
+            Some(
+                numbers
+                    .quartiles()
+                    .map(|q| q.into_iter().map(|n| n.to_string()).collect::<Vec<_>>()),
+            )
+        } else if let Some(mut numbers) = self.weights.and_then(|weights| weights.numbers) {
             numbers.finalize(false);
 
-            match numbers.quartiles() {
+            Some(
+                numbers
+                    .quartiles()
+                    .map(|q| q.into_iter().map(|n| n.to_string()).collect::<Vec<_>>()),
+            )
+        } else {
+            None
+        };
+
+        if let Some(quartiles) = quartiles {
+            match quartiles {
                 Some(quartiles) => {
                     for quartile in quartiles {
-                        record.push_field(quartile.to_string().as_bytes());
+                        record.push_field(quartile.as_bytes());
                     }
                 }
                 None => {
@@ -165,8 +258,8 @@ impl Stats {
             }
         }
 
-        record.push_field(&map_to_field(self.welford.variance()));
-        record.push_field(&map_to_field(self.welford.stdev()));
+        record.push_field(&map_to_field(variance));
+        record.push_field(&map_to_field(stdev));
         record.push_field(&map_to_field(self.extent.min()));
         record.push_field(&map_to_field(self.extent.max()));
 
@@ -199,25 +292,45 @@ impl Stats {
         record
     }
 
-    pub fn process(&mut self, cell: &[u8]) {
+    // NOTE: the weight is only taken into account if `use_weights` was called.
+    pub fn process_with_weight(&mut self, cell: &[u8], weight: f64) {
         self.length_extent.add(cell.len());
 
         if cell.is_empty() {
             self.types.set_empty();
-            self.count.add_falsey();
 
-            if self.nulls {
-                self.welford.add(0.0);
+            match self.weights.as_mut() {
+                Some(weights) => {
+                    weights.falsey += weight;
+
+                    if self.nulls {
+                        weights.welford.add(0.0, weight);
 
-                if let Some(numbers) = self.numbers.as_mut() {
-                    numbers.add(DynamicNumber::Float(0.0));
+                        if let Some(numbers) = weights.numbers.as_mut() {
+                            numbers.add(0.0, weight);
+                        }
+                    }
+                }
+                None => {
+                    self.count.add_falsey();
+
+                    if self.nulls {
+                        self.welford.add(0.0);
+
+                        if let Some(numbers) = self.numbers.as_mut() {
+                            numbers.add(DynamicNumber::Float(0.0));
+                        }
+                    }
                 }
             }
 
             return;
         }
 
-        self.count.add_truthy();
+        match self.weights.as_mut() {
+            Some(weights) => weights.truthy += weight,
+            None => self.count.add_truthy(),
+        };
 
         let cell = std::str::from_utf8(cell).expect("could not decode as utf-8");
 
@@ -231,16 +344,33 @@ impl Stats {
             if !number.is_nan() {
                 let float = number.as_float();
 
-                self.sum.add(number);
-                self.welford.add(float);
                 self.extent.add(number);
 
-                if let Some(numbers) = self.numbers.as_mut() {
-                    numbers.add(number);
-                }
+                match self.weights.as_mut() {
+                    Some(weights) => {
+                        self.sum.add(DynamicNumber::Float(float * weight));
+                        weights.welford.add(float, weight);
 
-                if let Some(approx_quantiles) = self.approx_quantiles.as_mut() {
-                    approx_quantiles.add(float);
+                        if let Some(numbers) = weights.numbers.as_mut() {
+                            numbers.add(float, weight);
+                        }
+
+                        if let Some(approx_quantiles) = self.approx_quantiles.as_mut() {
+                            approx_quantiles.add_weighted(float, weight);
+                        }
+                    }
+                    None => {
+                        self.sum.add(number);
+                        self.welford.add(float);
+
+                        if let Some(numbers) = self.numbers.as_mut() {
+                            numbers.add(number);
+                        }
+
+                        if let Some(approx_quantiles) = self.approx_quantiles.as_mut() {
+                            approx_quantiles.add(float);
+                        }
+                    }
                 }
             }
         } else if dates::could_be_date(cell) {
@@ -29,6 +29,7 @@ pub enum ConcretizationError {
     InvalidCSSSelector(String),
     StaticEvaluationError(SpecifiedEvaluationError),
     NotStaticallyAnalyzable,
+    InvalidWindow(String),
 }
 
 impl Display for ConcretizationError {
@@ -46,6 +47,7 @@ impl Display for ConcretizationError {
             Self::InvalidCSSSelector(css) => write!(f, "invalid css selector: {}", css),
             Self::StaticEvaluationError(error) => error.fmt(f),
             Self::NotStaticallyAnalyzable => write!(f, "not statically analyzable"),
+            Self::InvalidWindow(reason) => write!(f, "invalid window: {}", reason),
         }
     }
 }
@@ -9,11 +9,15 @@ mod select;
 mod special_functions;
 mod types;
 mod utils;
+mod window;
 
-pub use self::agg::{AggregationProgram, GroupAggregationProgram, Stats};
+pub use self::agg::{
+    AggregationProgram, GroupAggregationProgram, OutlierFences, OutlierMethod, OutlierStats, Stats,
+};
 pub use self::choose::ChooseProgram;
 pub use self::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
 pub use self::interpreter::{GlobalVariables, Program};
 pub use self::scrape::ScrapingProgram;
 pub use self::select::SelectionProgram;
-pub use self::types::DynamicValue;
+pub use self::types::{DynamicNumber, DynamicValue};
+pub use self::window::WindowAggregationProgram;
@@ -686,6 +686,7 @@ pub struct Aggregation {
     pub agg_name: String,
     pub args: Vec<Expr>,
     pub func_name: String,
+    pub filter: Option<Expr>,
 }
 
 pub type Aggregations = Vec<Aggregation>;
@@ -697,31 +698,41 @@ pub fn parse_aggregations(input: &str) -> Result<Aggregations, ParseError> {
         .filter(|p| !matches!(p.as_rule(), Rule::EOI))
         .map(|p| {
             let (agg_name, p) = match p.as_rule() {
-                Rule::func => (p.as_span().as_str().to_string(), p),
+                Rule::agg_func => (p.as_span().as_str().to_string(), p),
                 Rule::named_func => {
                     let mut inner = p.into_inner();
 
                     debug_assert!(inner.len() == 2);
 
-                    let func = inner.next().unwrap();
+                    let agg_func = inner.next().unwrap();
 
                     let expr_name = inner.next().unwrap();
                     let name = parse_expression_name(expr_name);
 
-                    debug_assert!(matches!(func.as_rule(), Rule::func));
+                    debug_assert!(matches!(agg_func.as_rule(), Rule::agg_func));
 
-                    (name, func)
+                    (name, agg_func)
                 }
                 _ => unreachable!(),
             };
 
-            let expr = pratt_parse(Pairs::single(p))?;
+            // NOTE: an aggregation function may be followed by a filter clause,
+            // e.g. `count() if status == 'ok'`.
+            let mut inner = p.into_inner();
+
+            let expr = pratt_parse(Pairs::single(inner.next().unwrap()))?;
+
+            let filter = match inner.nth(1) {
+                Some(condition) => Some(pratt_parse(Pairs::single(condition))?),
+                None => None,
+            };
 
             match expr {
                 Expr::Func(call) => Ok(Aggregation {
                     agg_name,
                     args: call.args.into_iter().map(|(_, arg)| arg).collect(),
                     func_name: call.name,
+                    filter,
                 }),
                 _ => unreachable!(),
             }
@@ -1201,7 +1212,8 @@ mod tests {
                 args: vec![func(
                     "add",
                     vec![func("add", vec![id("A"), id("B")]), Int(1)]
-                ),]
+                ),],
+                filter: None
             }])
         );
 
@@ -1210,7 +1222,8 @@ mod tests {
             Ok(vec![Aggregation {
                 agg_name: "join(name, '|')".to_string(),
                 func_name: "join".to_string(),
-                args: vec![id("name"), s("|")]
+                args: vec![id("name"), s("|")],
+                filter: None
             }])
         );
 
@@ -1220,15 +1233,37 @@ mod tests {
                 Aggregation {
                     agg_name: "c".to_string(),
                     func_name: "count".to_string(),
-                    args: vec![id("a")]
+                    args: vec![id("a")],
+                    filter: None
                 },
                 Aggregation {
                     agg_name: "Sum".to_string(),
                     func_name: "sum".to_string(),
-                    args: vec![id("b")]
+                    args: vec![id("b")],
+                    filter: None
+                }
+            ])
+        );
+
+        assert_eq!(
+            parse_aggregations("count() if status == 'ok' as ok, mean(x) if y > 2"),
+            Ok(vec![
+                Aggregation {
+                    agg_name: "ok".to_string(),
+                    func_name: "count".to_string(),
+                    args: vec![],
+                    filter: Some(func("==", vec![id("status"), s("ok")]))
+                },
+                Aggregation {
+                    agg_name: "mean(x) if y > 2".to_string(),
+                    func_name: "mean".to_string(),
+                    args: vec![id("x")],
+                    filter: Some(func(">", vec![id("y"), Int(2)]))
                 }
             ])
         );
+
+        assert!(parse_aggregations("count() iffy").is_err());
     }
 
     #[test]
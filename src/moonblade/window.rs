@@ -0,0 +1,531 @@
+use std::collections::{HashMap, VecDeque};
+
+use csv::ByteRecord;
+use jiff::{tz::TimeZone, Span, Timestamp};
+
+use super::agg::{AggregationProgram, EvaluatedRow};
+use super::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
+use super::interpreter::{concretize_expression, eval_expression, ConcreteExpr, EvaluationContext};
+use super::parser::{parse_aggregations, Aggregation, Expr};
+use super::types::Arity;
+use crate::dates;
+
+type GroupKey = Vec<Vec<u8>>;
+
+#[derive(Debug, Clone)]
+enum Frame {
+    Rows(usize),
+    Duration(Span),
+}
+
+#[derive(Debug, Clone)]
+enum WindowFunction {
+    RowNumber,
+    Rank(bool),
+    Lag(ConcreteExpr, usize, Option<ConcreteExpr>),
+    Lead(ConcreteExpr, usize, Option<ConcreteExpr>),
+    Running(AggregationProgram),
+    Rolling {
+        program: AggregationProgram,
+        frame: Frame,
+        invertible: bool,
+        keep_records: bool,
+    },
+}
+
+fn validate_arity(name: &str, arity: Arity, got: usize) -> Result<(), ConcretizationError> {
+    arity
+        .validate(got)
+        .map_err(|error| ConcretizationError::InvalidArity(name.to_string(), error))
+}
+
+fn parse_offset(arg: Option<&Expr>) -> Result<usize, ConcretizationError> {
+    match arg {
+        None => Ok(1),
+        Some(Expr::Int(n)) if *n > 0 => Ok(*n as usize),
+        _ => Err(ConcretizationError::InvalidWindow(
+            "offset should be a positive integer".to_string(),
+        )),
+    }
+}
+
+fn parse_frame(arg: &Expr, has_time_column: bool) -> Result<Frame, ConcretizationError> {
+    match arg {
+        Expr::Int(n) if *n > 0 => Ok(Frame::Rows(*n as usize)),
+        Expr::Str(duration) => {
+            if !has_time_column {
+                return Err(ConcretizationError::InvalidWindow(format!(
+                    "a time column is required to use the \"{}\" window",
+                    duration
+                )));
+            }
+
+            duration.parse::<Span>().map(Frame::Duration).map_err(|_| {
+                ConcretizationError::InvalidWindow(format!(
+                    "could not parse \"{}\" as a duration",
+                    duration
+                ))
+            })
+        }
+        _ => Err(ConcretizationError::InvalidWindow(
+            "window should be a positive number of rows or a duration string".to_string(),
+        )),
+    }
+}
+
+impl WindowFunction {
+    fn parse(
+        aggregation: Aggregation,
+        headers: &ByteRecord,
+        has_time_column: bool,
+        ordered: bool,
+    ) -> Result<Self, ConcretizationError> {
+        let name = aggregation.func_name.as_str();
+        let mut args = aggregation.args;
+
+        let concretize = |arg: Expr| concretize_expression(arg, headers, None);
+
+        let running = |func_name: &str, args: Vec<Expr>| {
+            AggregationProgram::from_aggregations(
+                vec![Aggregation {
+                    agg_name: aggregation.agg_name.clone(),
+                    func_name: func_name.to_string(),
+                    args,
+                    filter: aggregation.filter.clone(),
+                }],
+                headers,
+            )
+        };
+
+        if aggregation.filter.is_some()
+            && ["row_number", "rank", "dense_rank", "lag", "lead"].contains(&name)
+        {
+            return Err(ConcretizationError::InvalidWindow(format!(
+                "{}() does not support a filter clause",
+                name
+            )));
+        }
+
+        Ok(match name {
+            "row_number" => {
+                validate_arity(name, Arity::Strict(0), args.len())?;
+                Self::RowNumber
+            }
+            "rank" | "dense_rank" => {
+                validate_arity(name, Arity::Strict(0), args.len())?;
+
+                if !ordered {
+                    return Err(ConcretizationError::InvalidWindow(format!(
+                        "{}() requires rows to be ordered using -O, --order-by",
+                        name
+                    )));
+                }
+
+                Self::Rank(name == "dense_rank")
+            }
+            "lag" | "lead" => {
+                validate_arity(name, Arity::Range(1..=3), args.len())?;
+
+                let default = if args.len() == 3 {
+                    Some(concretize(args.pop().unwrap())?)
+                } else {
+                    None
+                };
+
+                let offset = parse_offset(args.get(1))?;
+                let expr = concretize(args.swap_remove(0))?;
+
+                if name == "lag" {
+                    Self::Lag(expr, offset, default)
+                } else {
+                    Self::Lead(expr, offset, default)
+                }
+            }
+            "cumsum" | "cummin" | "cummax" => {
+                validate_arity(name, Arity::Strict(1), args.len())?;
+                Self::Running(running(&name[3..], args)?)
+            }
+            _ => match name.strip_prefix("rolling_") {
+                Some(func_name) => {
+                    validate_arity(name, Arity::Min(1), args.len())?;
+
+                    let frame = parse_frame(&args.remove(0), has_time_column)?;
+                    let program = running(func_name, args)?;
+
+                    Self::Rolling {
+                        invertible: program.is_invertible(),
+                        keep_records: program.requires_records(),
+                        program,
+                        frame,
+                    }
+                }
+                None => return Err(ConcretizationError::UnknownFunction(name.to_string())),
+            },
+        })
+    }
+
+    fn new_state(&self) -> WindowState {
+        match self {
+            Self::Rank(_) => WindowState::Rank {
+                rank: 0,
+                dense_rank: 0,
+            },
+            Self::Lag(_, _, _) => WindowState::Lag(VecDeque::new()),
+            Self::Running(program) => WindowState::Running(program.clone()),
+            Self::Rolling { program, .. } => WindowState::Rolling(program.clone(), VecDeque::new()),
+            _ => WindowState::Stateless,
+        }
+    }
+
+    fn uses_duration(&self) -> bool {
+        matches!(
+            self,
+            Self::Rolling {
+                frame: Frame::Duration(_),
+                ..
+            }
+        )
+    }
+
+    fn lead_offset(&self) -> usize {
+        match self {
+            Self::Lead(_, offset, _) => *offset,
+            _ => 0,
+        }
+    }
+}
+
+// NOTE: rolling windows only buffer the evaluated arguments of their
+// aggregation, and the whole rows only when the aggregation needs them, e.g.
+// to evaluate the return expression of argmin.
+#[derive(Debug)]
+struct RollingEntry {
+    index: usize,
+    time: Option<Timestamp>,
+    row: EvaluatedRow,
+    record: Option<ByteRecord>,
+}
+
+#[derive(Debug)]
+enum WindowState {
+    Stateless,
+    Rank { rank: usize, dense_rank: usize },
+    Lag(VecDeque<Vec<u8>>),
+    Running(AggregationProgram),
+    Rolling(AggregationProgram, VecDeque<RollingEntry>),
+}
+
+#[derive(Debug)]
+struct Partition {
+    count: usize,
+    last_time: Option<Timestamp>,
+    states: Vec<WindowState>,
+    // NOTE: ids of the last rows of the partition, to be able to resolve leads
+    recent: VecDeque<usize>,
+}
+
+#[derive(Debug)]
+struct PendingRow {
+    record: ByteRecord,
+    cells: Vec<Vec<u8>>,
+    unresolved: usize,
+}
+
+// NOTE: rows are emitted in the same order they were given. Which means that
+// when using lead functions, rows need to be buffered until the next rows of
+// their partition are known.
+#[derive(Debug)]
+pub struct WindowAggregationProgram {
+    names: Vec<String>,
+    functions: Vec<WindowFunction>,
+    context: EvaluationContext,
+    time_column: Option<usize>,
+    max_lead: usize,
+    partitions: HashMap<GroupKey, Partition>,
+    queue: VecDeque<PendingRow>,
+    dequeued: usize,
+    empty_record: ByteRecord,
+}
+
+impl WindowAggregationProgram {
+    pub fn parse(
+        code: &str,
+        headers: &ByteRecord,
+        time_column: Option<usize>,
+        ordered: bool,
+    ) -> Result<Self, ConcretizationError> {
+        let aggregations = parse_aggregations(code)
+            .map_err(|_| ConcretizationError::ParseError(code.to_string()))?;
+
+        let mut names = Vec::with_capacity(aggregations.len());
+        let mut functions = Vec::with_capacity(aggregations.len());
+
+        for aggregation in aggregations {
+            names.push(aggregation.agg_name.clone());
+            functions.push(WindowFunction::parse(
+                aggregation,
+                headers,
+                time_column.is_some(),
+                ordered,
+            )?);
+        }
+
+        let uses_duration = functions.iter().any(|f| f.uses_duration());
+        let max_lead = functions.iter().map(|f| f.lead_offset()).max().unwrap_or(0);
+
+        Ok(Self {
+            names,
+            functions,
+            context: EvaluationContext::new(headers),
+            time_column: time_column.filter(|_| uses_duration),
+            max_lead,
+            partitions: HashMap::new(),
+            queue: VecDeque::new(),
+            dequeued: 0,
+            empty_record: ByteRecord::new(),
+        })
+    }
+
+    pub fn headers(&self) -> impl Iterator<Item = &[u8]> {
+        self.names.iter().map(|name| name.as_bytes())
+    }
+
+    fn parse_time(
+        &self,
+        record: &ByteRecord,
+    ) -> Result<Option<Timestamp>, SpecifiedEvaluationError> {
+        let Some(i) = self.time_column else {
+            return Ok(None);
+        };
+
+        let value = String::from_utf8_lossy(&record[i]);
+
+        dates::parse_zoned(&value, None, None)
+            .map(|zoned| Some(zoned.timestamp()))
+            .map_err(|error| {
+                SpecifiedEvaluationError::new(
+                    "window",
+                    EvaluationError::from_zoned_parse_error(&value, None, None, error),
+                )
+            })
+    }
+
+    /// Process the next row of the given partition. `tied` indicates whether
+    /// the row is tied with the previous row of its partition, according to
+    /// the order of the rows, which is used by rank functions.
+    pub fn run_with_record(
+        &mut self,
+        group: GroupKey,
+        index: usize,
+        tied: bool,
+        record: &ByteRecord,
+    ) -> Result<(), SpecifiedEvaluationError> {
+        let time = self.parse_time(record)?;
+        let id = self.dequeued + self.queue.len();
+
+        let functions = &self.functions;
+        let context = &self.context;
+        let queue = &mut self.queue;
+        let dequeued = self.dequeued;
+        let empty_record = &self.empty_record;
+
+        let eval = |expr: &ConcreteExpr| -> Result<Vec<u8>, SpecifiedEvaluationError> {
+            eval_expression(expr, Some(index), record, context)
+                .map(|value| value.serialize_as_bytes().into_owned())
+        };
+
+        let eval_default = |default: &Option<ConcreteExpr>| match default {
+            Some(expr) => eval(expr),
+            None => Ok(vec![]),
+        };
+
+        let partition = self.partitions.entry(group).or_insert_with(|| Partition {
+            count: 0,
+            last_time: None,
+            states: functions.iter().map(|f| f.new_state()).collect(),
+            recent: VecDeque::new(),
+        });
+
+        // NOTE: duration windows evict rows from their front, which is only
+        // correct if time never goes backwards within a partition.
+        if let (Some(current), Some(last)) = (time, partition.last_time) {
+            if current < last {
+                return Err(SpecifiedEvaluationError::new(
+                    "window",
+                    EvaluationError::Custom(format!(
+                        "rows must be sorted by time within their partition, but {} comes after {}",
+                        current, last
+                    )),
+                ));
+            }
+        }
+
+        partition.count += 1;
+        partition.last_time = time;
+
+        let mut cells = Vec::with_capacity(functions.len());
+        let mut unresolved: usize = 0;
+
+        for (i, (function, state)) in functions
+            .iter()
+            .zip(partition.states.iter_mut())
+            .enumerate()
+        {
+            let cell = match (function, state) {
+                (WindowFunction::RowNumber, _) => partition.count.to_string().into_bytes(),
+                (WindowFunction::Rank(dense), WindowState::Rank { rank, dense_rank }) => {
+                    if !tied || partition.count == 1 {
+                        *rank = partition.count;
+                        *dense_rank += 1;
+                    }
+
+                    (if *dense { *dense_rank } else { *rank })
+                        .to_string()
+                        .into_bytes()
+                }
+                (WindowFunction::Lag(expr, offset, default), WindowState::Lag(previous)) => {
+                    let cell = if previous.len() >= *offset {
+                        previous[previous.len() - offset].clone()
+                    } else {
+                        eval_default(default)?
+                    };
+
+                    previous.push_back(eval(expr)?);
+
+                    if previous.len() > *offset {
+                        previous.pop_front();
+                    }
+
+                    cell
+                }
+                (WindowFunction::Lead(expr, offset, default), _) => {
+                    if partition.recent.len() >= *offset {
+                        let target = partition.recent[partition.recent.len() - offset];
+                        let pending = &mut queue[target - dequeued];
+
+                        pending.cells[i] = eval(expr)?;
+                        pending.unresolved -= 1;
+                    }
+
+                    unresolved += 1;
+
+                    eval_default(default)?
+                }
+                (WindowFunction::Running(_), WindowState::Running(program)) => {
+                    program.run_with_record(index, record)?;
+                    program.finalize(false)?[0].to_vec()
+                }
+                (
+                    WindowFunction::Rolling {
+                        frame,
+                        invertible,
+                        keep_records,
+                        ..
+                    },
+                    WindowState::Rolling(program, buffer),
+                ) => {
+                    let row = program.evaluate(index, record)?;
+
+                    if *invertible {
+                        program.run_with_evaluated(index, row.clone(), empty_record)?;
+                    }
+
+                    buffer.push_back(RollingEntry {
+                        index,
+                        time,
+                        row,
+                        record: keep_records.then(|| record.clone()),
+                    });
+
+                    let evicted = match frame {
+                        Frame::Rows(size) => buffer.len().saturating_sub(*size),
+                        Frame::Duration(span) => {
+                            // NOTE: going through UTC so that spans may contain days
+                            let start = time
+                                .unwrap()
+                                .to_zoned(TimeZone::UTC)
+                                .checked_sub(*span)
+                                .map_err(|error| {
+                                    SpecifiedEvaluationError::new(
+                                        "window",
+                                        EvaluationError::DateTime(error.to_string()),
+                                    )
+                                })?
+                                .timestamp();
+
+                            buffer
+                                .iter()
+                                .take_while(|entry| entry.time.unwrap() <= start)
+                                .count()
+                        }
+                    };
+
+                    for entry in buffer.drain(..evicted) {
+                        if *invertible {
+                            program.remove_evaluated(&entry.row)?;
+                        }
+                    }
+
+                    // NOTE: aggregations that cannot be updated incrementally
+                    // are recomputed over the buffered values of the window.
+                    if !*invertible {
+                        program.clear();
+
+                        for entry in buffer.iter() {
+                            program.run_with_evaluated(
+                                entry.index,
+                                entry.row.clone(),
+                                entry.record.as_ref().unwrap_or(empty_record),
+                            )?;
+                        }
+                    }
+
+                    program.finalize(false)?[0].to_vec()
+                }
+                _ => unreachable!(),
+            };
+
+            cells.push(cell);
+        }
+
+        if self.max_lead > 0 {
+            partition.recent.push_back(id);
+
+            if partition.recent.len() > self.max_lead {
+                partition.recent.pop_front();
+            }
+        }
+
+        queue.push_back(PendingRow {
+            record: record.clone(),
+            cells,
+            unresolved,
+        });
+
+        Ok(())
+    }
+
+    /// Return the next row whose window values are all known, if any.
+    pub fn pop(&mut self) -> Option<ByteRecord> {
+        if self.queue.front()?.unresolved > 0 {
+            return None;
+        }
+
+        let mut row = self.queue.pop_front().unwrap();
+        self.dequeued += 1;
+
+        for cell in row.cells {
+            row.record.push_field(&cell);
+        }
+
+        Some(row.record)
+    }
+
+    /// Indicate that there are no more rows, so that remaining leads can be
+    /// resolved using their default values.
+    pub fn finish(&mut self) {
+        for row in self.queue.iter_mut() {
+            row.unresolved = 0;
+        }
+    }
+}
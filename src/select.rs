@@ -572,6 +572,10 @@ impl Selection {
         self.0.insert(index, element);
     }
 
+    pub fn pop(&mut self) -> Option<usize> {
+        self.0.pop()
+    }
+
     pub fn select<'a, 'b>(&'a self, row: &'b csv::ByteRecord) -> impl Iterator<Item = &'b [u8]>
     where
         'a: 'b,
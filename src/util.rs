@@ -383,6 +383,50 @@ pub fn could_be_url(string: &str) -> bool {
     false
 }
 
+pub fn could_be_email(string: &str) -> bool {
+    match string.split_once('@') {
+        Some((user, domain)) => {
+            !user.is_empty()
+                && !string.contains(char::is_whitespace)
+                && !domain.contains('@')
+                && domain
+                    .split_once('.')
+                    .is_some_and(|(name, tld)| !name.is_empty() && !tld.is_empty())
+        }
+        None => false,
+    }
+}
+
+// NOTE: uppercase letters are masked as "A", other letters as "a", digits as
+// "9" and whitespace as " ". Remaining characters are kept as-is.
+pub fn value_shape(string: &str, collapse: bool) -> String {
+    let mut shape = String::with_capacity(string.len());
+    let mut last: Option<char> = None;
+
+    for c in string.chars() {
+        let masked = if c.is_uppercase() {
+            'A'
+        } else if c.is_alphabetic() {
+            'a'
+        } else if c.is_numeric() {
+            '9'
+        } else if c.is_whitespace() {
+            ' '
+        } else {
+            c
+        };
+
+        if collapse && last == Some(masked) {
+            continue;
+        }
+
+        shape.push(masked);
+        last = Some(masked);
+    }
+
+    shape
+}
+
 #[derive(PartialEq, Debug)]
 pub enum ColorOrStyles {
     Color(Color),
@@ -750,6 +794,49 @@ impl<'a> ImmutableRecordHelpers<'a> for csv::StringRecord {
     }
 }
 
+// NOTE: used by commands accepting a weight column, e.g. `xan stats -w`.
+pub fn parse_weight(cell: &[u8]) -> Result<f64, String> {
+    match fast_float::parse::<f64, &[u8]>(cell) {
+        Ok(weight) if weight >= 0.0 => Ok(weight),
+        _ => Err(format!(
+            "could not parse weight \"{}\" as a non-negative number!",
+            String::from_utf8_lossy(cell)
+        )),
+    }
+}
+
+// NOTE: returns the average ranks of given values, along with the sum of
+// t^3 - t for each group of t tied values. Used by `xan test` & `xan matrix`.
+pub fn average_ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut indices = (0..values.len()).collect::<Vec<_>>();
+    indices.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = 0.0;
+    let mut i = 0;
+
+    while i < indices.len() {
+        let mut j = i + 1;
+
+        while j < indices.len() && values[indices[j]] == values[indices[i]] {
+            j += 1;
+        }
+
+        let rank = (i + j + 1) as f64 / 2.0;
+
+        for index in indices[i..j].iter() {
+            ranks[*index] = rank;
+        }
+
+        let t = (j - i) as f64;
+        ties += t * t * t - t;
+
+        i = j;
+    }
+
+    (ranks, ties)
+}
+
 pub fn str_to_csv_byte_record(target: &str) -> csv::ByteRecord {
     let cursor = io::Cursor::new(target);
     let reader = csv::ReaderBuilder::new()
@@ -926,6 +1013,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_value_shape() {
+        assert_eq!(value_shape("2024-01-31", false), "9999-99-99");
+        assert_eq!(value_shape("2024-01-31", true), "9-9-9");
+        assert_eq!(value_shape("Hello big world", true), "Aa a a");
+        assert_eq!(value_shape("AB-123", false), "AA-999");
+        assert_eq!(value_shape("Été", false), "Aaa");
+    }
+
+    #[test]
+    fn test_could_be_email() {
+        assert!(could_be_email("john@example.com"));
+        assert!(!could_be_email("john@example"));
+        assert!(!could_be_email("@example.com"));
+        assert!(!could_be_email("john doe@example.com"));
+        assert!(!could_be_email("john@@example.com"));
+    }
+
     macro_rules! brec {
         () => {
             csv::ByteRecord::new()
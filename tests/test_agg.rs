@@ -131,6 +131,175 @@ fn agg_sqlish_count() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn agg_if_clause() {
+    let wrk = Workdir::new("agg_if_clause");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["status", "n"],
+            svec!["ok", "1"],
+            svec!["ko", "2"],
+            svec!["ok", "3"],
+            svec!["ok", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("count() if status eq 'ok' as ok_count, count() as count, sum(n) if n > 1 as sum, mean(n) if status eq 'ok'")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["ok_count", "count", "sum", "mean(n) if status eq 'ok'"],
+        svec!["3", "4", "9", "2.6666666666666665"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn agg_distribution_measures() {
+    let wrk = Workdir::new("agg_distribution_measures");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n", "cat"],
+            svec!["1", "a"],
+            svec!["2", "a"],
+            svec!["4", "b"],
+            svec!["", "b"],
+        ],
+    );
+
+    test_single_agg_function(&wrk, "entropy(cat) as h", "h", "1");
+    test_single_agg_function(&wrk, "gini(n) as gini", "gini", "0.2857142857142858");
+    test_single_agg_function(&wrk, "mad(n) as mad", "mad", "1");
+    test_single_agg_function(&wrk, "geometric_mean(n) as mean", "mean", "2");
+    test_single_agg_function(
+        &wrk,
+        "harmonic_mean(n) as mean",
+        "mean",
+        "1.7142857142857142",
+    );
+    test_single_agg_function(
+        &wrk,
+        "skewness(n) as skewness",
+        "skewness",
+        "0.3818017741606063",
+    );
+    test_single_agg_function(&wrk, "kurtosis(n) as kurtosis", "kurtosis", "-1.5");
+}
+
+#[test]
+fn agg_weighted() {
+    let wrk = Workdir::new("agg_weighted");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n", "w"],
+            svec!["1", "1"],
+            svec!["2", "3"],
+            svec!["5", "2"],
+        ],
+    );
+
+    test_single_agg_function(
+        &wrk,
+        "weighted_mean(n, w) as mean",
+        "mean",
+        "2.833333333333333",
+    );
+    test_single_agg_function(
+        &wrk,
+        "weighted_var(n, w) as var",
+        "var",
+        "2.4722222222222228",
+    );
+    test_single_agg_function(
+        &wrk,
+        "weighted_stddev(n, w) as stddev",
+        "stddev",
+        "1.5723301886761007",
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("weighted_mean(n)").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn agg_string_agg() {
+    let wrk = Workdir::new("agg_string_agg");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "n"],
+            svec!["john", "3"],
+            svec!["lucy", "10"],
+            svec!["mary", "1"],
+            svec!["", "5"],
+        ],
+    );
+
+    test_single_agg_function(&wrk, "string_agg(name) as s", "s", "john|lucy|mary");
+    test_single_agg_function(&wrk, "string_agg(name, ', ') as s", "s", "john, lucy, mary");
+    test_single_agg_function(&wrk, "string_agg(name, '|', n) as s", "s", "mary|john|lucy");
+    test_single_agg_function(
+        &wrk,
+        "string_agg(name, '|', -n) as s",
+        "s",
+        "lucy|john|mary",
+    );
+}
+
+#[test]
+fn agg_pair_aggregations_do_not_shadow_others() {
+    let wrk = Workdir::new("agg_pair_aggregations_do_not_shadow_others");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["2", "4"]],
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("correlation(a, b) as corr, sum(a) as sum")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["corr", "sum"], svec!["1", "3"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("covariance(a, b) as cov, count() as count, mean(b) as mean")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["cov", "count", "mean"], svec!["0.5", "2", "3"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn agg_pair_aggregations_arity() {
+    let wrk = Workdir::new("agg_pair_aggregations_arity");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["2", "4"]],
+    );
+
+    for func in [
+        "covariance",
+        "covariance_pop",
+        "covariance_sample",
+        "correlation",
+    ] {
+        let mut cmd = wrk.command("agg");
+        cmd.arg(format!("{}(a)", func)).arg("data.csv");
+
+        // NOTE: a panic would exit with code 101
+        assert_eq!(cmd.output().unwrap().status.code(), Some(1));
+    }
+}
+
 #[test]
 fn agg_multiple_columns() {
     let wrk = Workdir::new("agg_multiple_columns");
@@ -366,3 +366,109 @@ fn dedup_choose_sorted() {
     ];
     assert_eq!(got, expected);
 }
+
+fn near_duplicates() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "text"],
+        svec!["1", "the quick brown fox jumps over the lazy dog"],
+        svec!["2", "the quick brown fox jumps over the lazy dog!"],
+        svec!["3", "lorem ipsum dolor sit amet consectetur"],
+        svec!["4", "The quick brown fox jumps over the lazy dog!!"],
+        svec!["5", "lorem ipsum dolor sit amet consectetur adipiscing"],
+        svec!["6", "something completely different"],
+    ]
+}
+
+#[test]
+fn dedup_jaccard() {
+    let wrk = Workdir::new("dedup_jaccard");
+    wrk.create("data.csv", near_duplicates());
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-s", "text", "--jaccard", "0.7"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "text"],
+        svec!["1", "the quick brown fox jumps over the lazy dog"],
+        svec!["3", "lorem ipsum dolor sit amet consectetur"],
+        svec!["6", "something completely different"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-s", "text", "--jaccard", "0.5"])
+        .args(["--word-shingles", "--shingle-size", "2"])
+        .args(["--cluster-column", "cluster"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "text", "cluster"],
+        svec!["1", "the quick brown fox jumps over the lazy dog", "0"],
+        svec!["2", "the quick brown fox jumps over the lazy dog!", "0"],
+        svec!["3", "lorem ipsum dolor sit amet consectetur", "1"],
+        svec!["4", "The quick brown fox jumps over the lazy dog!!", "0"],
+        svec![
+            "5",
+            "lorem ipsum dolor sit amet consectetur adipiscing",
+            "1"
+        ],
+        svec!["6", "something completely different", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn dedup_jaccard_errors() {
+    let wrk = Workdir::new("dedup_jaccard_errors");
+    wrk.create("data.csv", near_duplicates());
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--jaccard", "1.5"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--jaccard", "0.5", "--sorted"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--cluster-column", "cluster"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn dedup_approx() {
+    let wrk = Workdir::new("dedup_approx");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "color"],
+            svec!["john", "blue"],
+            svec!["mary", "red"],
+            svec!["john", "yellow"],
+            svec!["lucy", "red"],
+            svec!["mary", "purple"],
+        ],
+    );
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-a", "-s", "name"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "color"],
+        svec!["john", "blue"],
+        svec!["mary", "red"],
+        svec!["lucy", "red"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-a", "--keep-last"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-m", "10"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -295,3 +295,133 @@ fn frequency_all() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn frequency_natural() {
+    let wrk = Workdir::new("frequency_natural");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["file"],
+            svec!["file10"],
+            svec!["file9"],
+            svec!["file2"],
+            svec!["file10"],
+        ],
+    );
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("--natural").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["file", "file10", "2"],
+        svec!["file", "file2", "1"],
+        svec!["file", "file9", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["--natural", "-l", "2"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["file", "file10", "2"],
+        svec!["file", "file2", "1"],
+        svec!["file", "<rest>", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_shapes() {
+    let wrk = Workdir::new("frequency_shapes");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["date", "name"],
+            svec!["2024-01-31", "John Doe"],
+            svec!["2024-02-01", "Mary Sue"],
+            svec!["31/01/2024", "lucy"],
+            svec!["", "Hello world"],
+        ],
+    );
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("-S").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["date", "9999-99-99", "2"],
+        svec!["date", "99/99/9999", "1"],
+        svec!["date", "<empty>", "1"],
+        svec!["name", "Aaaa Aaa", "2"],
+        svec!["name", "Aaaaa aaaaa", "1"],
+        svec!["name", "aaaa", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["-S", "--collapse", "-s", "name"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["name", "Aa Aa", "2"],
+        svec!["name", "Aa a", "1"],
+        svec!["name", "a", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("--collapse").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn frequency_weight() {
+    let wrk = Workdir::new("frequency_weight");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["color", "weight"],
+            svec!["red", "2"],
+            svec!["blue", "0.5"],
+            svec!["red", "1"],
+            svec!["", "1"],
+            svec!["green", "0.25"],
+            svec!["yellow", "0"],
+        ],
+    );
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["-w", "weight"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["color", "red", "3"],
+        svec!["color", "<empty>", "1"],
+        svec!["color", "blue", "0.5"],
+        svec!["color", "green", "0.25"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["-w", "weight", "-l", "1"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["color", "red", "3"],
+        svec!["color", "<rest>", "1.75"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.args(["-w", "weight", "-a"]).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -149,6 +149,36 @@ fn groupby_max() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn groupby_if_clause() {
+    let wrk = Workdir::new("groupby_if_clause");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "status", "value"],
+            svec!["x", "ok", "1"],
+            svec!["y", "ko", "3"],
+            svec!["x", "ko", "2"],
+            svec!["y", "ok", "5"],
+            svec!["x", "ok", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("count() if status eq 'ok' as ok, max(value) if status eq 'ko' as max_ko")
+        .arg("data.csv");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1..].sort();
+    let expected = vec![
+        svec!["id", "ok", "max_ko"],
+        svec!["x", "2", "2"],
+        svec!["y", "1", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn groupby_sorted() {
     let wrk = Workdir::new("groupby");
@@ -316,3 +346,209 @@ fn groupby_complex_keep() {
     ];
     assert_eq!(got, expected);
 }
+
+fn grouping_sets_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["country", "year", "count"],
+        svec!["France", "2020", "10"],
+        svec!["France", "2021", "5"],
+        svec!["Italy", "2020", "6"],
+        svec!["France", "2020", "2"],
+    ]
+}
+
+#[test]
+fn groupby_rollup() {
+    let wrk = Workdir::new("groupby_rollup");
+    wrk.create("data.csv", grouping_sets_data());
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("country,year")
+        .arg("sum(count) as sum")
+        .args(["--rollup", "--total-marker", "TOTAL"])
+        .arg("data.csv");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1..].sort();
+    let expected = vec![
+        svec!["country", "year", "sum"],
+        svec!["France", "2020", "12"],
+        svec!["France", "2021", "5"],
+        svec!["France", "TOTAL", "17"],
+        svec!["Italy", "2020", "6"],
+        svec!["Italy", "TOTAL", "6"],
+        svec!["TOTAL", "TOTAL", "23"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_cube() {
+    let wrk = Workdir::new("groupby_cube");
+    wrk.create("data.csv", grouping_sets_data());
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("country,year")
+        .arg("sum(count) as sum")
+        .arg("--cube")
+        .arg("data.csv");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1..].sort();
+    let expected = vec![
+        svec!["country", "year", "sum"],
+        svec!["", "", "23"],
+        svec!["", "2020", "18"],
+        svec!["", "2021", "5"],
+        svec!["France", "", "17"],
+        svec!["France", "2020", "12"],
+        svec!["France", "2021", "5"],
+        svec!["Italy", "", "6"],
+        svec!["Italy", "2020", "6"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_grouping_sets() {
+    let wrk = Workdir::new("groupby_grouping_sets");
+    wrk.create("data.csv", grouping_sets_data());
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("country,year")
+        .arg("sum(count) as sum")
+        .args(["--grouping-sets", "year;"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let mut sets = got[1..3].to_vec();
+    sets.sort();
+    assert_eq!(sets, vec![svec!["", "2020", "18"], svec!["", "2021", "5"]]);
+    assert_eq!(got[3], svec!["", "", "23"]);
+    assert_eq!(got.len(), 4);
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("country")
+        .arg("sum(count) as sum")
+        .args(["--grouping-sets", "year"])
+        .arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn groupby_grouping_sets_errors() {
+    let wrk = Workdir::new("groupby_grouping_sets_errors");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["country", "year", "count"],
+            svec!["France", "2020", "1"],
+            svec!["Italy", "2021", "oops"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("country,year")
+        .arg("sum(count) as sum")
+        .args(["--cube", "-e", "log"])
+        .arg("data.csv");
+
+    let output = wrk.output(&mut cmd);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    // Errors must be reported once per row, not once per grouping set
+    assert_eq!(stderr.lines().count(), 1);
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1..].sort();
+    let expected = vec![
+        svec!["country", "year", "sum"],
+        svec!["", "", "1"],
+        svec!["", "2020", "1"],
+        svec!["", "2021", "0"],
+        svec!["France", "", "1"],
+        svec!["France", "2020", "1"],
+        svec!["Italy", "", "0"],
+        svec!["Italy", "2021", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_external() {
+    let wrk = Workdir::new("groupby_external");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "value"],
+            svec!["y", "1"],
+            svec!["x", "2"],
+            svec!["z", "3"],
+            svec!["x", "4"],
+            svec!["y", "5"],
+            svec!["x", "6"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("values(value) as values, sum(value) as sum")
+        .args(["--external", "-m", "0"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "values", "sum"],
+        svec!["y", "1|5", "6"],
+        svec!["x", "2|4|6", "12"],
+        svec!["z", "3", "3"],
+    ];
+    assert_eq!(got, expected);
+
+    // Row indices must survive spilling
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("first(index()) as first, argmax(value) as argmax")
+        .args(["--external", "-m", "0"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "first", "argmax"],
+        svec!["y", "0", "4"],
+        svec!["x", "1", "5"],
+        svec!["z", "2", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_external_hot_key() {
+    let wrk = Workdir::new("groupby_external_hot_key");
+
+    let mut rows = vec![svec!["id"]];
+
+    for i in 0..100 {
+        rows.push(svec!["hot"]);
+        rows.push(vec![format!("cold{}", i)]);
+    }
+
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("count() as count")
+        .args(["--external", "-m", "0"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    let mut expected = vec![svec!["id", "count"], svec!["hot", "100"]];
+
+    for i in 0..100 {
+        expected.push(vec![format!("cold{}", i), "1".to_string()]);
+    }
+
+    assert_eq!(got, expected);
+}
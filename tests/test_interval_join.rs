@@ -0,0 +1,202 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "variants.csv",
+        vec![
+            svec!["chrom", "pos", "id"],
+            svec!["1", "100", "v1"],
+            svec!["1", "250", "v2"],
+            svec!["2", "100", "v3"],
+            svec!["1", "5000", "v4"],
+        ],
+    );
+    wrk.create(
+        "genes.csv",
+        vec![
+            svec!["chrom", "start", "end", "gene"],
+            svec!["1", "50", "150", "g1"],
+            svec!["1", "90", "300", "g2"],
+            svec!["2", "1", "99", "g3"],
+            svec!["1", "240", "260", "g4"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn interval_join_points() {
+    let wrk = setup("interval_join_points");
+    let mut cmd = wrk.command("interval-join");
+    cmd.args([
+        "--by",
+        "chrom",
+        "pos",
+        "variants.csv",
+        "start,end",
+        "genes.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["chrom", "pos", "id", "chrom", "start", "end", "gene"],
+        svec!["1", "100", "v1", "1", "50", "150", "g1"],
+        svec!["1", "100", "v1", "1", "90", "300", "g2"],
+        svec!["1", "250", "v2", "1", "90", "300", "g2"],
+        svec!["1", "250", "v2", "1", "240", "260", "g4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn interval_join_left() {
+    let wrk = setup("interval_join_left");
+    let mut cmd = wrk.command("interval-join");
+    cmd.args(["--left", "--by", "chrom"])
+        .args(["pos", "variants.csv", "start,end", "genes.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["chrom", "pos", "id", "chrom", "start", "end", "gene"],
+        svec!["1", "100", "v1", "1", "50", "150", "g1"],
+        svec!["1", "100", "v1", "1", "90", "300", "g2"],
+        svec!["1", "250", "v2", "1", "90", "300", "g2"],
+        svec!["1", "250", "v2", "1", "240", "260", "g4"],
+        svec!["2", "100", "v3", "", "", "", ""],
+        svec!["1", "5000", "v4", "", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn interval_join_intervals() {
+    let wrk = setup("interval_join_intervals");
+    wrk.create("ranges.csv", vec![svec!["from", "to"], svec!["80", "120"]]);
+
+    // Overlap
+    let mut cmd = wrk.command("interval-join");
+    cmd.args([
+        "-R",
+        "gene_",
+        "from,to",
+        "ranges.csv",
+        "start,end",
+        "genes.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "from",
+            "to",
+            "gene_chrom",
+            "gene_start",
+            "gene_end",
+            "gene_gene"
+        ],
+        svec!["80", "120", "2", "1", "99", "g3"],
+        svec!["80", "120", "1", "50", "150", "g1"],
+        svec!["80", "120", "1", "90", "300", "g2"],
+    ];
+    assert_eq!(got, expected);
+
+    // Minimum overlap
+    let mut cmd = wrk.command("interval-join");
+    cmd.args(["--min-overlap", "30"])
+        .args(["from,to", "ranges.csv", "start,end", "genes.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["from", "to", "chrom", "start", "end", "gene"],
+        svec!["80", "120", "1", "50", "150", "g1"],
+        svec!["80", "120", "1", "90", "300", "g2"],
+    ];
+    assert_eq!(got, expected);
+
+    // Minimum overlap is the length of the intersection of closed intervals
+    let mut cmd = wrk.command("interval-join");
+    cmd.args(["--min-overlap", "19"])
+        .args(["from,to", "ranges.csv", "start,end", "genes.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 4);
+
+    let mut cmd = wrk.command("interval-join");
+    cmd.args(["--min-overlap", "20"])
+        .args(["from,to", "ranges.csv", "start,end", "genes.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 3);
+
+    // Invalid options
+    let mut cmd = wrk.command("interval-join");
+    cmd.args(["--min-overlap=-1"])
+        .args(["from,to", "ranges.csv", "start,end", "genes.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("interval-join");
+    cmd.args(["--within", "--contains"])
+        .args(["from,to", "ranges.csv", "start,end", "genes.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    // Containment
+    let mut cmd = wrk.command("interval-join");
+    cmd.args(["--within"])
+        .args(["from,to", "ranges.csv", "start,end", "genes.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["from", "to", "chrom", "start", "end", "gene"],
+        svec!["80", "120", "1", "50", "150", "g1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn interval_join_dates() {
+    let wrk = Workdir::new("interval_join_dates");
+    wrk.create(
+        "events.csv",
+        vec![
+            svec!["time"],
+            svec!["2024-01-01T10:00:00"],
+            svec!["2024-03-01T00:00:00"],
+        ],
+    );
+    wrk.create(
+        "periods.csv",
+        vec![
+            svec!["name", "from", "to"],
+            svec!["january", "2024-01-01T00:00:00", "2024-01-31T23:59:59"],
+            svec!["winter", "2023-12-21T00:00:00", "2024-03-20T00:00:00"],
+        ],
+    );
+
+    let mut cmd = wrk.command("interval-join");
+    cmd.args(["--dates", "time", "events.csv", "from,to", "periods.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["time", "name", "from", "to"],
+        svec![
+            "2024-01-01T10:00:00",
+            "winter",
+            "2023-12-21T00:00:00",
+            "2024-03-20T00:00:00"
+        ],
+        svec![
+            "2024-01-01T10:00:00",
+            "january",
+            "2024-01-01T00:00:00",
+            "2024-01-31T23:59:59"
+        ],
+        svec![
+            "2024-03-01T00:00:00",
+            "winter",
+            "2023-12-21T00:00:00",
+            "2024-03-20T00:00:00"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
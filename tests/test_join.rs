@@ -138,6 +138,59 @@ join_test!(
     }
 );
 
+#[test]
+fn join_semi() {
+    let wrk = setup("join_semi", true);
+    let mut cmd = wrk.command("join");
+    cmd.arg("--semi")
+        .args(["city", "cities.csv", "city", "places.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state"],
+        svec!["Boston", "MA"],
+        svec!["Buffalo", "NY"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_anti() {
+    let wrk = setup("join_anti", true);
+    let mut cmd = wrk.command("join");
+    cmd.arg("--anti")
+        .args(["city", "cities.csv", "city", "places.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state"],
+        svec!["New York", "NY"],
+        svec!["San Francisco", "CA"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_outer_full_indicator() {
+    let wrk = setup("join_outer_full_indicator", true);
+    let mut cmd = wrk.command("join");
+    cmd.args(["--full", "--indicator", "_merge"]).args([
+        "city",
+        "cities.csv",
+        "city",
+        "places.csv",
+    ]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state", "city", "place", "_merge"],
+        svec!["Boston", "MA", "Boston", "Logan Airport", "both"],
+        svec!["Boston", "MA", "Boston", "Boston Garden", "both"],
+        svec!["Buffalo", "NY", "Buffalo", "Ralph Wilson Stadium", "both"],
+        svec!["", "", "Orlando", "Disney World", "right_only"],
+        svec!["New York", "NY", "", "", "left_only"],
+        svec!["San Francisco", "CA", "", "", "left_only"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn join_inner_issue11() {
     let a = vec![svec!["1", "2"], svec!["3", "4"], svec!["5", "6"]];
@@ -238,3 +291,352 @@ fn join_prefix() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn join_asof() {
+    let wrk = Workdir::new("join_asof");
+    wrk.create(
+        "events.csv",
+        vec![
+            svec!["group", "time"],
+            svec!["a", "3"],
+            svec!["b", "5"],
+            svec!["a", "12"],
+            svec!["a", "0"],
+        ],
+    );
+    wrk.create(
+        "references.csv",
+        vec![
+            svec!["group", "time", "value"],
+            svec!["a", "10", "second"],
+            svec!["a", "1", "first"],
+            svec!["b", "7", "third"],
+        ],
+    );
+
+    // Backward
+    let mut cmd = wrk.command("join");
+    cmd.arg("--asof")
+        .args(["group,time", "events.csv", "group,time", "references.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "time", "group", "time", "value"],
+        svec!["a", "3", "a", "1", "first"],
+        svec!["b", "5", "", "", ""],
+        svec!["a", "12", "a", "10", "second"],
+        svec!["a", "0", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+
+    // Forward
+    let mut cmd = wrk.command("join");
+    cmd.args(["--asof", "--direction", "forward"]).args([
+        "group,time",
+        "events.csv",
+        "group,time",
+        "references.csv",
+    ]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "time", "group", "time", "value"],
+        svec!["a", "3", "a", "10", "second"],
+        svec!["b", "5", "b", "7", "third"],
+        svec!["a", "12", "", "", ""],
+        svec!["a", "0", "a", "1", "first"],
+    ];
+    assert_eq!(got, expected);
+
+    // Nearest, without by keys
+    let mut cmd = wrk.command("join");
+    cmd.args(["--asof", "--direction", "nearest", "--tolerance", "2"])
+        .args(["time", "events.csv", "time", "references.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "time", "group", "time", "value"],
+        svec!["a", "3", "a", "1", "first"],
+        svec!["b", "5", "b", "7", "third"],
+        svec!["a", "12", "a", "10", "second"],
+        svec!["a", "0", "a", "1", "first"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_asof_dates() {
+    let wrk = Workdir::new("join_asof_dates");
+    wrk.create(
+        "trades.csv",
+        vec![
+            svec!["ticker", "time"],
+            svec!["A", "2024-01-01T10:30:00"],
+            svec!["A", "2024-01-01T12:00:00"],
+            svec!["B", "2024-01-01T10:00:00"],
+        ],
+    );
+    wrk.create(
+        "quotes.csv",
+        vec![
+            svec!["ticker", "time", "price"],
+            svec!["A", "2024-01-01T10:00:00", "10"],
+            svec!["A", "2024-01-01T10:20:00", "11"],
+            svec!["B", "2024-01-01T09:59:00", "20"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--asof", "--dates", "--tolerance", "1h"]).args([
+        "ticker,time",
+        "trades.csv",
+        "ticker,time",
+        "quotes.csv",
+    ]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["ticker", "time", "ticker", "time", "price"],
+        svec!["A", "2024-01-01T10:30:00", "A", "2024-01-01T10:20:00", "11"],
+        svec!["A", "2024-01-01T12:00:00", "", "", ""],
+        svec!["B", "2024-01-01T10:00:00", "B", "2024-01-01T09:59:00", "20"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_asof_invalid_options() {
+    let wrk = Workdir::new("join_asof_invalid_options");
+    wrk.create("a.csv", vec![svec!["t"], svec!["1"]]);
+    wrk.create("b.csv", vec![svec!["t"], svec!["1"]]);
+
+    for tolerance in ["-1", "NaN", "inf"] {
+        let mut cmd = wrk.command("join");
+        cmd.args(["--asof", "--tolerance", tolerance])
+            .args(["t", "a.csv", "t", "b.csv"]);
+        wrk.assert_err(&mut cmd);
+    }
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--asof", "--dates", "--tolerance", "-1h"])
+        .args(["t", "a.csv", "t", "b.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--direction", "forward"])
+        .args(["t", "a.csv", "t", "b.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn join_sorted() {
+    let wrk = Workdir::new("join_sorted");
+    wrk.create(
+        "left.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "john"],
+            svec!["2", "mary"],
+            svec!["2", "lucy"],
+            svec!["4", "august"],
+        ],
+    );
+    wrk.create(
+        "right.csv",
+        vec![
+            svec!["id", "color"],
+            svec!["2", "blue"],
+            svec!["2", "red"],
+            svec!["3", "green"],
+            svec!["4", "yellow"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.arg("--sorted")
+        .args(["id", "left.csv", "id", "right.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "id", "color"],
+        svec!["2", "mary", "2", "blue"],
+        svec!["2", "mary", "2", "red"],
+        svec!["2", "lucy", "2", "blue"],
+        svec!["2", "lucy", "2", "red"],
+        svec!["4", "august", "4", "yellow"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--sorted", "--full"])
+        .args(["id", "left.csv", "id", "right.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "id", "color"],
+        svec!["1", "john", "", ""],
+        svec!["2", "mary", "2", "blue"],
+        svec!["2", "mary", "2", "red"],
+        svec!["2", "lucy", "2", "blue"],
+        svec!["2", "lucy", "2", "red"],
+        svec!["", "", "3", "green"],
+        svec!["4", "august", "4", "yellow"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--sorted", "--anti"])
+        .args(["id", "left.csv", "id", "right.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name"], svec!["1", "john"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_sorted_not_sorted() {
+    let wrk = setup("join_sorted_not_sorted", true);
+    let mut cmd = wrk.command("join");
+    cmd.arg("--sorted")
+        .args(["city", "cities.csv", "city", "places.csv"]);
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn join_external() {
+    let wrk = setup("join_external", true);
+    let mut cmd = wrk.command("join");
+    cmd.args(["-e", "--left"])
+        .args(["city", "cities.csv", "city", "places.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state", "city", "place"],
+        svec!["Boston", "MA", "Boston", "Logan Airport"],
+        svec!["Boston", "MA", "Boston", "Boston Garden"],
+        svec!["Buffalo", "NY", "Buffalo", "Ralph Wilson Stadium"],
+        svec!["New York", "NY", "", ""],
+        svec!["San Francisco", "CA", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_expr() {
+    let wrk = Workdir::new("join_expr");
+    wrk.create(
+        "people.csv",
+        vec![svec!["name"], svec!["John Smith"], svec!["mary JANE"]],
+    );
+    wrk.create(
+        "users.csv",
+        vec![
+            svec!["full_name", "age"],
+            svec!["smith john", "34"],
+            svec!["Jane Mary", "45"],
+            svec!["bob", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.arg("--expr").args([
+        "fingerprint(name)",
+        "people.csv",
+        "fingerprint(full_name)",
+        "users.csv",
+    ]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "full_name", "age"],
+        svec!["John Smith", "smith john", "34"],
+        svec!["mary JANE", "Jane Mary", "45"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--expr", "--anti"]).args([
+        "fingerprint(full_name)",
+        "users.csv",
+        "fingerprint(name)",
+        "people.csv",
+    ]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["full_name", "age"], svec!["bob", "4"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_multi() {
+    let wrk = Workdir::new("join_multi");
+    wrk.create(
+        "people.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "john"],
+            svec!["2", "mary"],
+            svec!["3", "lucy"],
+        ],
+    );
+    wrk.create(
+        "addresses.csv",
+        vec![
+            svec!["id", "name", "city"],
+            svec!["2", "home", "paris"],
+            svec!["1", "work", "lyon"],
+            svec!["1", "home", "nice"],
+            svec!["4", "home", "lille"],
+        ],
+    );
+    wrk.create(
+        "jobs.csv",
+        vec![
+            svec!["id", "job"],
+            svec!["1", "dev"],
+            svec!["2", "cook"],
+            svec!["5", "pilot"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--multi", "id", "people.csv", "addresses.csv", "jobs.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "people_name", "addresses_name", "city", "job"],
+        svec!["1", "john", "work", "lyon", "dev"],
+        svec!["1", "john", "home", "nice", "dev"],
+        svec!["2", "mary", "home", "paris", "cook"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--multi", "--full"])
+        .args(["id", "people.csv", "addresses.csv", "jobs.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "people_name", "addresses_name", "city", "job"],
+        svec!["1", "john", "work", "lyon", "dev"],
+        svec!["1", "john", "home", "nice", "dev"],
+        svec!["2", "mary", "home", "paris", "cook"],
+        svec!["3", "lucy", "", "", ""],
+        svec!["4", "", "home", "lille", ""],
+        svec!["5", "", "", "", "pilot"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_multi_prefixes() {
+    let wrk = Workdir::new("join_multi_prefixes");
+    std::fs::create_dir_all(wrk.path("a")).unwrap();
+    std::fs::create_dir_all(wrk.path("b")).unwrap();
+
+    wrk.create(
+        "my.people.csv",
+        vec![svec!["id", "name"], svec!["1", "john"]],
+    );
+    wrk.create("a/data.csv", vec![svec!["id", "name"], svec!["1", "home"]]);
+    wrk.create("b/data.csv", vec![svec!["id", "name"], svec!["1", "dev"]]);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--multi", "id", "my.people.csv", "a/data.csv", "b/data.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "my.people_name", "input2_name", "input3_name"],
+        svec!["1", "john", "home", "dev"],
+    ];
+    assert_eq!(got, expected);
+}
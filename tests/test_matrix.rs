@@ -0,0 +1,311 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "a", "b", "c", "tags"],
+        svec!["x", "1", "2", "3", "cat|dog"],
+        svec!["y", "2", "4", "1", "dog"],
+        svec!["z", "3", "5", "2", "cat|bird"],
+        svec!["w", "4", "4", "0", ""],
+    ]
+}
+
+#[test]
+fn matrix_corr() {
+    let wrk = Workdir::new("matrix_corr");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["corr", "-s", "a,c"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["", "a", "c"],
+        svec!["a", "", "-0.7999999999999998"],
+        svec!["c", "-0.7999999999999998", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["corr", "-D", "-s", "a,c"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["", "a", "c"],
+        svec!["a", "1", "-0.7999999999999998"],
+        svec!["c", "-0.7999999999999998", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_corr_spearman() {
+    let wrk = Workdir::new("matrix_corr_spearman");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["corr", "-m", "spearman", "-s", "a,b", "--long"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "target", "value"],
+        svec!["a", "b", "0.6324555320336759"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_corr_kendall() {
+    let wrk = Workdir::new("matrix_corr_kendall");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["corr", "-m", "kendall", "-s", "a,b,c", "--long"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "target", "value"],
+        svec!["a", "b", "0.5477225575051661"],
+        svec!["a", "c", "-0.6666666666666666"],
+        svec!["b", "c", "-0.18257418583505536"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_cov() {
+    let wrk = Workdir::new("matrix_cov");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["cov", "-s", "a,c"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["", "a", "c"],
+        svec!["a", "1.6666666666666667", "-1.3333333333333333"],
+        svec!["c", "-1.3333333333333333", "1.6666666666666667"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_dist() {
+    let wrk = Workdir::new("matrix_dist");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["dist", "-i", "name", "-s", "name,a,b"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["", "x", "y", "z", "w"],
+        svec![
+            "x",
+            "0",
+            "2.23606797749979",
+            "3.605551275463989",
+            "3.605551275463989"
+        ],
+        svec!["y", "2.23606797749979", "0", "1.4142135623730951", "2"],
+        svec![
+            "z",
+            "3.605551275463989",
+            "1.4142135623730951",
+            "0",
+            "1.4142135623730951"
+        ],
+        svec!["w", "3.605551275463989", "2", "1.4142135623730951", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_dist_jaccard() {
+    let wrk = Workdir::new("matrix_dist_jaccard");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["dist", "--metric", "jaccard", "-s", "tags", "-L"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "target", "value"],
+        svec!["0", "1", "0.5"],
+        svec!["0", "2", "0.3333333333333333"],
+        svec!["0", "3", "0"],
+        svec!["1", "2", "0"],
+        svec!["1", "3", "0"],
+        svec!["2", "3", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_contingency() {
+    let wrk = Workdir::new("matrix_contingency");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["color", "size"],
+            svec!["red", "small"],
+            svec!["blue", "large"],
+            svec!["red", "small"],
+            svec!["red", "large"],
+            svec!["", "large"],
+        ],
+    );
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["contingency", "color", "size"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["", "small", "large"],
+        svec!["red", "2", "1"],
+        svec!["blue", "0", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["contingency", "color", "size", "--long"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "target", "value"],
+        svec!["red", "small", "2"],
+        svec!["red", "large", "1"],
+        svec!["blue", "large", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+fn pca_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "a", "b", "c"],
+        svec!["x", "2.5", "2.4", "1.0"],
+        svec!["y", "0.5", "0.7", "2.0"],
+        svec!["z", "2.2", "2.9", "1.5"],
+        svec!["w", "1.9", "2.2", "0.5"],
+        svec!["v", "3.1", "3.0", "2.5"],
+        svec!["u", "2.3", "2.7", "1.0"],
+    ]
+}
+
+#[test]
+fn matrix_pca() {
+    let wrk = Workdir::new("matrix_pca");
+    wrk.create("data.csv", pca_data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["pca", "-K", "name", "-k", "1"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "pc_1"],
+        svec!["x", "0.3665585280957094"],
+        svec!["y", "-2.2749447645632204"],
+        svec!["z", "0.487891467807499"],
+        svec!["w", "-0.191682608228011"],
+        svec!["v", "1.1802701970765694"],
+        svec!["u", "0.4319071798114544"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_pca_variance() {
+    let wrk = Workdir::new("matrix_pca_variance");
+    wrk.create("data.csv", pca_data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["pca", "-K", "name", "--variance"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "component",
+            "variance",
+            "explained_variance_ratio",
+            "cumulative_explained_variance_ratio"
+        ],
+        svec![
+            "pc_1",
+            "1.432820138613919",
+            "0.708965927072696",
+            "0.708965927072696"
+        ],
+        svec![
+            "pc_2",
+            "0.5457130043359223",
+            "0.2700212787411787",
+            "0.9789872058138747"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_pca_loadings() {
+    let wrk = Workdir::new("matrix_pca_loadings");
+    wrk.create("data.csv", pca_data());
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["pca", "-K", "name", "--loadings"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["column", "pc_1", "pc_2"],
+        svec!["a", "0.7175097833252515", "0.0824097732949723"],
+        svec!["b", "0.6961686946026506", "-0.05205977836405153"],
+        svec!["c", "-0.022996945183920984", "0.9952378654081432"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_mds() {
+    let wrk = Workdir::new("matrix_mds");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["", "a", "b", "c"],
+            svec!["a", "0", "3", "4"],
+            svec!["b", "3", "0", "5"],
+            svec!["c", "4", "5", "0"],
+        ],
+    );
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["mds"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    assert_eq!(got[0], svec!["name", "dim_1", "dim_2"]);
+
+    let points = got[1..]
+        .iter()
+        .map(|row| {
+            (
+                row[1].parse::<f64>().unwrap(),
+                row[2].parse::<f64>().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Distances should be preserved
+    let distance = |i: usize, j: usize| -> f64 {
+        ((points[i].0 - points[j].0).powi(2) + (points[i].1 - points[j].1).powi(2)).sqrt()
+    };
+
+    assert!((distance(0, 1) - 3.0).abs() < 1e-9);
+    assert!((distance(0, 2) - 4.0).abs() < 1e-9);
+    assert!((distance(1, 2) - 5.0).abs() < 1e-9);
+}
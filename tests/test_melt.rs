@@ -0,0 +1,102 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["country", "year_2019", "year_2020"],
+        svec!["France", "66", "67"],
+        svec!["Italy", "59", ""],
+    ]
+}
+
+#[test]
+fn melt() {
+    let wrk = Workdir::new("melt");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("melt");
+    cmd.arg("year_*").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "variable", "value"],
+        svec!["France", "year_2019", "66"],
+        svec!["France", "year_2020", "67"],
+        svec!["Italy", "year_2019", "59"],
+        svec!["Italy", "year_2020", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn melt_names_drop_empty() {
+    let wrk = Workdir::new("melt_names_drop_empty");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("melt");
+    cmd.args([
+        "year_2019:year_2020",
+        "-D",
+        "--variable-name",
+        "year",
+        "--value-name",
+        "population",
+    ])
+    .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "year", "population"],
+        svec!["France", "year_2019", "66"],
+        svec!["France", "year_2020", "67"],
+        svec!["Italy", "year_2019", "59"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn melt_id() {
+    let wrk = Workdir::new("melt_id");
+    wrk.create(
+        "data.csv",
+        vec![svec!["id", "name", "a", "b"], svec!["1", "john", "x", "y"]],
+    );
+
+    let mut cmd = wrk.command("melt");
+    cmd.args(["a,b", "-i", "name"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "variable", "value"],
+        svec!["john", "a", "x"],
+        svec!["john", "b", "y"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn melt_regex() {
+    let wrk = Workdir::new("melt_regex");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("melt");
+    cmd.args(["year_*", "-r", "(?<unit>\\w+)_(\\d+)"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "unit", "variable_2", "value"],
+        svec!["France", "year", "2019", "66"],
+        svec!["France", "year", "2020", "67"],
+        svec!["Italy", "year", "2019", "59"],
+        svec!["Italy", "year", "2020", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("melt");
+    cmd.args(["year_*", "-r", "month_(\\d+)"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("melt");
+    cmd.args(["year_*", "-r", "year_\\d+"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
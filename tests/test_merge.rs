@@ -258,3 +258,28 @@ fn merge_paths() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn merge_natural_collate() {
+    let wrk = Workdir::new("merge_natural_collate");
+    wrk.create(
+        "a.csv",
+        vec![svec!["name"], svec!["Émile 2"], svec!["emile 10"]],
+    );
+    wrk.create("b.csv", vec![svec!["name"], svec!["emile 9"], svec!["Zoé"]]);
+
+    let mut cmd = wrk.command("merge");
+    cmd.args(["--natural", "--collate", "fr"])
+        .arg("a.csv")
+        .arg("b.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name"],
+        svec!["Émile 2"],
+        svec!["emile 9"],
+        svec!["emile 10"],
+        svec!["Zoé"],
+    ];
+    assert_eq!(got, expected);
+}
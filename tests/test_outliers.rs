@@ -0,0 +1,192 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["cat", "price"],
+        svec!["a", "10"],
+        svec!["a", "12"],
+        svec!["a", "11"],
+        svec!["a", "13"],
+        svec!["a", "100"],
+        svec!["b", "1"],
+        svec!["b", "2"],
+        svec!["b", "2"],
+        svec!["b", "3"],
+        svec!["b", "-20"],
+        svec!["b", ""],
+    ]
+}
+
+#[test]
+fn outliers_iqr() {
+    let wrk = Workdir::new("outliers_iqr");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-g", "cat", "price"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["cat", "price", "price_score", "price_outlier"],
+        svec!["a", "10", "-0.5", "0"],
+        svec!["a", "12", "0", "0"],
+        svec!["a", "11", "0", "0"],
+        svec!["a", "13", "0", "0"],
+        svec!["a", "100", "43.5", "1"],
+        svec!["b", "1", "0", "0"],
+        svec!["b", "2", "0", "0"],
+        svec!["b", "2", "0", "0"],
+        svec!["b", "3", "1", "0"],
+        svec!["b", "-20", "-21", "1"],
+        svec!["b", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_iqr_stdin() {
+    let wrk = Workdir::new("outliers_iqr_stdin");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-g", "cat", "-f", "price"])
+        .stdin(std::fs::File::open(wrk.path("data.csv")).unwrap());
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["cat", "price", "price_score", "price_outlier"],
+        svec!["a", "100", "43.5", "1"],
+        svec!["b", "-20", "-21", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_zscore() {
+    let wrk = Workdir::new("outliers_zscore");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n"],
+            svec!["2"],
+            svec!["4"],
+            svec!["4"],
+            svec!["4"],
+            svec!["5"],
+            svec!["5"],
+            svec!["7"],
+            svec!["9"],
+        ],
+    );
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-m", "zscore", "-t", "1.5", "n"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "n_score", "n_outlier"],
+        svec!["2", "-1.5", "0"],
+        svec!["4", "-0.5", "0"],
+        svec!["4", "-0.5", "0"],
+        svec!["4", "-0.5", "0"],
+        svec!["5", "0", "0"],
+        svec!["5", "0", "0"],
+        svec!["7", "1", "0"],
+        svec!["9", "2", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_mad() {
+    let wrk = Workdir::new("outliers_mad");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-g", "cat", "-m", "mad", "-x", "price"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["cat", "price"],
+        svec!["a", "10"],
+        svec!["a", "12"],
+        svec!["a", "11"],
+        svec!["a", "13"],
+        svec!["b", "1"],
+        svec!["b", "2"],
+        svec!["b", "2"],
+        svec!["b", "3"],
+        svec!["b", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_percentile() {
+    let wrk = Workdir::new("outliers_percentile");
+    let mut rows = vec![svec!["n"]];
+    rows.extend((1..=10).map(|i| vec![i.to_string()]));
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args([
+        "-m",
+        "percentile",
+        "--lower",
+        "0.1",
+        "--upper",
+        "0.9",
+        "-f",
+        "n",
+    ])
+    .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["n", "n_outlier"], svec!["1", "1"], svec!["10", "1"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-m", "percentile", "--lower", "0.9", "--upper", "0.1", "n"])
+        .arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn outliers_approx() {
+    let wrk = Workdir::new("outliers_approx");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-a", "-g", "cat", "-f", "price"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = got
+        .into_iter()
+        .map(|row| vec![row[0].clone(), row[1].clone(), row[3].clone()])
+        .collect::<Vec<_>>();
+    let expected = vec![
+        svec!["cat", "price", "price_outlier"],
+        svec!["a", "100", "1"],
+        svec!["b", "-20", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-a", "-m", "mad", "price"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn outliers_invalid_method() {
+    let wrk = Workdir::new("outliers_invalid_method");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-m", "test", "price"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-f", "-x", "price"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
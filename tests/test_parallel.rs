@@ -110,6 +110,77 @@ fn parallel_freq_sep() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn parallel_freq_weight() {
+    let wrk = Workdir::new("parallel_freq_weight");
+    wrk.create(
+        "data1.csv",
+        vec![
+            svec!["color", "weight"],
+            svec!["blue", "1"],
+            svec!["yellow", "3.5"],
+        ],
+    );
+    wrk.create(
+        "data2.csv",
+        vec![
+            svec!["color", "weight"],
+            svec!["red", "2"],
+            svec!["blue", "2"],
+        ],
+    );
+
+    let mut cmd = wrk.command("parallel");
+    cmd.arg("freq")
+        .args(["-w", "weight"])
+        .arg("data1.csv")
+        .arg("data2.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["color", "yellow", "3.5"],
+        svec!["color", "blue", "3"],
+        svec!["color", "red", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn parallel_stats_weight() {
+    let wrk = Workdir::new("parallel_stats_weight");
+    wrk.create(
+        "data1.csv",
+        vec![svec!["n", "weight"], svec!["1", "2"], svec!["3", "1"]],
+    );
+    wrk.create("data2.csv", vec![svec!["n", "weight"], svec!["5", "1"]]);
+
+    let mut cmd = wrk.command("parallel");
+    cmd.arg("stats")
+        .args(["-w", "weight"])
+        .arg("data1.csv")
+        .arg("data2.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = got
+        .into_iter()
+        .map(|row| row[..7].to_vec())
+        .collect::<Vec<_>>();
+    let expected = vec![
+        svec![
+            "field",
+            "count",
+            "count_empty",
+            "type",
+            "types",
+            "sum",
+            "mean"
+        ],
+        svec!["n", "4", "0", "int", "int", "10", "2.5"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn parallel_cat() {
     let wrk = Workdir::new("parallel_cat");
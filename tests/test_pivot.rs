@@ -0,0 +1,96 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["country", "year", "population"],
+        svec!["France", "2020", "67"],
+        svec!["France", "2021", "68"],
+        svec!["Italy", "2020", "59"],
+        svec!["Italy", "2019", "58"],
+        svec!["France", "2020", "1"],
+    ]
+}
+
+#[test]
+fn pivot() {
+    let wrk = Workdir::new("pivot");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args(["year", "sum(population)", "-g", "country"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "2020", "2021", "2019"],
+        svec!["France", "68", "68", ""],
+        svec!["Italy", "59", "", "58"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_sort_headers_fill() {
+    let wrk = Workdir::new("pivot_sort_headers_fill");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args([
+        "year",
+        "count()",
+        "-g",
+        "country",
+        "--sort-headers",
+        "-F",
+        "0",
+    ])
+    .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "2019", "2020", "2021"],
+        svec!["France", "0", "2", "1"],
+        svec!["Italy", "1", "1", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_multiple_aggregations() {
+    let wrk = Workdir::new("pivot_multiple_aggregations");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args([
+        "year",
+        "min(population) as min, max(population) as max",
+        "-g",
+        "country",
+    ])
+    .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "2020_min", "2020_max", "2021_min", "2021_max", "2019_min", "2019_max"],
+        svec!["France", "1", "67", "68", "68", "", ""],
+        svec!["Italy", "59", "59", "", "", "58", "58"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_without_index() {
+    let wrk = Workdir::new("pivot_without_index");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args(["country", "sum(population)"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["France", "Italy"], svec!["136", "117"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("pivot");
+    cmd.args(["year", "count()", "-g", "year"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
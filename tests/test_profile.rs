@@ -0,0 +1,206 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "date", "score", "flag", "mail"],
+        svec!["AB-12", "01/02/2024", "1", "true", "john@example.com"],
+        svec!["CD-34", "13/02/2024", "2.5", "false", "mary@example.com"],
+        svec!["ef-56", "", "3", "yes", ""],
+        svec!["AB-12", "14/02/2024", "", "no", "lucy"],
+    ]
+}
+
+#[test]
+fn profile() {
+    let wrk = Workdir::new("profile");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("profile");
+    cmd.arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "field",
+            "type",
+            "types",
+            "date_format",
+            "count",
+            "count_empty",
+            "null_ratio",
+            "cardinality",
+            "distinctness",
+            "min_length",
+            "max_length",
+            "min",
+            "max",
+            "median",
+            "shapes",
+            "samples"
+        ],
+        svec![
+            "id",
+            "string",
+            "string",
+            "",
+            "4",
+            "0",
+            "0",
+            "3",
+            "0.75",
+            "5",
+            "5",
+            "",
+            "",
+            "",
+            "AA-99|aa-99",
+            "AB-12|CD-34|ef-56"
+        ],
+        svec![
+            "date",
+            "date",
+            "date",
+            "%d/%m/%Y",
+            "3",
+            "1",
+            "0.25",
+            "3",
+            "1",
+            "10",
+            "10",
+            "",
+            "",
+            "",
+            "99/99/9999",
+            "01/02/2024|13/02/2024|14/02/2024"
+        ],
+        svec![
+            "score",
+            "float",
+            "int|float",
+            "",
+            "3",
+            "1",
+            "0.25",
+            "3",
+            "1",
+            "1",
+            "3",
+            "1",
+            "3",
+            "2.5",
+            "9|9.9",
+            "1|2.5|3"
+        ],
+        svec![
+            "flag",
+            "boolean",
+            "boolean",
+            "",
+            "4",
+            "0",
+            "0",
+            "4",
+            "1",
+            "2",
+            "5",
+            "",
+            "",
+            "",
+            "aa|aaa|aaaa",
+            "true|false|yes"
+        ],
+        svec![
+            "mail",
+            "string",
+            "email|string",
+            "",
+            "3",
+            "1",
+            "0.25",
+            "3",
+            "1",
+            "4",
+            "16",
+            "",
+            "",
+            "",
+            "aaaa@aaaaaaa.aaa|aaaa",
+            "john@example.com|mary@example.com|lucy"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn profile_approx() {
+    let wrk = Workdir::new("profile_approx");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("profile");
+    cmd.args(["-a", "-s", "score,id"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = got
+        .into_iter()
+        .map(|row| vec![row[0].clone(), row[7].clone(), row[13].clone()])
+        .collect::<Vec<_>>();
+    let expected = vec![
+        svec!["field", "cardinality", "median"],
+        svec!["score", "3", "2.5"],
+        svec!["id", "3", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn profile_collapse() {
+    let wrk = Workdir::new("profile_collapse");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name"],
+            svec!["John Doe"],
+            svec!["Mary Sue"],
+            svec!["lucy"],
+        ],
+    );
+
+    let mut cmd = wrk.command("profile");
+    cmd.args(["--collapse", "--shapes", "1", "--samples", "1"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][14], "Aa Aa");
+    assert_eq!(got[1][15], "John Doe");
+}
+
+#[test]
+fn profile_sep() {
+    let wrk = Workdir::new("profile_sep");
+    wrk.create(
+        "data.csv",
+        vec![svec!["value"], svec!["a|b"], svec!["1"], svec!["c"]],
+    );
+
+    let mut cmd = wrk.command("profile");
+    cmd.args(["--sep", ";"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][2], "int;string");
+    assert_eq!(got[1][15], "a|b;1;c");
+}
+
+#[test]
+fn profile_report() {
+    let wrk = Workdir::new("profile_report");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("profile");
+    cmd.args(["-R", "-s", "date"]).arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.starts_with("date\n"));
+    assert!(got.contains("type         date (%d/%m/%Y)"));
+    assert!(got.contains("nulls        1 (25.00%)"));
+}
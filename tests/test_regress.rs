@@ -0,0 +1,196 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["y", "x", "city", "flag"],
+        svec!["10.1", "1", "paris", "0"],
+        svec!["12.3", "2", "lyon", "0"],
+        svec!["13.8", "3", "paris", "0"],
+        svec!["17.2", "4", "lyon", "1"],
+        svec!["18.1", "5", "marseille", "0"],
+        svec!["21.5", "6", "paris", "1"],
+        svec!["22.0", "7", "marseille", "1"],
+        svec!["25.9", "8", "lyon", "0"],
+        svec!["27.1", "9", "marseille", "1"],
+        svec!["30.4", "10", "paris", "1"],
+        svec!["", "11", "paris", "1"],
+    ]
+}
+
+// NOTE: rounding floats to avoid platform-dependent noise
+fn round(rows: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    rows.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|cell| match cell.parse::<f64>() {
+                    Ok(value) if cell.contains('.') => format!("{:.6}", value),
+                    _ => cell,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn headers() -> Vec<String> {
+    svec![
+        "term",
+        "coefficient",
+        "std_error",
+        "statistic",
+        "p_value",
+        "n",
+        "r_squared",
+        "adjusted_r_squared"
+    ]
+}
+
+#[test]
+fn regress() {
+    let wrk = Workdir::new("regress");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["-c", "city", "y", "x,city"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = round(wrk.read_stdout(&mut cmd));
+    let expected = vec![
+        headers(),
+        svec![
+            "(intercept)",
+            "7.557798",
+            "0.288879",
+            "26.162476",
+            "0.000000",
+            "10",
+            "0.997927",
+            "0.996890"
+        ],
+        svec![
+            "x",
+            "2.278440",
+            "0.043969",
+            "51.819770",
+            "0.000000",
+            "10",
+            "0.997927",
+            "0.996890"
+        ],
+        svec![
+            "city=lyon",
+            "0.276147",
+            "0.286640",
+            "0.963393",
+            "0.372555",
+            "10",
+            "0.997927",
+            "0.996890"
+        ],
+        svec![
+            "city=marseille",
+            "-1.106881",
+            "0.299467",
+            "-3.696168",
+            "0.010134",
+            "10",
+            "0.997927",
+            "0.996890"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn regress_logistic() {
+    let wrk = Workdir::new("regress_logistic");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["-l", "flag", "x"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = round(wrk.read_stdout(&mut cmd));
+    let expected = vec![
+        headers(),
+        svec![
+            "(intercept)",
+            "-3.108398",
+            "1.986401",
+            "-1.564839",
+            "0.117621",
+            "11",
+            "0.342057",
+            "0.078174"
+        ],
+        svec!["x", "0.569890", "0.325252", "1.752151", "0.079748", "11", "0.342057", "0.078174"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn regress_fitted() {
+    let wrk = Workdir::new("regress_fitted");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["-F", "y", "x"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = round(wrk.read_stdout(&mut cmd));
+
+    assert_eq!(
+        got[0],
+        svec!["y", "x", "city", "flag", "fitted", "residual"]
+    );
+    assert_eq!(
+        got[1],
+        svec!["10.100000", "1", "paris", "0", "9.896364", "0.203636"]
+    );
+    assert_eq!(
+        got[10],
+        svec!["30.400000", "10", "paris", "1", "29.783636", "0.616364"]
+    );
+    assert_eq!(got[11], svec!["", "11", "paris", "1", "", ""]);
+}
+
+#[test]
+fn regress_no_intercept() {
+    let wrk = Workdir::new("regress_no_intercept");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["y", "x"],
+            svec!["2", "1"],
+            svec!["4", "2"],
+            svec!["6", "3"],
+            svec!["9", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["--no-intercept", "y", "x"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = round(wrk.read_stdout(&mut cmd));
+
+    assert_eq!(got.len(), 2);
+    assert_eq!(got[1][0], "x");
+    assert_eq!(got[1][1], "2.133333");
+}
+
+#[test]
+fn regress_errors() {
+    let wrk = Workdir::new("regress_errors");
+    wrk.create("data.csv", data());
+
+    // Logistic target should be 0 or 1
+    let mut cmd = wrk.command("regress");
+    cmd.args(["-l", "y", "x"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    // Categorical columns should be predictors
+    let mut cmd = wrk.command("regress");
+    cmd.args(["-c", "city", "y", "x"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    // Non-numerical predictor
+    let mut cmd = wrk.command("regress");
+    cmd.args(["y", "city"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -415,3 +415,286 @@ fn sort_cells() {
     ];
     assert_eq!(got, expected);
 }
+
+fn cities() -> Vec<Vec<String>> {
+    vec![
+        svec!["country", "city", "population"],
+        svec!["France", "Lyon", "500000"],
+        svec!["Italy", "Rome", "2800000"],
+        svec!["France", "Paris", "2100000"],
+        svec!["Italy", "Turin", "870000"],
+        svec!["France", "Nice", "340000"],
+    ]
+}
+
+#[test]
+fn sort_by() {
+    let wrk = Workdir::new("sort_by");
+    wrk.create("in.csv", cities());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["-B", "country desc, population desc numeric"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "city", "population"],
+        svec!["Italy", "Rome", "2800000"],
+        svec!["Italy", "Turin", "870000"],
+        svec!["France", "Paris", "2100000"],
+        svec!["France", "Lyon", "500000"],
+        svec!["France", "Nice", "340000"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_by_parallel_external() {
+    let expected = vec![
+        svec!["country", "city", "population"],
+        svec!["France", "Nice", "340000"],
+        svec!["France", "Lyon", "500000"],
+        svec!["France", "Paris", "2100000"],
+        svec!["Italy", "Turin", "870000"],
+        svec!["Italy", "Rome", "2800000"],
+    ];
+
+    for flags in [vec!["-p"], vec!["-e"], vec!["-e", "-p"], vec!["-U", "-p"]] {
+        let wrk = Workdir::new("sort_by_parallel_external");
+        wrk.create("in.csv", cities());
+
+        let mut cmd = wrk.command("sort");
+        cmd.args(["-B", "country, population numeric"])
+            .args(flags)
+            .arg("in.csv");
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        assert_eq!(got, expected);
+    }
+}
+
+#[test]
+fn sort_by_reverse() {
+    let wrk = Workdir::new("sort_by_reverse");
+    wrk.create("in.csv", cities());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["-B", "country, population numeric"])
+        .arg("-R")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "city", "population"],
+        svec!["Italy", "Rome", "2800000"],
+        svec!["Italy", "Turin", "870000"],
+        svec!["France", "Paris", "2100000"],
+        svec!["France", "Lyon", "500000"],
+        svec!["France", "Nice", "340000"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_by_natural() {
+    let wrk = Workdir::new("sort_by_natural");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["version"],
+            svec!["v10.1"],
+            svec!["v2.10"],
+            svec!["v2.9"],
+            svec!["v1"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["-B", "version natural"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["version"],
+        svec!["v1"],
+        svec!["v2.9"],
+        svec!["v2.10"],
+        svec!["v10.1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_by_date() {
+    let wrk = Workdir::new("sort_by_date");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name", "date"],
+            svec!["a", "2024-03-01T09:00:00Z"],
+            svec!["b", ""],
+            svec!["c", "2024-03-01T09:00:00.5Z"],
+            svec!["d", "2023-12-25T00:00:00Z"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["-B", "date desc date"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "date"],
+        svec!["c", "2024-03-01T09:00:00.5Z"],
+        svec!["a", "2024-03-01T09:00:00Z"],
+        svec!["d", "2023-12-25T00:00:00Z"],
+        svec!["b", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_by_expr() {
+    let wrk = Workdir::new("sort_by_expr");
+    wrk.create("in.csv", cities());
+
+    let mut cmd = wrk.command("sort");
+    cmd.arg("--expr")
+        .args(["-B", "len(city) desc numeric, upper(city)"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "city", "population"],
+        svec!["France", "Paris", "2100000"],
+        svec!["Italy", "Turin", "870000"],
+        svec!["France", "Lyon", "500000"],
+        svec!["France", "Nice", "340000"],
+        svec!["Italy", "Rome", "2800000"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--expr", "-e", "-u", "-c", "count"])
+        .args(["-B", "len(city) numeric"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "city", "population", "count"],
+        svec!["France", "Lyon", "500000", "3"],
+        svec!["France", "Paris", "2100000", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_by_check() {
+    let wrk = Workdir::new("sort_by_check");
+    wrk.create("in.csv", cities());
+
+    let mut cmd = wrk.command("sort");
+    cmd.arg("--check").args(["-B", "country"]).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+
+    wrk.create(
+        "sorted.csv",
+        vec![
+            svec!["country", "population"],
+            svec!["France", "10"],
+            svec!["France", "9"],
+            svec!["Italy", "20"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.arg("--check")
+        .args(["-B", "country, population desc numeric"])
+        .arg("sorted.csv");
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn sort_natural() {
+    let wrk = Workdir::new("sort_natural");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["file"],
+            svec!["file10.txt"],
+            svec!["file9.txt"],
+            svec!["file1.txt"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.arg("--natural").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["file"],
+        svec!["file1.txt"],
+        svec!["file9.txt"],
+        svec!["file10.txt"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_natural_unique() {
+    let wrk = Workdir::new("sort_natural_unique");
+    wrk.create(
+        "in.csv",
+        vec![svec!["f"], svec!["file9"], svec!["file09"], svec!["file10"]],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["-u", "--natural", "-s", "f"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["f"], svec!["file09"], svec!["file9"], svec!["file10"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_collate() {
+    let wrk = Workdir::new("sort_collate");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["word", "n"],
+            svec!["zèbre", "1"],
+            svec!["Été", "2"],
+            svec!["eta", "3"],
+            svec!["abricot", "4"],
+            svec!["étage", "5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--collate", "fr"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["word", "n"],
+        svec!["abricot", "4"],
+        svec!["eta", "3"],
+        svec!["étage", "5"],
+        svec!["Été", "2"],
+        svec!["zèbre", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--collate", "fr", "-e", "-B", "word desc, n numeric"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["word", "n"],
+        svec!["zèbre", "1"],
+        svec!["Été", "2"],
+        svec!["étage", "5"],
+        svec!["eta", "3"],
+        svec!["abricot", "4"],
+    ];
+    assert_eq!(got, expected);
+}
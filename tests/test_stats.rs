@@ -242,3 +242,62 @@ mod stats_header_fields {
     stats_test_headers!(stats_header_field_name, "field", &["a"], "header");
     stats_test_no_headers!(stats_header_no_field_name, "field", &["a"], "0");
 }
+
+#[test]
+fn stats_weight() {
+    let wrk = Workdir::new("stats_weight");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["value", "weight"],
+            svec!["1", "2"],
+            svec!["3", "1"],
+            svec!["5", "1"],
+            svec!["", "0.5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["-q", "-w", "weight"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = got
+        .into_iter()
+        .map(|row| row[..12].to_vec())
+        .collect::<Vec<_>>();
+    let expected = vec![
+        svec![
+            "field",
+            "count",
+            "count_empty",
+            "type",
+            "types",
+            "sum",
+            "mean",
+            "q1",
+            "median",
+            "q3",
+            "variance",
+            "stddev"
+        ],
+        svec![
+            "value",
+            "4",
+            "0.5",
+            "int",
+            "int|empty",
+            "10",
+            "2.5",
+            "1",
+            "2",
+            "3.5",
+            "2.75",
+            "1.6583123951777"
+        ],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["-w", "value"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
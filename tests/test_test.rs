@@ -0,0 +1,178 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["group", "value", "answer"],
+        svec!["a", "1.2", "yes"],
+        svec!["a", "3.4", "no"],
+        svec!["a", "2.2", "yes"],
+        svec!["a", "5.1", "yes"],
+        svec!["a", "4.0", "no"],
+        svec!["b", "6.3", "no"],
+        svec!["b", "7.1", "no"],
+        svec!["b", "5.5", "yes"],
+        svec!["b", "8.0", "no"],
+        svec!["b", "6.6", "no"],
+        svec!["b", "4.0", "no"],
+        svec!["b", "", "no"],
+    ]
+}
+
+fn data_with_third_group() -> Vec<Vec<String>> {
+    let mut rows = data();
+
+    rows.extend([
+        svec!["c", "9.1", "yes"],
+        svec!["c", "8.8", "yes"],
+        svec!["c", "10.2", "no"],
+    ]);
+
+    rows
+}
+
+// NOTE: rounding floats to avoid platform-dependent noise
+fn round(rows: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    rows.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|cell| match cell.parse::<f64>() {
+                    Ok(value) if cell.contains('.') => format!("{:.6}", value),
+                    _ => cell,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn run_test(name: &str, rows: Vec<Vec<String>>, args: &[&str]) -> Vec<Vec<String>> {
+    let wrk = Workdir::new(name);
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("test");
+    cmd.args(args).arg("data.csv");
+
+    round(wrk.read_stdout(&mut cmd))
+}
+
+fn headers() -> Vec<String> {
+    svec![
+        "test",
+        "n",
+        "statistic",
+        "df",
+        "df2",
+        "p_value",
+        "effect_size"
+    ]
+}
+
+#[test]
+fn test_welch() {
+    let got = run_test("test_welch", data(), &["welch", "value", "group"]);
+    let expected = vec![
+        headers(),
+        svec![
+            "welch",
+            "11",
+            "-3.471125",
+            "8.255333",
+            "",
+            "0.008029",
+            "-2.122830"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn test_mann_whitney() {
+    let got = run_test(
+        "test_mann_whitney",
+        data(),
+        &["mann-whitney", "value", "group"],
+    );
+    let expected = vec![
+        headers(),
+        svec![
+            "mann-whitney",
+            "11",
+            "1.500000",
+            "",
+            "",
+            "0.017365",
+            "-0.900000"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn test_ks() {
+    let got = run_test("test_ks", data(), &["ks", "value", "group"]);
+    let expected = vec![
+        headers(),
+        svec!["ks", "11", "0.833333", "", "", "0.018331", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn test_anova() {
+    let got = run_test(
+        "test_anova",
+        data_with_third_group(),
+        &["anova", "value", "group"],
+    );
+    let expected = vec![
+        headers(),
+        svec![
+            "anova",
+            "14",
+            "20.357773",
+            "2",
+            "11",
+            "0.000201",
+            "0.787298"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn test_chi2() {
+    let got = run_test(
+        "test_chi2",
+        data_with_third_group(),
+        &["chi2", "answer", "group"],
+    );
+    let expected = vec![
+        headers(),
+        svec!["chi2", "15", "3.650794", "2", "", "0.161154", "0.493342"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn test_two_groups_error() {
+    let wrk = Workdir::new("test_two_groups_error");
+    wrk.create("data.csv", data_with_third_group());
+
+    let mut cmd = wrk.command("test");
+    cmd.args(["welch", "value", "group"]).arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn test_invalid_number_error() {
+    let wrk = Workdir::new("test_invalid_number_error");
+    wrk.create(
+        "data.csv",
+        vec![svec!["group", "value"], svec!["a", "1"], svec!["b", "nope"]],
+    );
+
+    let mut cmd = wrk.command("test");
+    cmd.args(["ks", "value", "group"]).arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
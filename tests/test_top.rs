@@ -205,3 +205,43 @@ fn top_ties() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn top_natural() {
+    let wrk = Workdir::new("top_natural");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["version"],
+            svec!["v1.9"],
+            svec!["v1.10"],
+            svec!["v1.2"],
+            svec![""],
+        ],
+    );
+
+    let mut cmd = wrk.command("top");
+    cmd.arg("version")
+        .arg("--natural")
+        .args(["-l", "2"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["version"], svec!["v1.10"], svec!["v1.9"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("top");
+    cmd.arg("version")
+        .arg("--natural")
+        .arg("-R")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["version"],
+        svec!["v1.2"],
+        svec!["v1.9"],
+        svec!["v1.10"],
+    ];
+    assert_eq!(got, expected);
+}
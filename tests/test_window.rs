@@ -0,0 +1,236 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["user", "date", "count"],
+        svec!["a", "2024-01-01", "1"],
+        svec!["b", "2024-01-01", "5"],
+        svec!["a", "2024-01-02", "3"],
+        svec!["a", "2024-01-02", "3"],
+        svec!["b", "2024-01-05", "2"],
+        svec!["a", "2024-01-04", "4"],
+    ]
+}
+
+#[test]
+fn window_row_number_and_rank() {
+    let wrk = Workdir::new("window_row_number_and_rank");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("row_number() as n, rank() as r, dense_rank() as dr")
+        .args(["-g", "user", "-O", "count numeric"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "n", "r", "dr"],
+        svec!["a", "2024-01-01", "1", "1", "1", "1"],
+        svec!["b", "2024-01-01", "5", "2", "2", "2"],
+        svec!["a", "2024-01-02", "3", "2", "2", "2"],
+        svec!["a", "2024-01-02", "3", "3", "2", "2"],
+        svec!["b", "2024-01-05", "2", "1", "1", "1"],
+        svec!["a", "2024-01-04", "4", "4", "4", "3"],
+    ];
+    assert_eq!(got, expected);
+
+    // Descending order
+    let mut cmd = wrk.command("window");
+    cmd.arg("rank() as r")
+        .args(["-O", "count desc numeric"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "r"],
+        svec!["a", "2024-01-01", "1", "6"],
+        svec!["b", "2024-01-01", "5", "1"],
+        svec!["a", "2024-01-02", "3", "3"],
+        svec!["a", "2024-01-02", "3", "3"],
+        svec!["b", "2024-01-05", "2", "5"],
+        svec!["a", "2024-01-04", "4", "2"],
+    ];
+    assert_eq!(got, expected);
+
+    // Ranks require an order
+    let mut cmd = wrk.command("window");
+    cmd.arg("rank()").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn window_order_by() {
+    let wrk = Workdir::new("window_order_by");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("lag(count) as prev, cumsum(count) as sum")
+        .args(["-O", "date date"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "prev", "sum"],
+        svec!["a", "2024-01-01", "1", "", "1"],
+        svec!["b", "2024-01-01", "5", "1", "6"],
+        svec!["a", "2024-01-02", "3", "5", "9"],
+        svec!["a", "2024-01-02", "3", "3", "12"],
+        svec!["b", "2024-01-05", "2", "4", "18"],
+        svec!["a", "2024-01-04", "4", "3", "16"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_lag_lead() {
+    let wrk = Workdir::new("window_lag_lead");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("lag(count) as prev, lead(count, 1, 'none') as next, lead(count, 2) as next2")
+        .args(["-g", "user"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "prev", "next", "next2"],
+        svec!["a", "2024-01-01", "1", "", "3", "3"],
+        svec!["b", "2024-01-01", "5", "", "2", ""],
+        svec!["a", "2024-01-02", "3", "1", "3", "4"],
+        svec!["a", "2024-01-02", "3", "3", "4", ""],
+        svec!["b", "2024-01-05", "2", "5", "none", ""],
+        svec!["a", "2024-01-04", "4", "3", "none", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_cumulative() {
+    let wrk = Workdir::new("window_cumulative");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("cumsum(count) as sum, cummin(count) as min, cummax(count) as max")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "sum", "min", "max"],
+        svec!["a", "2024-01-01", "1", "1", "1", "1"],
+        svec!["b", "2024-01-01", "5", "6", "1", "5"],
+        svec!["a", "2024-01-02", "3", "9", "1", "5"],
+        svec!["a", "2024-01-02", "3", "12", "1", "5"],
+        svec!["b", "2024-01-05", "2", "14", "1", "5"],
+        svec!["a", "2024-01-04", "4", "18", "1", "5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_rolling_rows() {
+    let wrk = Workdir::new("window_rolling_rows");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_mean(2, count) as mean, rolling_count(3, count) as count")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "mean", "count"],
+        svec!["a", "2024-01-01", "1", "1", "1"],
+        svec!["b", "2024-01-01", "5", "3", "2"],
+        svec!["a", "2024-01-02", "3", "4", "3"],
+        svec!["a", "2024-01-02", "3", "3", "3"],
+        svec!["b", "2024-01-05", "2", "2.5", "3"],
+        svec!["a", "2024-01-04", "4", "3", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_rolling_duration() {
+    let wrk = Workdir::new("window_rolling_duration");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_sum('2d', count) as sum")
+        .args(["-g", "user", "-t", "date"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "sum"],
+        svec!["a", "2024-01-01", "1", "1"],
+        svec!["b", "2024-01-01", "5", "5"],
+        svec!["a", "2024-01-02", "3", "4"],
+        svec!["a", "2024-01-02", "3", "7"],
+        svec!["b", "2024-01-05", "2", "2"],
+        svec!["a", "2024-01-04", "4", "4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_rolling_recomputed() {
+    let wrk = Workdir::new("window_rolling_recomputed");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_median(3, count) as median, rolling_argmax(2, count, user) as argmax, rolling_sum(2, count) if user eq 'a' as sum")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "median", "argmax", "sum"],
+        svec!["a", "2024-01-01", "1", "1", "a", "1"],
+        svec!["b", "2024-01-01", "5", "3", "b", "1"],
+        svec!["a", "2024-01-02", "3", "3", "b", "3"],
+        svec!["a", "2024-01-02", "3", "3", "a", "6"],
+        svec!["b", "2024-01-05", "2", "3", "a", "3"],
+        svec!["a", "2024-01-04", "4", "3", "a", "4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_rolling_duration_unsorted() {
+    let wrk = Workdir::new("window_rolling_duration_unsorted");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_sum('2d', count) as sum")
+        .args(["-t", "date"])
+        .arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_sum('2d', count) as sum")
+        .args(["-t", "date", "-O", "date date"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "date", "count", "sum"],
+        svec!["a", "2024-01-01", "1", "1"],
+        svec!["b", "2024-01-01", "5", "6"],
+        svec!["a", "2024-01-02", "3", "9"],
+        svec!["a", "2024-01-02", "3", "12"],
+        svec!["b", "2024-01-05", "2", "6"],
+        svec!["a", "2024-01-04", "4", "4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn window_rolling_duration_without_time() {
+    let wrk = Workdir::new("window_rolling_duration_without_time");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_sum('2d', count)").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
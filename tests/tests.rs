@@ -30,13 +30,20 @@ mod test_frequency;
 mod test_groupby;
 mod test_headers;
 mod test_implode;
+mod test_interval_join;
 mod test_join;
 mod test_map;
+mod test_matrix;
+mod test_melt;
 mod test_merge;
+mod test_outliers;
 mod test_parallel;
 mod test_partition;
+mod test_pivot;
+mod test_profile;
 mod test_range;
 mod test_regex_join;
+mod test_regress;
 mod test_rename;
 mod test_reverse;
 mod test_sample;
@@ -48,12 +55,14 @@ mod test_slice;
 mod test_sort;
 mod test_split;
 mod test_stats;
+mod test_test;
 mod test_to;
 mod test_tokenize;
 mod test_top;
 mod test_transform;
 mod test_url_join;
 mod test_vocab;
+mod test_window;
 
 pub type CsvVecs = Vec<Vec<String>>;
 